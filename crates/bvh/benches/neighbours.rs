@@ -0,0 +1,158 @@
+use std::hint::black_box;
+
+use bvh::{aabb::Aabb, create_random_elements_1, Bvh, Heuristic, TrivialHeuristic};
+use divan::Bencher;
+use glam::Vec3;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+fn main() {
+    divan::main();
+}
+
+const ENTITY_COUNTS: &[usize] = &[100, 1_000, 10_000];
+
+const QUERIES: usize = 1_000;
+const RADIUS: f32 = 8.0;
+const K: usize = 8;
+
+fn random_points(count: usize, width: f32) -> Vec<Vec3> {
+    (0..count)
+        .map(|_| Vec3::from_array(std::array::from_fn(|_| fastrand::f32() * width)))
+        .collect()
+}
+
+fn within_naive(elements: &[Aabb], center: Vec3, radius: f32) -> Vec<(&Aabb, f32)> {
+    elements
+        .iter()
+        .map(|elem| (elem, elem.dist2(center)))
+        .filter(|(_, dist2)| *dist2 <= radius * radius)
+        .collect()
+}
+
+fn k_nearest_naive(elements: &[Aabb], target: Vec3, k: usize) -> Vec<(&Aabb, f32)> {
+    let mut all: Vec<_> = elements
+        .iter()
+        .map(|elem| (elem, elem.dist2(target)))
+        .collect();
+
+    if all.len() > k {
+        all.select_nth_unstable_by(k, |(_, a), (_, b)| a.total_cmp(b));
+        all.truncate(k);
+    }
+
+    all.sort_unstable_by(|(_, a), (_, b)| a.total_cmp(b));
+    all
+}
+
+#[divan::bench(
+    args = ENTITY_COUNTS,
+    types = [TrivialHeuristic],
+)]
+fn within<H: Heuristic>(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let bvh = Bvh::build::<H>(elements);
+    let centers = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        for center in &centers {
+            bvh.get_within(*center, RADIUS, |elem, dist2| {
+                black_box((elem, dist2));
+                true
+            });
+        }
+    });
+}
+
+#[divan::bench(args = ENTITY_COUNTS)]
+fn within_brute_force(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let centers = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        for center in &centers {
+            black_box(within_naive(&elements, *center, RADIUS));
+        }
+    });
+}
+
+#[divan::bench(
+    args = ENTITY_COUNTS,
+    types = [TrivialHeuristic],
+)]
+fn within_batch<H: Heuristic>(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let bvh = Bvh::build::<H>(elements);
+    let centers = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES)
+        .bench_local(|| black_box(bvh.get_within_batch(&centers, RADIUS)));
+}
+
+#[divan::bench(args = ENTITY_COUNTS)]
+fn within_batch_brute_force(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let centers = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        let within: Vec<_> = centers
+            .par_iter()
+            .map(|center| within_naive(&elements, *center, RADIUS))
+            .collect();
+        black_box(within)
+    });
+}
+
+#[divan::bench(
+    args = ENTITY_COUNTS,
+    types = [TrivialHeuristic],
+)]
+fn k_nearest<H: Heuristic>(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let bvh = Bvh::build::<H>(elements);
+    let targets = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        for target in &targets {
+            black_box(bvh.get_k_nearest(*target, K));
+        }
+    });
+}
+
+#[divan::bench(args = ENTITY_COUNTS)]
+fn k_nearest_brute_force(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let targets = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        for target in &targets {
+            black_box(k_nearest_naive(&elements, *target, K));
+        }
+    });
+}
+
+#[divan::bench(
+    args = ENTITY_COUNTS,
+    types = [TrivialHeuristic],
+)]
+fn k_nearest_batch<H: Heuristic>(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let bvh = Bvh::build::<H>(elements);
+    let targets = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES)
+        .bench_local(|| black_box(bvh.get_k_nearest_batch(&targets, K)));
+}
+
+#[divan::bench(args = ENTITY_COUNTS)]
+fn k_nearest_batch_brute_force(b: Bencher, count: usize) {
+    let elements = create_random_elements_1(count, 100.0);
+    let targets = random_points(QUERIES, 100.0);
+
+    b.counter(QUERIES).bench_local(|| {
+        let nearest: Vec<_> = targets
+            .par_iter()
+            .map(|target| k_nearest_naive(&elements, *target, K))
+            .collect();
+        black_box(nearest)
+    });
+}
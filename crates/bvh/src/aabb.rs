@@ -158,6 +158,62 @@ impl Aabb {
         dist2
     }
 
+    /// The distance along `direction` at which a ray from `origin` enters the box, if it does so
+    /// within `max_distance`. A ray which starts inside the box enters it at `0.0`.
+    ///
+    /// The distance is measured in lengths of `direction`, so it is only a distance in blocks if
+    /// `direction` is normalized.
+    pub fn ray_intersection(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<f32> {
+        let mut enter = 0.0_f32;
+        let mut exit = max_distance;
+
+        for axis in 0..3 {
+            let (min, max) = (self.min[axis], self.max[axis]);
+            let (origin, direction) = (origin[axis], direction[axis]);
+
+            // empty boxes like `Aabb::NULL` are never hit
+            if min > max {
+                return None;
+            }
+
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let a = (min - origin) / direction;
+            let b = (max - origin) / direction;
+
+            enter = enter.max(a.min(b));
+            exit = exit.min(a.max(b));
+
+            if enter > exit {
+                return None;
+            }
+        }
+
+        Some(enter)
+    }
+
+    /// The fraction of `movement` after which the box touches `other` if the box is moved by
+    /// `movement`, if it does at all. Boxes which already overlap touch at `0.0`.
+    pub fn sweep(&self, movement: Vec3, other: &Self) -> Option<f32> {
+        // moving the box against `other` is the same as moving its middle against `other` grown
+        // by half of the box
+        let half = self.lens() / 2.0;
+        let grown = Self::new(other.min - half, other.max + half);
+
+        grown.ray_intersection(self.mid(), movement, 1.0)
+    }
+
     pub fn overlaps<'a, T>(
         &'a self,
         elements: impl Iterator<Item = &'a T>,
@@ -265,6 +321,43 @@ mod tests {
         assert_eq!(aabb.max, Vec3::new(2.0, 2.0, 2.0));
     }
 
+    #[test]
+    fn rays_enter_boxes_in_their_way() {
+        let aabb = Aabb::new(Vec3::new(2.0, 0.0, 0.0), Vec3::new(3.0, 1.0, 1.0));
+        let origin = Vec3::new(0.0, 0.5, 0.5);
+
+        assert_eq!(aabb.ray_intersection(origin, Vec3::X, 10.0), Some(2.0));
+        assert_eq!(aabb.ray_intersection(origin, Vec3::X, 1.0), None);
+        assert_eq!(aabb.ray_intersection(origin, Vec3::NEG_X, 10.0), None);
+        assert_eq!(aabb.ray_intersection(origin, Vec3::Y, 10.0), None);
+
+        // from the inside and along a face
+        assert_eq!(
+            aabb.ray_intersection(Vec3::new(2.5, 0.5, 0.5), Vec3::Y, 10.0),
+            Some(0.0)
+        );
+        assert_eq!(
+            aabb.ray_intersection(Vec3::new(0.0, 1.0, 0.5), Vec3::X, 10.0),
+            Some(2.0)
+        );
+
+        assert_eq!(Aabb::NULL.ray_intersection(origin, Vec3::ONE, 10.0), None);
+    }
+
+    #[test]
+    fn swept_boxes_stop_at_the_first_contact() {
+        let moving = Aabb::new(Vec3::ZERO, Vec3::ONE);
+        let wall = Aabb::new(Vec3::new(3.0, 0.5, 0.0), Vec3::new(4.0, 5.0, 1.0));
+
+        assert_eq!(moving.sweep(Vec3::new(4.0, 0.0, 0.0), &wall), Some(0.5));
+        assert_eq!(moving.sweep(Vec3::new(1.0, 0.0, 0.0), &wall), None);
+        assert_eq!(moving.sweep(Vec3::new(4.0, -2.0, 0.0), &wall), None);
+        assert_eq!(
+            moving.move_by(Vec3::X * 2.5).sweep(Vec3::Y, &wall),
+            Some(0.0)
+        );
+    }
+
     #[test]
     fn containing_returns_correct_aabb_for_multiple_aabbs() {
         let aabbs = vec![
@@ -11,6 +11,7 @@ use std::{
 
 use arrayvec::ArrayVec;
 use glam::Vec3;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::aabb::Aabb;
 
@@ -240,6 +241,221 @@ impl<T: HasAabb + Send + Copy + Sync + Debug> Bvh<T> {
     pub fn get_collisions(&self, target: Aabb, mut process: impl FnMut(&T) -> bool) {
         BvhIter::consume(self, target, &mut process);
     }
+
+    /// Returns the first element hit by a ray from `origin` in `direction` within `max_distance`
+    /// and the distance to it. `direction` does not have to be normalized.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<(&T, f32)> {
+        let direction = direction.normalize_or_zero();
+
+        self.nearest(|aabb| aabb.ray_intersection(origin, direction, max_distance))
+    }
+
+    /// Calls `process` with every element hit by a ray from `origin` in `direction` within
+    /// `max_distance` and the distance to it, in no particular order. Stops once `process`
+    /// returns `false`.
+    pub fn raycast_all(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        mut process: impl FnMut(&T, f32) -> bool,
+    ) {
+        let direction = direction.normalize_or_zero();
+
+        self.cast(
+            |aabb| aabb.ray_intersection(origin, direction, max_distance),
+            |elem, distance| process(elem, distance).then_some(f32::INFINITY),
+        );
+    }
+
+    /// Returns the first element `aabb` touches when it is moved by `movement` and the fraction
+    /// of `movement` after which it does.
+    pub fn sweep(&self, aabb: Aabb, movement: Vec3) -> Option<(&T, f32)> {
+        self.nearest(|other| aabb.sweep(movement, other))
+    }
+
+    /// Calls `process` with every element `aabb` touches when it is moved by `movement` and the
+    /// fraction of `movement` after which it does, in no particular order. Stops once `process`
+    /// returns `false`.
+    pub fn sweep_all(&self, aabb: Aabb, movement: Vec3, mut process: impl FnMut(&T, f32) -> bool) {
+        self.cast(
+            |other| aabb.sweep(movement, other),
+            |elem, fraction| process(elem, fraction).then_some(f32::INFINITY),
+        );
+    }
+
+    /// Calls `process` with every element within `radius` of `center` and the distance squared to
+    /// it, in no particular order. Stops once `process` returns `false`.
+    ///
+    /// Unlike [`Self::get_closest`], distances are measured to the boxes of elements rather than
+    /// to their midpoints, so elements containing `center` are at a distance of `0.0`.
+    pub fn get_within<'a>(
+        &'a self,
+        center: Vec3,
+        radius: f32,
+        mut process: impl FnMut(&'a T, f32) -> bool,
+    ) {
+        let radius2 = radius * radius;
+
+        self.cast(
+            |aabb| Some(aabb.dist2(center)).filter(|&dist2| dist2 <= radius2),
+            |elem, dist2| process(elem, dist2).then_some(f32::INFINITY),
+        );
+    }
+
+    /// Returns the `k` elements nearest to `target` and the distances squared to them, nearest
+    /// first. Distances are measured like in [`Self::get_within`].
+    pub fn get_k_nearest(&self, target: Vec3, k: usize) -> Vec<(&T, f32)> {
+        let mut nearest: Vec<(&T, f32)> = Vec::with_capacity(k);
+
+        if k == 0 {
+            return nearest;
+        }
+
+        self.cast(
+            // empty boxes are infinitely far away
+            |aabb| Some(aabb.dist2(target)).filter(|dist2| dist2.is_finite()),
+            |elem, dist2| {
+                if nearest.len() == k {
+                    if nearest[k - 1].1 <= dist2 {
+                        return Some(nearest[k - 1].1);
+                    }
+                    nearest.pop();
+                }
+
+                let idx = nearest.partition_point(|(_, other)| *other <= dist2);
+                nearest.insert(idx, (elem, dist2));
+
+                // once there are `k` elements, nothing farther than the last one can be nearer
+                Some(if nearest.len() == k {
+                    nearest[k - 1].1
+                } else {
+                    f32::INFINITY
+                })
+            },
+        );
+
+        nearest
+    }
+
+    /// Like [`Self::get_within`] for many centers at once, in parallel. Returns the elements and
+    /// distances squared for each center in the same order as `centers`.
+    pub fn get_within_batch(&self, centers: &[Vec3], radius: f32) -> Vec<Vec<(&T, f32)>> {
+        centers
+            .par_iter()
+            .map(|&center| {
+                let mut within = Vec::new();
+                self.get_within(center, radius, |elem, dist2| {
+                    within.push((elem, dist2));
+                    true
+                });
+                within
+            })
+            .collect()
+    }
+
+    /// Like [`Self::get_k_nearest`] for many targets at once, in parallel. Returns the nearest
+    /// elements for each target in the same order as `targets`.
+    pub fn get_k_nearest_batch(&self, targets: &[Vec3], k: usize) -> Vec<Vec<(&T, f32)>> {
+        targets
+            .par_iter()
+            .map(|&target| self.get_k_nearest(target, k))
+            .collect()
+    }
+
+    /// The element with the smallest distance according to `intersect`.
+    fn nearest(&self, intersect: impl Fn(&Aabb) -> Option<f32>) -> Option<(&T, f32)> {
+        let mut nearest = None;
+
+        self.cast(intersect, |elem, distance| {
+            if !nearest.is_some_and(|(_, nearest)| nearest <= distance) {
+                nearest = Some((elem, distance));
+            }
+
+            // nothing farther away can be nearer
+            Some(nearest.map_or(f32::INFINITY, |(_, nearest)| nearest))
+        });
+
+        nearest
+    }
+
+    /// Visits the elements for which `intersect` returns a distance, nearer nodes first.
+    ///
+    /// `process` returns the distance up to which to keep looking, so nodes which are farther away
+    /// are skipped, or `None` to stop.
+    fn cast<'a>(
+        &'a self,
+        intersect: impl Fn(&Aabb) -> Option<f32>,
+        mut process: impl FnMut(&'a T, f32) -> Option<f32>,
+    ) {
+        let root = match self.root() {
+            Node::Internal(internal) => internal,
+            Node::Leaf(leaf) => {
+                for elem in leaf {
+                    if let Some(distance) = intersect(&elem.aabb()) {
+                        if process(elem, distance).is_none() {
+                            return;
+                        }
+                    }
+                }
+                return;
+            }
+        };
+
+        let Some(distance) = intersect(&root.aabb) else {
+            return;
+        };
+
+        let mut limit = f32::INFINITY;
+        let mut stopped = false;
+
+        let mut stack: ArrayVec<(&BvhNode, f32), 64> = ArrayVec::new();
+        stack.push((root, distance));
+
+        while let Some((on, distance)) = stack.pop() {
+            if distance > limit {
+                continue;
+            }
+
+            let mut children: ArrayVec<(&BvhNode, f32), 2> = ArrayVec::new();
+
+            on.switch_children(
+                self,
+                |child| {
+                    if let Some(distance) = intersect(&child.aabb) {
+                        children.push((child, distance));
+                    }
+                },
+                |elements| {
+                    for elem in elements {
+                        let Some(distance) = intersect(&elem.aabb()) else {
+                            continue;
+                        };
+
+                        if distance > limit {
+                            continue;
+                        }
+
+                        match process(elem, distance) {
+                            Some(new_limit) => limit = new_limit,
+                            None => {
+                                stopped = true;
+                                return;
+                            }
+                        }
+                    }
+                },
+            );
+
+            if stopped {
+                return;
+            }
+
+            // the nearer child is pushed last so it is visited first
+            children.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+            stack.extend(children);
+        }
+    }
 }
 
 impl<T> Bvh<T> {
@@ -181,3 +181,231 @@ fn get_closest_returns_none_when_no_elements() {
 
     assert!(closest.is_none());
 }
+
+fn random_point(width: f32) -> Vec3 {
+    Vec3::from_array(std::array::from_fn(|_| fastrand::f32() * width))
+}
+
+fn random_direction() -> Vec3 {
+    let direction = Vec3::from_array(std::array::from_fn(|_| fastrand::f32().mul_add(2.0, -1.0)));
+    direction.normalize_or_zero()
+}
+
+fn raycast_naive(elements: &[Aabb], origin: Vec3, direction: Vec3, max: f32) -> Option<f32> {
+    // normalized like `Bvh::raycast` does so the distances are exactly the same
+    let direction = direction.normalize_or_zero();
+
+    elements
+        .iter()
+        .filter_map(|elem| elem.ray_intersection(origin, direction, max))
+        .min_by(f32::total_cmp)
+}
+
+#[test]
+fn raycast_returns_nearest_hit() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements.clone());
+
+    for _ in 0..1_000 {
+        let origin = random_point(100.0);
+        let direction = random_direction();
+
+        let naive = raycast_naive(&elements, origin, direction, 50.0);
+        let hit = bvh.raycast(origin, direction, 50.0);
+
+        assert_eq!(hit.map(|(_, distance)| distance), naive);
+
+        if let Some((elem, distance)) = hit {
+            assert_eq!(
+                raycast_naive(&[*elem], origin, direction, 50.0),
+                Some(distance)
+            );
+        }
+    }
+}
+
+#[test]
+fn raycast_all_returns_every_hit() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements.clone());
+
+    for _ in 0..100 {
+        let origin = random_point(100.0);
+        let direction = random_direction();
+
+        let naive: HashSet<CheckableAabb> = elements
+            .iter()
+            .filter(|elem| elem.ray_intersection(origin, direction, 50.0).is_some())
+            .map(|elem| CheckableAabb::try_from(*elem).unwrap())
+            .collect();
+
+        let mut hits = Vec::new();
+        bvh.raycast_all(origin, direction, 50.0, |elem, distance| {
+            assert!(distance <= 50.0);
+            hits.push(CheckableAabb::try_from(*elem).unwrap());
+            true
+        });
+
+        assert_eq!(hits.len(), naive.len());
+        assert_eq!(hits.into_iter().collect::<HashSet<_>>(), naive);
+    }
+}
+
+#[test]
+fn raycast_all_stops_when_asked() {
+    let elements = create_random_elements_1(10_000, 10.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements);
+
+    let mut hits = 0;
+    bvh.raycast_all(Vec3::ZERO, Vec3::ONE, 100.0, |_, _| {
+        hits += 1;
+        hits < 3
+    });
+
+    assert_eq!(hits, 3);
+}
+
+#[test]
+fn sweep_returns_first_contact() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements.clone());
+
+    for _ in 0..1_000 {
+        let aabb = Aabb::create(random_point(100.0), 0.6, 1.8);
+        let movement = random_direction() * 20.0;
+
+        let naive = elements
+            .iter()
+            .filter_map(|elem| aabb.sweep(movement, elem))
+            .min_by(f32::total_cmp);
+        let hit = bvh.sweep(aabb, movement);
+
+        assert_eq!(hit.map(|(_, fraction)| fraction), naive);
+
+        let naive_all = elements
+            .iter()
+            .filter(|elem| aabb.sweep(movement, elem).is_some())
+            .count();
+
+        let mut hits = 0;
+        bvh.sweep_all(aabb, movement, |elem, fraction| {
+            assert_eq!(aabb.sweep(movement, elem), Some(fraction));
+            hits += 1;
+            true
+        });
+
+        assert_eq!(hits, naive_all);
+    }
+}
+
+#[test]
+fn raycast_misses_without_elements() {
+    let bvh = Bvh::build::<TrivialHeuristic>(Vec::<Aabb>::new());
+    assert!(bvh.raycast(Vec3::ZERO, Vec3::X, 100.0).is_none());
+
+    let bvh = Bvh::<Aabb>::default();
+    assert!(bvh.raycast(Vec3::ZERO, Vec3::ONE, 100.0).is_none());
+    assert!(bvh
+        .sweep(Aabb::new(Vec3::ZERO, Vec3::ONE), Vec3::ONE)
+        .is_none());
+}
+
+fn dist2_naive(elements: &[Aabb], target: Vec3) -> Vec<f32> {
+    let mut dist2: Vec<f32> = elements.iter().map(|elem| elem.dist2(target)).collect();
+    dist2.sort_unstable_by(f32::total_cmp);
+    dist2
+}
+
+#[test]
+fn get_within_returns_every_element_in_radius() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements.clone());
+
+    for _ in 0..100 {
+        let center = random_point(100.0);
+        let radius = fastrand::f32() * 10.0;
+
+        let naive: HashSet<CheckableAabb> = elements
+            .iter()
+            .filter(|elem| elem.dist2(center) <= radius * radius)
+            .map(|elem| CheckableAabb::try_from(*elem).unwrap())
+            .collect();
+
+        let mut within = Vec::new();
+        bvh.get_within(center, radius, |elem, dist2| {
+            assert_eq!(elem.dist2(center), dist2);
+            within.push(CheckableAabb::try_from(*elem).unwrap());
+            true
+        });
+
+        assert_eq!(within.len(), naive.len());
+        assert_eq!(within.into_iter().collect::<HashSet<_>>(), naive);
+    }
+}
+
+#[test]
+fn get_k_nearest_returns_nearest_first() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements.clone());
+
+    for k in [1, 5, 50] {
+        for _ in 0..100 {
+            let target = random_point(100.0);
+
+            let naive = dist2_naive(&elements, target);
+            let nearest: Vec<f32> = bvh
+                .get_k_nearest(target, k)
+                .into_iter()
+                .map(|(elem, dist2)| {
+                    assert_eq!(elem.dist2(target), dist2);
+                    dist2
+                })
+                .collect();
+
+            assert_eq!(nearest, naive[..k]);
+        }
+    }
+}
+
+#[test]
+fn get_k_nearest_returns_everything_when_k_is_large() {
+    let elements = create_random_elements_1(10, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements);
+
+    let target = random_point(100.0);
+
+    assert_eq!(bvh.get_k_nearest(target, 100).len(), 10);
+    assert!(bvh.get_k_nearest(target, 0).is_empty());
+    assert!(Bvh::<Aabb>::default().get_k_nearest(target, 3).is_empty());
+}
+
+#[test]
+fn batches_match_single_queries() {
+    let elements = create_random_elements_1(10_000, 100.0);
+    let bvh = Bvh::build::<TrivialHeuristic>(elements);
+
+    let targets: Vec<Vec3> = (0..100).map(|_| random_point(100.0)).collect();
+
+    let nearest = bvh.get_k_nearest_batch(&targets, 8);
+    let within = bvh.get_within_batch(&targets, 5.0);
+
+    assert_eq!(nearest.len(), targets.len());
+    assert_eq!(within.len(), targets.len());
+
+    for ((target, nearest), within) in targets.iter().zip(nearest).zip(within) {
+        let single: Vec<f32> = bvh
+            .get_k_nearest(*target, 8)
+            .into_iter()
+            .map(|(_, dist2)| dist2)
+            .collect();
+        let batch: Vec<f32> = nearest.into_iter().map(|(_, dist2)| dist2).collect();
+        assert_eq!(batch, single);
+
+        let mut count = 0;
+        bvh.get_within(*target, 5.0, |_, _| {
+            count += 1;
+            true
+        });
+        assert_eq!(within.len(), count);
+    }
+}
@@ -0,0 +1,401 @@
+//! The combat audit log, which is evidence of who fought whom.
+//!
+//! If the [`crate::config::Config`] names a file, the server appends a [`Record`] of every join,
+//! leave, hit, kill and phase change to it as one JSON object per line. The file is never
+//! truncated, so each start of the server adds a [`Record::Start`]. A [`Summary`] adds up a log,
+//! which the `audit_summary` binary prints.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::BufRead,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    components::{damage::DamageCause, mob_kind::MobKind},
+    singleton::phase::Phase,
+};
+
+/// The player or mob in a [`Record`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Actor {
+    /// A player by their UUID.
+    Player(Uuid),
+    /// A mob by its name, i.e., `zombie`.
+    Mob(String),
+}
+
+impl Actor {
+    /// The actor of an entity with the given UUID and mob kind. Mobs have a UUID as well, so only
+    /// entities without a kind are players.
+    pub fn new(uuid: Option<Uuid>, kind: Option<MobKind>) -> Option<Self> {
+        match (uuid, kind) {
+            (_, Some(kind)) => Some(Self::Mob(kind.name().to_owned())),
+            (Some(uuid), None) => Some(Self::Player(uuid)),
+            (None, None) => None,
+        }
+    }
+
+    /// The UUID if the actor is a player.
+    pub const fn player(&self) -> Option<Uuid> {
+        match self {
+            Self::Player(uuid) => Some(*uuid),
+            Self::Mob(_) => None,
+        }
+    }
+}
+
+/// An entry of the audit log. Every entry except [`Record::Start`] has the tick it happened on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    /// The server started and ticks count from zero again.
+    Start {
+        /// The seconds since the Unix epoch.
+        time: u64,
+    },
+    /// A player joined.
+    Join {
+        /// The tick of the join.
+        tick: i64,
+        /// The UUID of the player.
+        player: Uuid,
+        /// The name of the player.
+        name: String,
+    },
+    /// A player left.
+    Leave {
+        /// The tick of the leave.
+        tick: i64,
+        /// The UUID of the player.
+        player: Uuid,
+    },
+    /// An entity hit another one.
+    Hit {
+        /// The tick of the hit.
+        tick: i64,
+        /// The entity which hit, if any.
+        attacker: Option<Actor>,
+        /// The entity which was hit.
+        victim: Actor,
+        /// The damage before armor in half hearts.
+        damage: f32,
+        /// Where the attack came from.
+        attacker_pos: [f32; 3],
+        /// Where the victim was.
+        victim_pos: [f32; 3],
+    },
+    /// A player died.
+    Kill {
+        /// The tick of the death.
+        tick: i64,
+        /// The entity credited with the kill, if any.
+        killer: Option<Actor>,
+        /// The UUID of the player who died.
+        victim: Uuid,
+        /// The damage type of the last damage, i.e., `minecraft:player_attack`.
+        cause: String,
+        /// Where the victim died.
+        victim_pos: [f32; 3],
+    },
+    /// The match switched to another phase.
+    Phase {
+        /// The tick of the switch.
+        tick: i64,
+        /// The previous phase.
+        from: Phase,
+        /// The new phase.
+        to: Phase,
+    },
+}
+
+impl Record {
+    /// A kill of `victim` with the last damage dealt by `cause`.
+    pub fn kill(
+        tick: i64,
+        killer: Option<Actor>,
+        victim: Uuid,
+        cause: DamageCause,
+        victim_pos: [f32; 3],
+    ) -> Self {
+        Self::Kill {
+            tick,
+            killer,
+            victim,
+            cause: cause.damage_type().to_owned(),
+            victim_pos,
+        }
+    }
+}
+
+/// What a player did according to the audit log.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct Participation {
+    /// The name the player last joined with.
+    pub name: String,
+    /// The number of times the player joined.
+    pub joins: usize,
+    /// The number of hits on other players.
+    pub hits_dealt: usize,
+    /// The number of hits by other players.
+    pub hits_taken: usize,
+    /// The damage dealt to other players in half hearts.
+    pub damage_dealt: f64,
+    /// The number of players killed.
+    pub kills: usize,
+    /// The number of deaths from any cause.
+    pub deaths: usize,
+}
+
+impl Participation {
+    /// Whether the player fought another player.
+    pub const fn fought(&self) -> bool {
+        self.hits_dealt > 0 || self.hits_taken > 0
+    }
+}
+
+/// The totals of an audit log, see [`crate::audit`].
+#[derive(Serialize, Debug, Default)]
+pub struct Summary {
+    /// The number of times the server started.
+    pub starts: usize,
+    /// The most players online at the same time.
+    pub peak_players: usize,
+    /// The number of hits of players on players.
+    pub pvp_hits: usize,
+    /// The number of players killed by players.
+    pub pvp_kills: usize,
+    /// The number of deaths from any cause.
+    pub deaths: usize,
+    /// The number of battles which started.
+    pub battles: usize,
+    /// What each player did by their UUID.
+    pub players: BTreeMap<Uuid, Participation>,
+    /// The players who are online.
+    #[serde(skip)]
+    online: BTreeSet<Uuid>,
+}
+
+impl Summary {
+    /// Reads a log with one [`Record`] per line. A crash of the server can cut off the last line,
+    /// so it is skipped if it is invalid.
+    pub fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut summary = Self::default();
+        let mut lines = reader.lines().enumerate().peekable();
+
+        while let Some((index, line)) = lines.next() {
+            let line = line.context("failed to read the audit log")?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&line) {
+                Ok(record) => summary.add(&record),
+                Err(_) if lines.peek().is_none() => break,
+                Err(error) => {
+                    return Err(error)
+                        .with_context(|| format!("invalid record on line {}", index + 1));
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Adds a record to the totals.
+    pub fn add(&mut self, record: &Record) {
+        match record {
+            Record::Start { .. } => {
+                // players do not stay online across restarts
+                self.starts += 1;
+                self.online.clear();
+            }
+            Record::Join { player, name, .. } => {
+                let participation = self.players.entry(*player).or_default();
+                participation.name.clone_from(name);
+                participation.joins += 1;
+
+                self.online.insert(*player);
+                self.peak_players = self.peak_players.max(self.online.len());
+            }
+            Record::Leave { player, .. } => {
+                self.online.remove(player);
+            }
+            Record::Hit {
+                attacker,
+                victim,
+                damage,
+                ..
+            } => {
+                let (Some(attacker), Some(victim)) =
+                    (attacker.as_ref().and_then(Actor::player), victim.player())
+                else {
+                    return;
+                };
+
+                self.pvp_hits += 1;
+
+                let participation = self.players.entry(attacker).or_default();
+                participation.hits_dealt += 1;
+                participation.damage_dealt += f64::from(*damage);
+
+                self.players.entry(victim).or_default().hits_taken += 1;
+            }
+            Record::Kill { killer, victim, .. } => {
+                self.deaths += 1;
+                self.players.entry(*victim).or_default().deaths += 1;
+
+                let Some(killer) = killer.as_ref().and_then(Actor::player) else {
+                    return;
+                };
+
+                if killer != *victim {
+                    self.pvp_kills += 1;
+                    self.players.entry(killer).or_default().kills += 1;
+                }
+            }
+            Record::Phase { to, .. } => {
+                if *to == Phase::Battle {
+                    self.battles += 1;
+                }
+            }
+        }
+    }
+
+    /// The number of players who fought another player.
+    pub fn participants(&self) -> usize {
+        self.players
+            .values()
+            .filter(|participation| participation.fought())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: Uuid = Uuid::from_u128(1);
+    const BOB: Uuid = Uuid::from_u128(2);
+    const CAROL: Uuid = Uuid::from_u128(3);
+
+    fn join(tick: i64, player: Uuid, name: &str) -> Record {
+        Record::Join {
+            tick,
+            player,
+            name: name.to_owned(),
+        }
+    }
+
+    const fn leave(tick: i64, player: Uuid) -> Record {
+        Record::Leave { tick, player }
+    }
+
+    fn hit(tick: i64, attacker: Actor, victim: Actor) -> Record {
+        Record::Hit {
+            tick,
+            attacker: Some(attacker),
+            victim,
+            damage: 4.0,
+            attacker_pos: [0.0; 3],
+            victim_pos: [1.0, 0.0, 0.0],
+        }
+    }
+
+    #[test]
+    fn records_are_one_line_of_json() {
+        let record = hit(5, Actor::Player(ALICE), Actor::Mob("zombie".to_owned()));
+
+        let line = serde_json::to_string(&record).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(line.starts_with(r#"{"type":"hit""#));
+
+        assert_eq!(serde_json::from_str::<Record>(&line).unwrap(), record);
+    }
+
+    #[test]
+    fn summaries_count_pvp() {
+        let records = [
+            Record::Start { time: 0 },
+            join(0, ALICE, "alice"),
+            join(1, BOB, "bob"),
+            hit(2, Actor::Player(ALICE), Actor::Player(BOB)),
+            hit(3, Actor::Player(ALICE), Actor::Player(BOB)),
+            // mobs are not pvp
+            hit(4, Actor::Mob("zombie".to_owned()), Actor::Player(ALICE)),
+            Record::kill(
+                5,
+                Some(Actor::Player(ALICE)),
+                BOB,
+                DamageCause::PlayerAttack,
+                [0.0; 3],
+            ),
+            Record::Leave {
+                tick: 6,
+                player: BOB,
+            },
+            // a restart forgets who was online
+            Record::Start { time: 10 },
+            join(0, BOB, "bobby"),
+        ];
+
+        let mut summary = Summary::default();
+        records.iter().for_each(|record| summary.add(record));
+
+        assert_eq!(summary.starts, 2);
+        assert_eq!(summary.peak_players, 2);
+        assert_eq!(summary.pvp_hits, 2);
+        assert_eq!(summary.pvp_kills, 1);
+        assert_eq!(summary.participants(), 2);
+
+        let alice = &summary.players[&ALICE];
+        assert_eq!((alice.hits_dealt, alice.hits_taken, alice.kills), (2, 0, 1));
+        assert!((alice.damage_dealt - 8.0).abs() < f64::EPSILON);
+
+        let bob = &summary.players[&BOB];
+        assert_eq!(bob.name, "bobby");
+        assert_eq!((bob.joins, bob.hits_taken, bob.deaths), (2, 2, 1));
+    }
+
+    #[test]
+    fn peak_players_are_the_most_online_at_once() {
+        let records = [
+            Record::Start { time: 0 },
+            join(0, ALICE, "alice"),
+            join(1, BOB, "bob"),
+            leave(2, ALICE),
+            join(3, CAROL, "carol"),
+            leave(4, BOB),
+            join(5, ALICE, "alice"),
+            leave(6, CAROL),
+            join(7, BOB, "bob"),
+            leave(8, ALICE),
+            leave(9, BOB),
+        ];
+
+        let mut summary = Summary::default();
+        records.iter().for_each(|record| summary.add(record));
+
+        // never more than two players were online, although three joined
+        assert_eq!(summary.peak_players, 2);
+        assert_eq!(summary.players.len(), 3);
+        assert_eq!(summary.players[&ALICE].joins, 2);
+    }
+
+    #[test]
+    fn logs_are_read_line_by_line() {
+        let log = "{\"type\":\"start\",\"time\":0}\n\n{\"type\":\"leave\",\"tick\":1,\"player\":\"00000000-0000-0000-0000-000000000001\"}\n";
+        assert_eq!(Summary::read(log.as_bytes()).unwrap().starts, 1);
+
+        // only the last line can be cut off
+        let log = "{\"type\":\"start\",\"time\":0}\n{\"type\":\"sta";
+        assert_eq!(Summary::read(log.as_bytes()).unwrap().starts, 1);
+
+        let log = "{\"type\":\"lap\"}\n{\"type\":\"start\",\"time\":0}\n";
+        assert!(Summary::read(log.as_bytes()).is_err());
+    }
+}
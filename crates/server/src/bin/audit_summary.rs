@@ -0,0 +1,62 @@
+//! Summarizes a combat audit log written by the server, see `server::audit`.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use anyhow::Context;
+use clap::Parser;
+use server::audit::Summary;
+
+/// The arguments to summarize an audit log
+#[derive(Parser)]
+struct Args {
+    /// The audit log, as configured with `audit_log` in the server configuration
+    path: PathBuf,
+    /// Print the summary as JSON instead of a table
+    #[clap(long)]
+    json: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let Args { path, json } = Args::parse();
+
+    let file = File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+    let summary = Summary::read(BufReader::new(file))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    println!("server starts:         {}", summary.starts);
+    println!("battles:               {}", summary.battles);
+    println!("peak concurrent:       {}", summary.peak_players);
+    println!("players seen:          {}", summary.players.len());
+    println!("players who fought:    {}", summary.participants());
+    println!("pvp interactions:      {}", summary.pvp_hits);
+    println!("pvp kills:             {}", summary.pvp_kills);
+    println!("deaths:                {}", summary.deaths);
+    println!();
+
+    let mut players: Vec<_> = summary.players.iter().collect();
+    players.sort_by(|(_, a), (_, b)| b.hits_dealt.cmp(&a.hits_dealt).then(a.name.cmp(&b.name)));
+
+    println!(
+        "{:<36}  {:<16}  {:>5}  {:>6}  {:>6}  {:>8}  {:>5}  {:>6}",
+        "uuid", "name", "joins", "dealt", "taken", "damage", "kills", "deaths"
+    );
+
+    for (uuid, player) in players {
+        println!(
+            "{uuid:<36}  {:<16}  {:>5}  {:>6}  {:>6}  {:>8.1}  {:>5}  {:>6}",
+            player.name,
+            player.joins,
+            player.hits_dealt,
+            player.hits_taken,
+            player.damage_dealt,
+            player.kills,
+            player.deaths,
+        );
+    }
+
+    Ok(())
+}
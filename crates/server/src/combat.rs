@@ -0,0 +1,708 @@
+//! The PvP combat model.
+//!
+//! An [`Attack`] describes the weapon an attacker is holding and how they are moving. The
+//! [`Rules`] of the [`Ruleset`] chosen in the config turn it into a [`Hit`]. [`Ruleset::Modern`]
+//! is the combat of 1.9 and later with an attack cooldown and sweeping, [`Ruleset::Legacy`] is the
+//! 1.8 combat without a cooldown.
+//!
+//! The damage of a hit is then reduced by the [`Defense`] of the armor the target is wearing, see
+//! [`Rules::reduce`].
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use valence_protocol::{
+    nbt::{List, Value},
+    ItemKind, ItemStack,
+};
+
+/// Attacks with a charge above this count as fully charged.
+const FULL_CHARGE: f32 = 0.9;
+
+/// The knockback every hit applies, pushing the target away from the attacker.
+pub const BASE_KNOCKBACK: f32 = 0.4;
+
+/// The damage of a sweep, measured in half hearts.
+pub const SWEEP_DAMAGE: f32 = 1.0;
+
+/// The knockback of a sweep in the direction the attacker is looking.
+pub const SWEEP_KNOCKBACK: f32 = 0.4;
+
+/// How far the attacker may be from an entity for a sweep to hit it.
+pub const SWEEP_REACH: f32 = 3.0;
+
+/// How much the bounding box of the target is grown horizontally to find entities hit by a
+/// sweep.
+pub const SWEEP_HORIZONTAL: f32 = 1.0;
+
+/// How much the bounding box of the target is grown vertically to find entities hit by a sweep.
+pub const SWEEP_VERTICAL: f32 = 0.25;
+
+/// The enchantment which increases the damage of a weapon.
+pub const SHARPNESS: &str = "sharpness";
+
+/// The enchantment which reduces the damage taken by the wearer of the armor.
+pub const PROTECTION: &str = "protection";
+
+/// The maximum number of armor points an entity can have.
+const MAX_ARMOR: f32 = 30.0;
+
+/// The fraction of damage every level of Resistance blocks.
+const RESISTANCE_PER_LEVEL: f32 = 0.2;
+
+/// The maximum armor points and combined Protection levels which still reduce damage.
+const MAX_REDUCTION: f32 = 20.0;
+
+/// Which version of Minecraft combat to use.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Ruleset {
+    /// 1.9+ combat with an attack cooldown, sweeping and the current weapon damage.
+    #[default]
+    Modern,
+    /// 1.8 combat without an attack cooldown.
+    Legacy,
+}
+
+impl Ruleset {
+    /// The rules of the ruleset.
+    pub const fn rules(self) -> &'static Rules {
+        match self {
+            Self::Modern => &Rules::MODERN,
+            Self::Legacy => &Rules::LEGACY,
+        }
+    }
+}
+
+/// Everything about combat which differs between versions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rules {
+    /// Whether attacks are weaker if the attacker did not wait for the attack cooldown.
+    pub cooldown: bool,
+    /// Whether weapons deal their 1.8 damage instead of their current damage.
+    pub legacy_damage: bool,
+    /// The damage multiplier of critical hits.
+    pub crit_multiplier: f32,
+    /// Whether sprinting attackers cannot land critical hits.
+    pub sprint_cancels_crit: bool,
+    /// The knockback which is added to attacks while sprinting.
+    pub sprint_knockback: f32,
+    /// Whether swords also hit the entities next to the target.
+    pub sweep: bool,
+    /// The damage Sharpness adds independent of its level.
+    pub sharpness_base: f32,
+    /// The damage every level of Sharpness adds.
+    pub sharpness_per_level: f32,
+    /// The damage every level of Strength adds to the damage of the weapon.
+    pub strength_per_level: f32,
+    /// The fraction of the damage of the weapon every level of Strength adds.
+    pub strength_multiplier: f32,
+    /// Whether armor uses the 1.8 formula which ignores toughness.
+    pub legacy_armor: bool,
+    /// Whether hunger is simulated, see [`crate::food`]. Without it players keep a full food bar
+    /// and regenerate half a heart every four seconds.
+    pub hunger: bool,
+    /// Whether players with full food quickly regenerate health from their saturation.
+    pub saturated_regeneration: bool,
+    /// The exhaustion of regenerating half a heart from food.
+    pub regeneration_exhaustion: f32,
+}
+
+impl Rules {
+    /// The rules of [`Ruleset::Modern`].
+    pub const MODERN: Self = Self {
+        cooldown: true,
+        legacy_damage: false,
+        crit_multiplier: 1.5,
+        sprint_cancels_crit: true,
+        sprint_knockback: 0.5,
+        sweep: true,
+        sharpness_base: 0.5,
+        sharpness_per_level: 0.5,
+        strength_per_level: 3.0,
+        strength_multiplier: 0.0,
+        legacy_armor: false,
+        hunger: true,
+        saturated_regeneration: true,
+        regeneration_exhaustion: 6.0,
+    };
+
+    /// The rules of [`Ruleset::Legacy`].
+    pub const LEGACY: Self = Self {
+        cooldown: false,
+        legacy_damage: true,
+        crit_multiplier: 1.5,
+        sprint_cancels_crit: false,
+        sprint_knockback: 0.5,
+        sweep: false,
+        sharpness_base: 0.0,
+        sharpness_per_level: 1.25,
+        strength_per_level: 0.0,
+        strength_multiplier: 1.3,
+        legacy_armor: true,
+        // legacy PvP is usually played without hunger, but keeps the 1.8 model if it is turned on
+        hunger: false,
+        saturated_regeneration: false,
+        regeneration_exhaustion: 3.0,
+    };
+
+    /// How charged an attack with `weapon` is if the attacker waited `ticks` ticks since their
+    /// last attack or item switch. Ranges from `0.0` to `1.0`.
+    pub fn charge(&self, weapon: &Weapon, ticks: i64) -> f32 {
+        if !self.cooldown {
+            return 1.0;
+        }
+
+        let delay = 20.0 / weapon.attack_speed;
+        ((ticks as f32 + 0.5) / delay).clamp(0.0, 1.0)
+    }
+
+    /// The damage Sharpness of the given level adds to an attack.
+    pub fn sharpness(&self, level: u32) -> f32 {
+        if level == 0 {
+            return 0.0;
+        }
+
+        (level as f32).mul_add(self.sharpness_per_level, self.sharpness_base)
+    }
+
+    /// Calculates the outcome of an attack.
+    pub fn hit(&self, attack: &Attack) -> Hit {
+        let weapon = &attack.weapon;
+
+        let base = if self.legacy_damage {
+            weapon.legacy_damage
+        } else {
+            weapon.damage
+        };
+
+        let strength = attack.strength as f32;
+        let base = strength.mul_add(self.strength_per_level, base)
+            * strength.mul_add(self.strength_multiplier, 1.0);
+
+        let charge = self.charge(weapon, attack.ticks_since_reset);
+        let charged = charge > FULL_CHARGE;
+
+        let mut damage = base * charge.mul_add(charge * 0.8, 0.2);
+        let enchantment = self.sharpness(attack.sharpness) * charge;
+
+        let knockback = if attack.sprinting && charged {
+            self.sprint_knockback
+        } else {
+            0.0
+        };
+
+        let falling = attack.fall_distance > 0.0 && !attack.on_ground;
+        let critical = charged && falling && !(attack.sprinting && self.sprint_cancels_crit);
+
+        // critical hits do not multiply the damage of enchantments
+        if critical {
+            damage *= self.crit_multiplier;
+        }
+
+        damage += enchantment;
+
+        let sweep = self.sweep
+            && weapon.sword
+            && charged
+            && !critical
+            && knockback == 0.0
+            && attack.on_ground;
+
+        Hit {
+            damage,
+            critical,
+            knockback,
+            sweep,
+        }
+    }
+
+    /// The damage which is left after armor, Resistance and Protection reduced `damage`.
+    pub fn reduce(&self, damage: f32, defense: &Defense) -> f32 {
+        let armor = defense.armor.min(MAX_ARMOR);
+
+        let points = if self.legacy_armor {
+            armor.min(MAX_REDUCTION)
+        } else {
+            // strong hits pierce armor unless it is tough
+            let piercing = defense.toughness.mul_add(0.25, 2.0);
+            (armor - damage / piercing).clamp(armor * 0.2, MAX_REDUCTION)
+        };
+
+        let damage = damage * (1.0 - points / 25.0);
+
+        let resistance = (defense.resistance as f32 * RESISTANCE_PER_LEVEL).min(1.0);
+        let damage = damage * (1.0 - resistance);
+
+        let protection = (defense.protection as f32).min(MAX_REDUCTION);
+        damage * (1.0 - protection / 25.0)
+    }
+}
+
+/// The damage reduction of the armor an entity is wearing.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Defense {
+    /// The armor points.
+    pub armor: f32,
+    /// The armor toughness, which keeps strong hits from piercing the armor.
+    pub toughness: f32,
+    /// The sum of the Protection levels of all pieces.
+    pub protection: u32,
+    /// The level of the Resistance effect of the entity.
+    pub resistance: u32,
+}
+
+impl Defense {
+    /// No armor at all.
+    pub const NONE: Self = Self {
+        armor: 0.0,
+        toughness: 0.0,
+        protection: 0,
+        resistance: 0,
+    };
+
+    /// The defense of the given armor pieces.
+    pub fn of<'a>(armor: impl IntoIterator<Item = &'a ItemStack>) -> Self {
+        armor.into_iter().fold(Self::NONE, |defense, stack| {
+            let (armor, toughness) = armor_points(stack.item);
+
+            Self {
+                armor: defense.armor + armor,
+                toughness: defense.toughness + toughness,
+                protection: defense.protection + enchantment_level(stack, PROTECTION),
+                resistance: 0,
+            }
+        })
+    }
+
+    /// The defense with the given level of the Resistance effect.
+    pub const fn with_resistance(self, resistance: u32) -> Self {
+        Self { resistance, ..self }
+    }
+}
+
+/// The armor points and toughness of an armor piece.
+const fn armor_points(kind: ItemKind) -> (f32, f32) {
+    match kind {
+        ItemKind::LeatherHelmet
+        | ItemKind::LeatherBoots
+        | ItemKind::ChainmailBoots
+        | ItemKind::GoldenBoots => (1.0, 0.0),
+        ItemKind::LeatherLeggings => (2.0, 0.0),
+        ItemKind::LeatherChestplate | ItemKind::GoldenLeggings => (3.0, 0.0),
+
+        ItemKind::ChainmailHelmet
+        | ItemKind::IronHelmet
+        | ItemKind::IronBoots
+        | ItemKind::GoldenHelmet
+        | ItemKind::TurtleHelmet => (2.0, 0.0),
+        ItemKind::ChainmailLeggings => (4.0, 0.0),
+        ItemKind::ChainmailChestplate | ItemKind::GoldenChestplate | ItemKind::IronLeggings => {
+            (5.0, 0.0)
+        }
+        ItemKind::IronChestplate => (6.0, 0.0),
+
+        ItemKind::DiamondHelmet | ItemKind::DiamondBoots => (3.0, 2.0),
+        ItemKind::DiamondLeggings => (6.0, 2.0),
+        ItemKind::DiamondChestplate => (8.0, 2.0),
+
+        ItemKind::NetheriteHelmet | ItemKind::NetheriteBoots => (3.0, 3.0),
+        ItemKind::NetheriteLeggings => (6.0, 3.0),
+        ItemKind::NetheriteChestplate => (8.0, 3.0),
+
+        _ => (0.0, 0.0),
+    }
+}
+
+/// The level of the enchantment with the given name (without the `minecraft:` namespace) on an
+/// item stack, or `0` if the stack does not have it.
+pub fn enchantment_level(stack: &ItemStack, enchantment: &str) -> u32 {
+    let Some(Value::List(List::Compound(enchantments))) =
+        stack.nbt.as_ref().and_then(|nbt| nbt.get("Enchantments"))
+    else {
+        return 0;
+    };
+
+    enchantments
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry.get("id"),
+                Some(Value::String(id)) if id.strip_prefix("minecraft:").unwrap_or(id) == enchantment
+            )
+        })
+        .filter_map(|entry| {
+            let level = match entry.get("lvl")? {
+                Value::Short(level) => i32::from(*level),
+                Value::Int(level) => *level,
+                _ => return None,
+            };
+
+            u32::try_from(level).ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The combat stats of an item.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Weapon {
+    /// The damage with [`Ruleset::Modern`], measured in half hearts.
+    pub damage: f32,
+    /// The damage with [`Ruleset::Legacy`], measured in half hearts.
+    pub legacy_damage: f32,
+    /// The number of fully charged attacks per second with [`Ruleset::Modern`].
+    pub attack_speed: f32,
+    /// Whether the weapon is a sword and can sweep.
+    pub sword: bool,
+}
+
+impl Weapon {
+    /// An empty hand or any item which is not a weapon.
+    pub const FIST: Self = Self::new(1.0, 1.0, 4.0);
+
+    const fn new(damage: f32, legacy_damage: f32, attack_speed: f32) -> Self {
+        Self {
+            damage,
+            legacy_damage,
+            attack_speed,
+            sword: false,
+        }
+    }
+
+    const fn sword(damage: f32, legacy_damage: f32) -> Self {
+        Self {
+            damage,
+            legacy_damage,
+            attack_speed: 1.6,
+            sword: true,
+        }
+    }
+
+    /// The stats of an item. Netherite did not exist in 1.8, so its legacy damage is one more
+    /// than diamond like in later versions.
+    pub const fn of(kind: ItemKind) -> Self {
+        match kind {
+            ItemKind::WoodenSword | ItemKind::GoldenSword => Self::sword(4.0, 5.0),
+            ItemKind::StoneSword => Self::sword(5.0, 6.0),
+            ItemKind::IronSword => Self::sword(6.0, 7.0),
+            ItemKind::DiamondSword => Self::sword(7.0, 8.0),
+            ItemKind::NetheriteSword => Self::sword(8.0, 9.0),
+
+            ItemKind::WoodenAxe => Self::new(7.0, 4.0, 0.8),
+            ItemKind::GoldenAxe => Self::new(7.0, 4.0, 1.0),
+            ItemKind::StoneAxe => Self::new(9.0, 5.0, 0.8),
+            ItemKind::IronAxe => Self::new(9.0, 6.0, 0.9),
+            ItemKind::DiamondAxe => Self::new(9.0, 7.0, 1.0),
+            ItemKind::NetheriteAxe => Self::new(10.0, 8.0, 1.0),
+
+            ItemKind::WoodenPickaxe | ItemKind::GoldenPickaxe => Self::new(2.0, 3.0, 1.2),
+            ItemKind::StonePickaxe => Self::new(3.0, 4.0, 1.2),
+            ItemKind::IronPickaxe => Self::new(4.0, 5.0, 1.2),
+            ItemKind::DiamondPickaxe => Self::new(5.0, 6.0, 1.2),
+            ItemKind::NetheritePickaxe => Self::new(6.0, 7.0, 1.2),
+
+            ItemKind::WoodenShovel | ItemKind::GoldenShovel => Self::new(2.5, 2.0, 1.0),
+            ItemKind::StoneShovel => Self::new(3.5, 3.0, 1.0),
+            ItemKind::IronShovel => Self::new(4.5, 4.0, 1.0),
+            ItemKind::DiamondShovel => Self::new(5.5, 5.0, 1.0),
+            ItemKind::NetheriteShovel => Self::new(6.5, 6.0, 1.0),
+
+            ItemKind::WoodenHoe | ItemKind::GoldenHoe => Self::new(1.0, 1.0, 1.0),
+            ItemKind::StoneHoe => Self::new(1.0, 1.0, 2.0),
+            ItemKind::IronHoe => Self::new(1.0, 1.0, 3.0),
+            ItemKind::DiamondHoe | ItemKind::NetheriteHoe => Self::new(1.0, 1.0, 4.0),
+
+            ItemKind::Trident => Self::new(9.0, 9.0, 1.1),
+
+            _ => Self::FIST,
+        }
+    }
+}
+
+/// What the attacker was doing when they attacked.
+#[derive(Copy, Clone, Debug)]
+pub struct Attack {
+    /// The weapon in the main hand of the attacker.
+    pub weapon: Weapon,
+    /// The number of ticks since the attacker last attacked or switched items.
+    pub ticks_since_reset: i64,
+    /// Whether the attacker is sprinting.
+    pub sprinting: bool,
+    /// The level of Sharpness on the weapon.
+    pub sharpness: u32,
+    /// The level of the Strength effect of the attacker.
+    pub strength: u32,
+    /// Whether the attacker is standing on the ground.
+    pub on_ground: bool,
+    /// The distance the attacker fell since they last stood on the ground.
+    pub fall_distance: f32,
+}
+
+/// The outcome of an [`Attack`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hit {
+    /// The damage dealt to the target, measured in half hearts.
+    pub damage: f32,
+    /// Whether the hit is a critical hit.
+    pub critical: bool,
+    /// The knockback in addition to [`BASE_KNOCKBACK`] in the direction the attacker is looking.
+    pub knockback: f32,
+    /// Whether the entities next to the target are hit as well.
+    pub sweep: bool,
+}
+
+/// The horizontal direction an entity with the given yaw (in degrees) is looking in.
+pub fn facing(yaw: f32) -> Vec2 {
+    let (sin, cos) = yaw.to_radians().sin_cos();
+    Vec2::new(-sin, cos)
+}
+
+/// Applies knockback like vanilla which pushes an entity with `velocity` in `direction`.
+/// Entities in the air are only pushed horizontally.
+pub fn knockback(velocity: &mut Vec3, strength: f32, direction: Vec2, on_ground: bool) {
+    let Some(direction) = direction.try_normalize() else {
+        return;
+    };
+
+    let push = direction * strength;
+
+    velocity.x = velocity.x / 2.0 + push.x;
+    velocity.z = velocity.z / 2.0 + push.y;
+
+    if on_ground {
+        velocity.y = (velocity.y / 2.0 + strength).min(0.4);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use valence_protocol::nbt::compound;
+
+    use super::*;
+
+    fn attack(weapon: Weapon, ticks_since_reset: i64) -> Attack {
+        Attack {
+            weapon,
+            ticks_since_reset,
+            sharpness: 0,
+            strength: 0,
+            sprinting: false,
+            on_ground: true,
+            fall_distance: 0.0,
+        }
+    }
+
+    #[test]
+    fn full_charge_deals_full_damage() {
+        let sword = Weapon::of(ItemKind::DiamondSword);
+        let hit = Rules::MODERN.hit(&attack(sword, 100));
+
+        assert!((hit.damage - 7.0).abs() < 1e-5);
+        assert!(!hit.critical);
+        assert!(hit.sweep);
+    }
+
+    #[test]
+    fn spam_click_is_weak() {
+        let sword = Weapon::of(ItemKind::DiamondSword);
+
+        // a sword needs 12.5 ticks to charge, the same tick gives a charge of 0.04
+        let hit = Rules::MODERN.hit(&attack(sword, 0));
+        let charge: f32 = 0.5 / 12.5;
+        let expected = 7.0 * charge.mul_add(charge * 0.8, 0.2);
+
+        assert!((hit.damage - expected).abs() < 1e-5);
+        assert!(!hit.sweep);
+
+        // without a cooldown every attack is fully charged
+        let hit = Rules::LEGACY.hit(&attack(sword, 0));
+        assert!((hit.damage - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn falling_attack_is_critical() {
+        let axe = Weapon::of(ItemKind::NetheriteAxe);
+
+        let falling = Attack {
+            on_ground: false,
+            fall_distance: 0.5,
+            ..attack(axe, 100)
+        };
+
+        let hit = Rules::MODERN.hit(&falling);
+        assert!(hit.critical);
+        assert!((hit.damage - 15.0).abs() < 1e-5);
+
+        // sprinting cancels critical hits since 1.9
+        let sprinting = Attack {
+            sprinting: true,
+            ..falling
+        };
+
+        let hit = Rules::MODERN.hit(&sprinting);
+        assert!(!hit.critical);
+        assert!((hit.knockback - 0.5).abs() < 1e-5);
+
+        let hit = Rules::LEGACY.hit(&sprinting);
+        assert!(hit.critical);
+        assert!((hit.damage - 12.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn only_swords_sweep() {
+        let hit = Rules::MODERN.hit(&attack(Weapon::of(ItemKind::IronAxe), 100));
+        assert!(!hit.sweep);
+
+        let hit = Rules::LEGACY.hit(&attack(Weapon::of(ItemKind::IronSword), 100));
+        assert!(!hit.sweep);
+    }
+
+    fn enchanted(kind: ItemKind, enchantment: &str, level: i16) -> ItemStack {
+        let nbt = compound! {
+            "Enchantments" => List::Compound(vec![compound! {
+                "id" => format!("minecraft:{enchantment}"),
+                "lvl" => level,
+            }]),
+        };
+
+        ItemStack::new(kind, 1, Some(nbt))
+    }
+
+    fn netherite() -> [ItemStack; 4] {
+        [
+            ItemKind::NetheriteBoots,
+            ItemKind::NetheriteLeggings,
+            ItemKind::NetheriteChestplate,
+            ItemKind::NetheriteHelmet,
+        ]
+        .map(|kind| ItemStack::new(kind, 1, None))
+    }
+
+    #[test]
+    fn sharpness_adds_damage() {
+        let sword = enchanted(ItemKind::DiamondSword, SHARPNESS, 5);
+        assert_eq!(enchantment_level(&sword, SHARPNESS), 5);
+        assert_eq!(enchantment_level(&sword, PROTECTION), 0);
+
+        let sharp = Attack {
+            sharpness: enchantment_level(&sword, SHARPNESS),
+            ..attack(Weapon::of(sword.item), 100)
+        };
+
+        // 7 + 0.5 * 5 + 0.5
+        let hit = Rules::MODERN.hit(&sharp);
+        assert!((hit.damage - 10.0).abs() < 1e-5);
+
+        // 8 + 1.25 * 5
+        let hit = Rules::LEGACY.hit(&sharp);
+        assert!((hit.damage - 14.25).abs() < 1e-5);
+
+        // critical hits only multiply the damage of the sword
+        let critical = Attack {
+            on_ground: false,
+            fall_distance: 1.0,
+            ..sharp
+        };
+
+        let hit = Rules::MODERN.hit(&critical);
+        assert!(hit.critical);
+        assert!((hit.damage - 13.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn netherite_armor_reduces_damage() {
+        let defense = Defense::of(&netherite());
+        assert_eq!(
+            defense,
+            Defense {
+                armor: 20.0,
+                toughness: 12.0,
+                protection: 0,
+                resistance: 0,
+            }
+        );
+
+        // a fully charged diamond sword: 7 * (1 - (20 - 7 / 5) / 25)
+        let damage = Rules::MODERN.reduce(7.0, &defense);
+        assert!((damage - 1.792).abs() < 1e-5);
+
+        // the same hit against full diamond armor: 7 * (1 - (20 - 7 / 4) / 25)
+        let diamond = [
+            ItemKind::DiamondBoots,
+            ItemKind::DiamondLeggings,
+            ItemKind::DiamondChestplate,
+            ItemKind::DiamondHelmet,
+        ]
+        .map(|kind| ItemStack::new(kind, 1, None));
+
+        let damage = Rules::MODERN.reduce(7.0, &Defense::of(&diamond));
+        assert!((damage - 1.89).abs() < 1e-5);
+
+        // armor always blocks at least a fifth of its points: 100 * (1 - 4 / 25)
+        let damage = Rules::MODERN.reduce(100.0, &defense);
+        assert!((damage - 84.0).abs() < 1e-3);
+
+        // 1.8 ignores toughness and blocks 4% per point
+        let damage = Rules::LEGACY.reduce(7.0, &defense);
+        assert!((damage - 1.4).abs() < 1e-5);
+
+        assert!((Rules::MODERN.reduce(7.0, &Defense::NONE) - 7.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn protection_reduces_damage() {
+        let armor = netherite().map(|stack| enchanted(stack.item, PROTECTION, 4));
+        let defense = Defense::of(&armor);
+
+        assert_eq!(defense.protection, 16);
+
+        // 1.792 * (1 - 16 / 25)
+        let damage = Rules::MODERN.reduce(7.0, &defense);
+        assert!((damage - 0.645_12).abs() < 1e-5);
+
+        // protection is capped at 20 levels
+        let defense = Defense {
+            protection: 32,
+            ..Defense::NONE
+        };
+
+        let damage = Rules::MODERN.reduce(10.0, &defense);
+        assert!((damage - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn strength_and_resistance() {
+        let sword = Weapon::of(ItemKind::DiamondSword);
+
+        let strong = Attack {
+            strength: 2,
+            ..attack(sword, 100)
+        };
+
+        // 1.9+ adds 3 damage per level, 1.8 adds 130% per level
+        assert!((Rules::MODERN.hit(&strong).damage - 13.0).abs() < 1e-5);
+        assert!((Rules::LEGACY.hit(&strong).damage - 28.8).abs() < 1e-4);
+
+        // resistance blocks a fifth per level after armor: 1.792 * (1 - 0.4)
+        let defense = Defense::of(&netherite()).with_resistance(2);
+        let damage = Rules::MODERN.reduce(7.0, &defense);
+        assert!((damage - 1.075_2).abs() < 1e-5);
+
+        // resistance V blocks everything
+        let defense = Defense::NONE.with_resistance(5);
+        assert!(Rules::MODERN.reduce(7.0, &defense).abs() < 1e-5);
+    }
+
+    #[test]
+    fn knockback_on_ground_and_in_air() {
+        let mut velocity = Vec3::ZERO;
+        knockback(&mut velocity, 0.4, Vec2::new(3.0, 4.0), true);
+
+        assert!((velocity - Vec3::new(0.24, 0.4, 0.32)).length() < 1e-5);
+
+        let mut velocity = Vec3::new(0.0, -0.2, 0.0);
+        knockback(&mut velocity, 0.4, Vec2::X, false);
+
+        assert!((velocity - Vec3::new(0.4, -0.2, 0.0)).length() < 1e-5);
+    }
+}
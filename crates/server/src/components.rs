@@ -1,17 +1,33 @@
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 use bvh::aabb::Aabb;
 use derive_more::{Deref, Display, From};
 use evenio::component::Component;
-use glam::Vec3;
+use glam::{IVec3, Vec3};
+use valence_protocol::{ItemKind, ItemStack};
 
 use crate::{
-    components::vitals::{Absorption, Regeneration},
+    combat::{self, Attack, Defense, Rules, Weapon, SHARPNESS},
+    components::{
+        projectile::BowDraw,
+        status_effects::{EffectKind, StatusEffects},
+    },
+    config,
+    food::{
+        Food, EXHAUSTION_PER_POINT, HUNGER_INTERVAL, JUMP_EXHAUSTION, MAX_EXHAUSTION, MAX_FOOD,
+        REGENERATION_EXHAUSTION, REGENERATION_FOOD, SATURATED_INTERVAL, SPRINT_EXHAUSTION,
+        SPRINT_JUMP_EXHAUSTION,
+    },
     global::Global,
+    singleton::{path_cache::Path, player_aabb_lookup::LookupData},
 };
 
+pub mod damage;
+pub mod inventory;
+pub mod mob_kind;
 pub mod pose;
-pub mod vitals;
+pub mod projectile;
+pub mod status_effects;
 
 #[derive(Component, Deref, From, Display)]
 pub struct InGameName(Box<str>);
@@ -43,6 +59,9 @@ pub enum LoginState {
 #[derive(Component)]
 pub struct HasInvincibility(pub bool);
 
+/// The health of a player at full health, measured in half hearts.
+pub const MAX_HEALTH: f32 = 20.0;
+
 #[derive(Copy, Clone, PartialEq, Debug, Component)]
 pub enum Vitals {
     /// If the player is alive
@@ -50,10 +69,8 @@ pub enum Vitals {
         /// Measured in half hearts
         health: f32,
 
-        /// The absorption effect
-        absorption: Absorption,
-        /// The regeneration effect
-        regeneration: Regeneration,
+        /// The extra health from the absorption effect, which is lost before the normal health.
+        absorption: f32,
     },
     /// If the player is dead
     Dead {
@@ -64,24 +81,41 @@ pub enum Vitals {
 
 impl Vitals {
     pub const ALIVE: Self = Self::Alive {
-        health: 20.0,
-        absorption: Absorption::DEFAULT,
-        regeneration: Regeneration::DEFAULT,
+        health: MAX_HEALTH,
+        absorption: 0.0,
     };
 }
 
-#[derive(Component, Debug, Eq, PartialEq, Default)]
+#[derive(Component, Debug, PartialEq, Default)]
 pub struct ImmuneStatus {
     pub until: i64,
+    /// The damage of the hit which made the entity invulnerable. Stronger hits still deal the
+    /// difference while the entity is invulnerable.
+    pub last_damage: f32,
 }
 
 impl ImmuneStatus {
     pub const fn is_invincible(&self, global: &Global) -> bool {
         global.tick < self.until
     }
+
+    /// Makes the entity immune to all damage for `ticks` ticks from `tick`, i.e., after it spawned.
+    pub fn protect(&mut self, tick: i64, ticks: i64) {
+        self.until = tick + ticks;
+        // no hit is stronger
+        self.last_damage = f32::INFINITY;
+    }
 }
 
 impl Vitals {
+    /// The health and absorption left, `0.0` if dead.
+    pub fn hit_points(&self) -> f32 {
+        match *self {
+            Self::Alive { health, absorption } => health.max(0.0) + absorption,
+            Self::Dead { .. } => 0.0,
+        }
+    }
+
     /// Heal the player by a given amount.
     pub fn heal(&mut self, amount: f32) {
         assert!(amount.is_finite());
@@ -92,55 +126,260 @@ impl Vitals {
         };
 
         *health += amount;
-        *health = health.min(20.0);
+        *health = health.min(MAX_HEALTH);
     }
 
-    /// Hurt the player by a given amount.
-    pub fn hurt(&mut self, global: &Global, mut amount: f32, immune: &mut ImmuneStatus) {
+    /// Hurt the player by a given amount, which is reduced by the armor they are wearing and their
+    /// resistance.
+    pub fn hurt(
+        &mut self,
+        global: &Global,
+        mut amount: f32,
+        defense: &Defense,
+        immune: &mut ImmuneStatus,
+    ) -> Hurt {
         debug_assert!(amount.is_finite());
         debug_assert!(amount >= 0.0);
 
         let tick = global.tick;
 
-        if tick < immune.until {
-            return;
-        }
-
-        let max_hurt_resistant_time = global.max_hurt_resistant_time;
-
-        immune.until = tick + i64::from(max_hurt_resistant_time) / 2;
-
         let Self::Alive {
             health, absorption, ..
         } = self
         else {
-            return;
+            return Hurt::Immune;
         };
 
-        if tick < absorption.end_tick {
-            if amount > absorption.bonus_health {
-                amount -= absorption.bonus_health;
-                absorption.bonus_health = 0.0;
-            } else {
-                absorption.bonus_health -= amount;
-                return;
+        let hurt = if tick < immune.until {
+            if amount <= immune.last_damage {
+                return Hurt::Immune;
             }
-        }
 
-        *health -= amount;
+            // like vanilla, a stronger hit only deals the damage it adds to the last hit
+            let last_damage = immune.last_damage;
+            immune.last_damage = amount;
+            amount -= last_damage;
+
+            Hurt::Stronger
+        } else {
+            let max_hurt_resistant_time = global.max_hurt_resistant_time;
+
+            immune.until = tick + i64::from(max_hurt_resistant_time) / 2;
+            immune.last_damage = amount;
+
+            Hurt::Full
+        };
+
+        // invulnerability compares the damage before armor like vanilla
+        amount = config::CONFIG.combat.rules().reduce(amount, defense);
+
+        let absorbed = amount.min(*absorption);
+        *absorption -= absorbed;
+        *health -= amount - absorbed;
 
         if *health <= 0.0 {
             *self = Self::Dead {
-                respawn_tick: tick + 100,
+                respawn_tick: tick + config::CONFIG.respawn_delay,
             }
         }
+
+        hurt
     }
 }
 
+/// How an entity was affected by [`Vitals::hurt`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hurt {
+    /// The entity is dead or invulnerable and took no damage.
+    Immune,
+    /// The entity is invulnerable, but the hit was stronger than the one which made it
+    /// invulnerable and dealt the difference. Like in vanilla, this does not knock it back.
+    Stronger,
+    /// The entity took the full damage and is invulnerable now.
+    Full,
+}
+
 /// A UUID component. Generally speaking, this tends to be tied to entities with a [`Player`] component.
 #[derive(Component, Copy, Clone, Debug, Deref, From)]
 pub struct Uuid(pub uuid::Uuid);
 
+/// The state of a player which affects their attacks, see [`crate::combat`].
+#[derive(Component, Debug, Default)]
+pub struct CombatState {
+    /// The tick of the last attack or item switch, which resets the attack cooldown.
+    pub last_reset: i64,
+    /// Whether the player is sprinting.
+    pub sprinting: bool,
+    /// The distance the player fell since they last stood on the ground.
+    pub fall_distance: f32,
+    /// The bow the player is drawing, if any.
+    pub drawing: Option<BowDraw>,
+}
+
+impl CombatState {
+    /// Tracks the fall distance after the player moved vertically by `delta_y`.
+    pub fn moved(&mut self, delta_y: f32, on_ground: bool) {
+        if on_ground {
+            self.fall_distance = 0.0;
+        } else if delta_y < 0.0 {
+            self.fall_distance -= delta_y;
+        }
+    }
+
+    /// The attack the player makes with the item in their main hand on the given tick.
+    pub fn attack(
+        &self,
+        tick: i64,
+        on_ground: bool,
+        main_hand: &ItemStack,
+        effects: &StatusEffects,
+    ) -> Attack {
+        Attack {
+            weapon: Weapon::of(main_hand.item),
+            ticks_since_reset: tick - self.last_reset,
+            sharpness: combat::enchantment_level(main_hand, SHARPNESS),
+            strength: effects.level(EffectKind::Strength),
+            sprinting: self.sprinting,
+            on_ground,
+            fall_distance: self.fall_distance,
+        }
+    }
+}
+
+/// The food level and saturation of a player. Saturation is used up before the food level and
+/// can never be higher than it.
+///
+/// Actions like sprinting add exhaustion. Every [`EXHAUSTION_PER_POINT`] of exhaustion use up a
+/// point of saturation or, without saturation, a food point.
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct Hunger {
+    /// The food level, ranging from `0` to [`MAX_FOOD`].
+    pub food: u8,
+    /// The saturation, ranging from `0` to the food level.
+    pub saturation: f32,
+    /// The exhaustion, ranging from `0` to [`MAX_EXHAUSTION`].
+    pub exhaustion: f32,
+    /// The ticks since the player last regenerated or starved.
+    pub timer: u8,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self {
+            food: MAX_FOOD,
+            saturation: 5.0,
+            exhaustion: 0.0,
+            timer: 0,
+        }
+    }
+}
+
+impl Hunger {
+    /// Whether the food level or saturation differ, which are the values the client shows.
+    pub fn differs(&self, other: &Self) -> bool {
+        self.food != other.food || (self.saturation - other.saturation).abs() > f32::EPSILON
+    }
+
+    /// Adds exhaustion from an action.
+    pub fn exhaust(&mut self, amount: f32) {
+        self.exhaustion = (self.exhaustion + amount).min(MAX_EXHAUSTION);
+    }
+
+    /// Adds the exhaustion of moving by `delta`.
+    pub fn moved(&mut self, delta: Vec3, jumped: bool, sprinting: bool) {
+        if sprinting {
+            let distance = delta.x.hypot(delta.z);
+            self.exhaust(SPRINT_EXHAUSTION * distance);
+        }
+
+        if jumped {
+            self.exhaust(if sprinting {
+                SPRINT_JUMP_EXHAUSTION
+            } else {
+                JUMP_EXHAUSTION
+            });
+        }
+    }
+
+    /// Runs a tick of the hunger simulation for a player with `health`. Returns the health the
+    /// player regenerates, or a negative amount if they starve.
+    pub fn update(&mut self, rules: &Rules, health: f32) -> f32 {
+        if self.exhaustion > EXHAUSTION_PER_POINT {
+            self.exhaustion -= EXHAUSTION_PER_POINT;
+
+            if self.saturation > 0.0 {
+                self.saturation = (self.saturation - 1.0).max(0.0);
+            } else {
+                self.food = self.food.saturating_sub(1);
+            }
+        }
+
+        let hurt = health < MAX_HEALTH;
+
+        let (interval, change, exhaustion) = if rules.saturated_regeneration
+            && hurt
+            && self.saturation > 0.0
+            && self.food == MAX_FOOD
+        {
+            // well fed players quickly regenerate from their saturation
+            let amount = self.saturation.min(REGENERATION_EXHAUSTION);
+            (SATURATED_INTERVAL, amount / REGENERATION_EXHAUSTION, amount)
+        } else if hurt && self.food >= REGENERATION_FOOD {
+            (HUNGER_INTERVAL, 1.0, rules.regeneration_exhaustion)
+        } else if self.food == 0 && health > 1.0 {
+            // like on normal difficulty, starving does not kill
+            (HUNGER_INTERVAL, -1.0, 0.0)
+        } else {
+            self.timer = 0;
+            return 0.0;
+        };
+
+        self.timer += 1;
+
+        if self.timer < interval {
+            return 0.0;
+        }
+
+        self.timer = 0;
+        self.exhaust(exhaustion);
+
+        change
+    }
+
+    /// Whether the player can eat the food.
+    pub const fn can_eat(&self, food: &Food) -> bool {
+        food.always_edible || self.food < MAX_FOOD
+    }
+
+    /// Restores food points and saturation from eating the food.
+    pub fn eat(&mut self, food: &Food) {
+        self.food = self.food.saturating_add(food.nutrition).min(MAX_FOOD);
+
+        let saturation = f32::from(food.nutrition) * food.saturation_modifier * 2.0;
+        self.saturation = (self.saturation + saturation).min(f32::from(self.food));
+    }
+}
+
+/// An item a player is eating, see [`crate::food`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Meal {
+    /// The inventory slot of the item.
+    pub slot: usize,
+    /// The item being eaten. Eating is cancelled if the slot holds a different item.
+    pub item: ItemKind,
+    /// The tick the player finishes eating.
+    pub finish_tick: i64,
+}
+
+/// What a player is eating, if anything.
+#[derive(Component, Debug, Default)]
+pub struct Eating {
+    /// The meal, `None` if the player is not eating.
+    pub meal: Option<Meal>,
+    /// Whether other players were shown that the player is eating.
+    pub shown: bool,
+}
+
 /// Any living minecraft entity that is NOT a player.
 ///
 /// Example: zombie, skeleton, etc.
@@ -157,6 +396,43 @@ impl Default for RunningSpeed {
     }
 }
 
+/// The direction a [`mob_kind::Behavior::Passive`] mob is wandering in.
+#[derive(Component, Default, Debug)]
+pub struct Wander {
+    /// The horizontal direction to walk in. Zero if the mob is standing still.
+    pub direction: Vec3,
+    /// The tick the mob picks a new direction.
+    pub until: i64,
+}
+
+/// When a [`mob_kind::Behavior::Ranged`] mob can shoot next.
+#[derive(Component, Default, Debug)]
+pub struct RangedAttack {
+    /// The tick the mob can shoot again.
+    pub next_shot: i64,
+}
+
+/// A mob which died and is playing the death animation. It is despawned on `despawn_tick`.
+#[derive(Component, Debug)]
+pub struct Dying {
+    /// The tick the mob is despawned.
+    pub despawn_tick: i64,
+}
+
+/// An item lying on the ground, i.e., dropped by a mob when it died. Items cannot be picked up
+/// yet and are despawned on `despawn_tick`.
+#[derive(Component, Debug)]
+pub struct DroppedItem {
+    /// The UUID sent to clients.
+    pub uuid: uuid::Uuid,
+    /// The item stack.
+    pub stack: ItemStack,
+    /// The position of the item.
+    pub position: Vec3,
+    /// The tick the item is despawned.
+    pub despawn_tick: i64,
+}
+
 /// If the entity can be targeted by non-player entities.
 #[derive(Component)]
 pub struct AiTargetable;
@@ -221,3 +497,73 @@ pub struct EntityReaction {
     /// The velocity of the entity.
     pub velocity: Vec3,
 }
+
+/// The physics state of an entity which is simulated by the server, see
+/// [`crate::system::entity_physics`].
+#[derive(Component, Default, Debug)]
+pub struct EntityPhysics {
+    /// The velocity of the entity in blocks per tick. Gravity and drag are applied every tick.
+    pub velocity: Vec3,
+    /// The movement the entity makes on its own this tick, i.e., walking towards a target. This
+    /// is reset after every tick.
+    pub walk: Vec3,
+}
+
+/// Whether the entity is standing on a block.
+#[derive(Component, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct OnGround(pub bool);
+
+/// The teleports a player was sent with `PlayerPositionLookS2c`. Until the client confirms the
+/// last one, it may still send positions from before the teleport, so movement is ignored.
+#[derive(Component, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Teleport {
+    /// The id of the last teleport.
+    id: i32,
+    /// Whether the client has not confirmed the last teleport yet.
+    pending: bool,
+}
+
+impl Teleport {
+    /// Starts a new teleport and returns its id.
+    pub fn start(&mut self) -> i32 {
+        self.id = self.id.wrapping_add(1);
+        self.pending = true;
+        self.id
+    }
+
+    /// Confirms the teleport with the given id. Older teleports are ignored.
+    pub fn confirm(&mut self, id: i32) {
+        if id == self.id {
+            self.pending = false;
+        }
+    }
+
+    /// Whether the client has not confirmed the last teleport yet.
+    pub const fn is_pending(&self) -> bool {
+        self.pending
+    }
+}
+
+/// The player a hostile mob is after this tick. `system::entity_pathfinding` looks up the closest
+/// player once per tick and `system::entity_move_logic` walks towards them.
+#[derive(Component, Copy, Clone, Default, Debug)]
+pub struct Target(pub Option<LookupData>);
+
+/// The path an entity is following, see [`crate::system::entity_pathfinding`].
+#[derive(Component, Default, Debug)]
+pub struct PathFollower {
+    /// The path which is being followed. If `None`, the entity walks straight towards its target.
+    pub path: Option<Arc<Path>>,
+    /// The index of the next waypoint in the path.
+    pub waypoint: usize,
+    /// The entity does not search for a new path before this tick. Used to back off after a
+    /// search failed.
+    pub next_search: i64,
+}
+
+impl PathFollower {
+    /// The next waypoint to walk to, if any.
+    pub fn next_waypoint(&self) -> Option<IVec3> {
+        self.path.as_ref()?.waypoints.get(self.waypoint).copied()
+    }
+}
@@ -0,0 +1,313 @@
+//! Who and what last hurt a player, for death messages and kill credit.
+//!
+//! Every time a player takes damage, the [`DamageTracker`] of the player records the
+//! [`DamageCause`] and, if an entity dealt it, the attacker and their weapon. Once the player dies,
+//! [`DamageTracker::death`] credits the kill to the last attacker if they hit the player recently,
+//! so players who push someone out of the border still get the kill. Every source of damage goes
+//! through [`DamageTracker::hurt`], which also sends the [`PlayerKilled`] event. Like vanilla, the
+//! death messages are translated by the client.
+use evenio::{component::Component, entity::EntityId};
+use valence_protocol::{
+    text::{IntoText, Text},
+    ItemKind,
+};
+
+use crate::{components::Vitals, events::PlayerKilled};
+
+/// The number of ticks after a hit in which the attacker is credited with the kill, like vanilla.
+pub const ATTRIBUTION_TICKS: i64 = 100;
+
+/// What dealt damage to a player.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DamageCause {
+    /// A player hit them.
+    PlayerAttack,
+    /// A mob hit them.
+    MobAttack,
+    /// An arrow hit them.
+    Arrow,
+    /// A thrown snowball or ender pearl hit them.
+    Thrown,
+    /// They landed after a fall or an ender pearl teleport.
+    Fall,
+    /// They were outside the world border.
+    OutsideBorder,
+    /// Anything else.
+    #[default]
+    Generic,
+}
+
+impl DamageCause {
+    /// The name of the vanilla damage type, see [`crate::singleton::registries::Registries`].
+    pub const fn damage_type(self) -> &'static str {
+        match self {
+            Self::PlayerAttack => "minecraft:player_attack",
+            Self::MobAttack => "minecraft:mob_attack",
+            Self::Arrow => "minecraft:arrow",
+            Self::Thrown => "minecraft:thrown",
+            Self::Fall => "minecraft:fall",
+            Self::OutsideBorder => "minecraft:outside_border",
+            Self::Generic => "minecraft:generic",
+        }
+    }
+
+    /// The name of the cause in vanilla translation keys, i.e., `death.attack.player`.
+    const fn message_id(self) -> &'static str {
+        match self {
+            Self::PlayerAttack => "player",
+            Self::MobAttack => "mob",
+            Self::Arrow => "arrow",
+            Self::Thrown => "thrown",
+            Self::Fall => "fall",
+            Self::OutsideBorder => "outsideBorder",
+            Self::Generic => "generic",
+        }
+    }
+
+    /// Whether the damage is dealt directly by the attacker.
+    const fn is_attack(self) -> bool {
+        matches!(
+            self,
+            Self::PlayerAttack | Self::MobAttack | Self::Arrow | Self::Thrown
+        )
+    }
+
+    /// The vanilla translation key of the death message. Attacks mention the weapon if there is
+    /// one, other causes mention the player who was fought last if there is one.
+    fn message_key(self, killer: bool, weapon: bool) -> String {
+        let id = self.message_id();
+
+        match (self.is_attack(), killer, weapon) {
+            (true, _, true) => format!("death.attack.{id}.item"),
+            (false, true, _) => format!("death.attack.{id}.player"),
+            _ => format!("death.attack.{id}"),
+        }
+    }
+}
+
+/// The entity which last hit a player.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Attacker {
+    /// The attacking player or mob.
+    pub id: EntityId,
+    /// The item the attacker held, if any.
+    pub weapon: Option<ItemKind>,
+    /// The tick of the hit.
+    pub tick: i64,
+}
+
+/// Why a player died, see [`DamageTracker::death`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Death {
+    /// What dealt the last damage.
+    pub cause: DamageCause,
+    /// The entity credited with the kill, if any.
+    pub killer: Option<EntityId>,
+    /// The weapon of the killer if they killed the player with a hit.
+    pub weapon: Option<ItemKind>,
+}
+
+/// See [`crate::components::damage`].
+#[derive(Component, Debug, Default)]
+pub struct DamageTracker {
+    /// What dealt the last damage.
+    cause: DamageCause,
+    /// The last entity which hit the player.
+    attacker: Option<Attacker>,
+}
+
+impl DamageTracker {
+    /// Records damage of `cause`, which `attacker` dealt if it is set.
+    pub fn record(&mut self, cause: DamageCause, attacker: Option<Attacker>) {
+        self.cause = cause;
+
+        if attacker.is_some() {
+            self.attacker = attacker;
+        }
+    }
+
+    /// Works out why the player died on `tick` and forgets the damage for the next life.
+    pub fn death(&mut self, tick: i64) -> Death {
+        let cause = std::mem::take(&mut self.cause);
+
+        let attacker = self
+            .attacker
+            .take()
+            .filter(|attacker| tick - attacker.tick <= ATTRIBUTION_TICKS);
+
+        Death {
+            cause,
+            killer: attacker.map(|attacker| attacker.id),
+            weapon: attacker
+                .filter(|_| cause.is_attack())
+                .and_then(|attacker| attacker.weapon),
+        }
+    }
+
+    /// Records damage which left `victim` with `vitals` and, if it killed them, returns the event
+    /// which announces and scores the kill.
+    pub fn hurt(
+        &mut self,
+        victim: EntityId,
+        vitals: &Vitals,
+        cause: DamageCause,
+        attacker: Option<Attacker>,
+        tick: i64,
+    ) -> Option<PlayerKilled> {
+        self.record(cause, attacker);
+
+        if !matches!(vitals, Vitals::Dead { .. }) {
+            return None;
+        }
+
+        let Death {
+            cause,
+            killer,
+            weapon,
+        } = self.death(tick);
+
+        Some(PlayerKilled {
+            victim,
+            killer,
+            cause,
+            weapon,
+        })
+    }
+}
+
+/// The vanilla death message of `victim`, i.e., `Steve was slain by Alex using [Iron Sword]`.
+pub fn death_message(
+    victim: Text,
+    cause: DamageCause,
+    killer: Option<Text>,
+    weapon: Option<ItemKind>,
+) -> Text {
+    let key = cause.message_key(killer.is_some(), weapon.is_some());
+
+    let mut with = vec![victim];
+    with.extend(killer);
+    with.extend(weapon.map(weapon_name));
+
+    Text::translate(key, with)
+}
+
+/// The name of a weapon in brackets, like vanilla shows items in chat.
+pub fn weapon_name(weapon: ItemKind) -> Text {
+    "[".into_text() + Text::translate(weapon.translation_key(), []) + "]"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attacker(tick: i64) -> Option<Attacker> {
+        Some(Attacker {
+            id: EntityId::NULL,
+            weapon: Some(ItemKind::IronSword),
+            tick,
+        })
+    }
+
+    #[test]
+    fn recent_attackers_are_credited() {
+        let mut tracker = DamageTracker::default();
+
+        tracker.record(DamageCause::PlayerAttack, attacker(0));
+        assert_eq!(
+            tracker.death(10),
+            Death {
+                cause: DamageCause::PlayerAttack,
+                killer: Some(EntityId::NULL),
+                weapon: Some(ItemKind::IronSword),
+            }
+        );
+
+        // the border gets the kill once the attacker is forgotten
+        tracker.record(DamageCause::PlayerAttack, attacker(0));
+        tracker.record(DamageCause::OutsideBorder, None);
+        assert_eq!(
+            tracker.death(ATTRIBUTION_TICKS).killer,
+            Some(EntityId::NULL)
+        );
+
+        tracker.record(DamageCause::PlayerAttack, attacker(0));
+        tracker.record(DamageCause::OutsideBorder, None);
+        assert_eq!(
+            tracker.death(ATTRIBUTION_TICKS + 1),
+            Death {
+                cause: DamageCause::OutsideBorder,
+                killer: None,
+                weapon: None,
+            }
+        );
+    }
+
+    #[test]
+    fn kills_are_reported_once_the_player_died() {
+        let mut tracker = DamageTracker::default();
+
+        let hit = tracker.hurt(
+            EntityId::NULL,
+            &Vitals::ALIVE,
+            DamageCause::PlayerAttack,
+            attacker(0),
+            0,
+        );
+        assert!(hit.is_none());
+
+        let dead = Vitals::Dead { respawn_tick: 100 };
+        let kill = tracker
+            .hurt(EntityId::NULL, &dead, DamageCause::OutsideBorder, None, 20)
+            .unwrap();
+        assert_eq!(kill.killer, Some(EntityId::NULL));
+        assert_eq!(kill.cause, DamageCause::OutsideBorder);
+        assert_eq!(kill.weapon, None);
+    }
+
+    #[test]
+    fn weapons_are_only_named_for_attacks() {
+        let mut tracker = DamageTracker::default();
+
+        tracker.record(DamageCause::PlayerAttack, attacker(0));
+        tracker.record(DamageCause::OutsideBorder, None);
+
+        let death = tracker.death(20);
+        assert_eq!(death.killer, Some(EntityId::NULL));
+        assert_eq!(death.weapon, None);
+    }
+
+    #[test]
+    fn messages_use_vanilla_keys() {
+        let key = DamageCause::message_key;
+
+        assert_eq!(
+            key(DamageCause::PlayerAttack, true, false),
+            "death.attack.player"
+        );
+        assert_eq!(
+            key(DamageCause::PlayerAttack, true, true),
+            "death.attack.player.item"
+        );
+        assert_eq!(key(DamageCause::MobAttack, true, false), "death.attack.mob");
+        assert_eq!(
+            key(DamageCause::Arrow, true, true),
+            "death.attack.arrow.item"
+        );
+        assert_eq!(
+            key(DamageCause::Fall, true, false),
+            "death.attack.fall.player"
+        );
+        assert_eq!(
+            key(DamageCause::OutsideBorder, false, false),
+            "death.attack.outsideBorder"
+        );
+        assert_eq!(
+            key(DamageCause::OutsideBorder, true, false),
+            "death.attack.outsideBorder.player"
+        );
+        assert_eq!(
+            key(DamageCause::Generic, true, false),
+            "death.attack.generic.player"
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! The inventory of a [`Player`](super::Player).
+//!
+//! The slots are numbered like the player inventory window of the client:
+//!
+//! - `0`: crafting output
+//! - `1..=4`: crafting grid
+//! - `5..=8`: helmet, chestplate, leggings and boots
+//! - `9..=35`: main inventory
+//! - `36..=44`: hotbar
+//! - `45`: offhand
+
+use std::{borrow::Cow, iter};
+
+use evenio::component::Component;
+use valence_protocol::{
+    packets::play::{
+        self, click_slot_c2s::SlotChange, entity_equipment_update_s2c::EquipmentEntry,
+    },
+    ItemKind, ItemStack, VarInt,
+};
+
+use crate::combat::Defense;
+
+/// The number of slots of the player inventory window.
+pub const SLOT_COUNT: usize = 46;
+
+/// The slot of the helmet. The chestplate, leggings and boots follow.
+pub const HELMET: usize = 5;
+
+/// The slot of the boots.
+pub const BOOTS: usize = 8;
+
+/// The first slot of the hotbar.
+pub const HOTBAR: usize = 36;
+
+/// The number of slots in the hotbar.
+pub const HOTBAR_SIZE: u8 = 9;
+
+/// The slot of the offhand.
+pub const OFFHAND: usize = 45;
+
+/// The window id of the player inventory.
+const WINDOW_ID: u8 = 0;
+
+/// The items of a player and the item they are holding with the cursor.
+#[derive(Component, Debug)]
+pub struct Inventory {
+    /// All slots of the player inventory window.
+    slots: [ItemStack; SLOT_COUNT],
+    /// The item held by the cursor while the inventory is open.
+    cursor: ItemStack,
+    /// The selected hotbar slot, ranging from `0` to `8`.
+    selected: u8,
+    /// Increases every time the server sends the inventory. The client sends it back when
+    /// clicking so that clicks on an outdated inventory are rejected.
+    state_id: i32,
+    /// Whether the items other players see changed since the last
+    /// [`Inventory::take_equipment_changed`].
+    equipment_changed: bool,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: std::array::from_fn(|_| ItemStack::EMPTY),
+            cursor: ItemStack::EMPTY,
+            selected: 0,
+            state_id: 0,
+            equipment_changed: false,
+        }
+    }
+}
+
+impl Inventory {
+    /// An inventory with a netherite sword and armor. Players get their gear from their kit instead,
+    /// see [`crate::singleton::kits`].
+    pub fn netherite() -> Self {
+        let mut inventory = Self::default();
+
+        inventory.set(HOTBAR, ItemStack::new(ItemKind::NetheriteSword, 1, None));
+        inventory.set(HELMET, ItemStack::new(ItemKind::NetheriteHelmet, 1, None));
+        inventory.set(
+            HELMET + 1,
+            ItemStack::new(ItemKind::NetheriteChestplate, 1, None),
+        );
+        inventory.set(
+            HELMET + 2,
+            ItemStack::new(ItemKind::NetheriteLeggings, 1, None),
+        );
+        inventory.set(BOOTS, ItemStack::new(ItemKind::NetheriteBoots, 1, None));
+
+        inventory
+    }
+
+    /// The item in a slot.
+    pub fn get(&self, slot: usize) -> &ItemStack {
+        &self.slots[slot]
+    }
+
+    /// Replaces the item in a slot. The client has to be sent the inventory afterwards, see
+    /// [`Inventory::sync_packet`].
+    pub fn set(&mut self, slot: usize, stack: ItemStack) {
+        self.equipment_changed |= self.is_equipment(slot);
+        self.slots[slot] = stack;
+    }
+
+    /// Removes one item from a slot. The client has to be sent the slot afterwards, see
+    /// [`Inventory::slot_packet`].
+    pub fn remove_one(&mut self, slot: usize) {
+        let mut stack = self.get(slot).clone();
+        stack.count -= 1;
+
+        if stack.count <= 0 {
+            stack = ItemStack::EMPTY;
+        }
+
+        self.set(slot, stack);
+    }
+
+    /// The slot of the first `item` like vanilla looks for arrows: the offhand, the main hand, the
+    /// hotbar and then the main inventory.
+    pub fn find(&self, item: ItemKind) -> Option<usize> {
+        [OFFHAND, self.main_hand_slot()]
+            .into_iter()
+            .chain(HOTBAR..OFFHAND)
+            .chain(BOOTS + 1..HOTBAR)
+            .find(|&slot| self.slots[slot].item == item && self.slots[slot].count > 0)
+    }
+
+    /// The selected hotbar slot.
+    pub const fn selected(&self) -> u8 {
+        self.selected
+    }
+
+    /// Selects a hotbar slot. Returns whether the item in the main hand changed.
+    pub fn select(&mut self, selected: u8) -> bool {
+        debug_assert!(selected < HOTBAR_SIZE);
+
+        let previous = self.main_hand().clone();
+        self.selected = selected;
+
+        let changed = previous != *self.main_hand();
+        self.equipment_changed |= changed;
+        changed
+    }
+
+    /// The slot of the item in the main hand.
+    pub fn main_hand_slot(&self) -> usize {
+        HOTBAR + usize::from(self.selected)
+    }
+
+    /// The item in the main hand.
+    pub fn main_hand(&self) -> &ItemStack {
+        &self.slots[self.main_hand_slot()]
+    }
+
+    /// The item in the offhand.
+    pub fn off_hand(&self) -> &ItemStack {
+        &self.slots[OFFHAND]
+    }
+
+    /// The armor from the boots to the helmet.
+    pub fn armor(&self) -> impl Iterator<Item = &ItemStack> {
+        self.slots[HELMET..=BOOTS].iter().rev()
+    }
+
+    /// The damage reduction of the armor.
+    pub fn defense(&self) -> Defense {
+        Defense::of(self.armor())
+    }
+
+    /// Whether other players see the item in the slot.
+    fn is_equipment(&self, slot: usize) -> bool {
+        matches!(slot, HELMET..=BOOTS | OFFHAND) || slot == self.main_hand_slot()
+    }
+
+    /// Returns whether the equipment changed since this was last called and resets it.
+    pub fn take_equipment_changed(&mut self) -> bool {
+        std::mem::take(&mut self.equipment_changed)
+    }
+
+    /// The equipment as sent to other players in the entity equipment update packet.
+    pub fn equipment(&self) -> Vec<EquipmentEntry> {
+        // 0: Mainhand
+        // 1: Offhand
+        // 2: Boots
+        // 3: Leggings
+        // 4: Chestplate
+        // 5: Helmet
+        let hands = [self.main_hand(), self.off_hand()];
+
+        hands
+            .into_iter()
+            .chain(self.armor())
+            .zip(0..)
+            .map(|(item, slot)| EquipmentEntry {
+                slot,
+                item: item.clone(),
+            })
+            .collect()
+    }
+
+    /// Applies a click of the client, which already moved the items on its side. Returns `false`
+    /// if the click is invalid and was not applied. The client has to be sent the inventory
+    /// then, see [`Inventory::sync_packet`].
+    ///
+    /// Vanilla simulates every kind of click on the server. Instead, this accepts the resulting
+    /// slots from the client as long as no items are created or destroyed, which also rejects
+    /// dropping items.
+    pub fn click(&mut self, pkt: &play::ClickSlotC2s) -> bool {
+        if pkt.window_id != WINDOW_ID || pkt.state_id.0 != self.state_id {
+            return false;
+        }
+
+        let changes: Vec<(usize, &ItemStack)> = pkt
+            .slot_changes
+            .iter()
+            .filter_map(|SlotChange { idx, stack }| Some((usize::try_from(*idx).ok()?, stack)))
+            .collect();
+
+        if changes.len() != pkt.slot_changes.len()
+            || !self.conserves_items(&changes, &pkt.carried_item)
+        {
+            return false;
+        }
+
+        for (slot, stack) in changes {
+            self.set(slot, stack.clone());
+        }
+
+        self.cursor = pkt.carried_item.clone();
+
+        true
+    }
+
+    /// Whether replacing the given slots and the cursor keeps the number of every item the same.
+    fn conserves_items(&self, changes: &[(usize, &ItemStack)], cursor: &ItemStack) -> bool {
+        for (i, &(slot, _)) in changes.iter().enumerate() {
+            // a slot which is changed twice would count its items twice
+            if slot >= SLOT_COUNT || changes[..i].iter().any(|&(other, _)| other == slot) {
+                return false;
+            }
+        }
+
+        let before: Vec<&ItemStack> = changes
+            .iter()
+            .map(|&(slot, _)| &self.slots[slot])
+            .chain(iter::once(&self.cursor))
+            .filter(|stack| !stack.is_empty())
+            .collect();
+
+        let after: Vec<&ItemStack> = changes
+            .iter()
+            .map(|&(_, stack)| stack)
+            .chain(iter::once(cursor))
+            .filter(|stack| !stack.is_empty())
+            .collect();
+
+        let total = |stacks: &[&ItemStack], stack: &ItemStack| -> i32 {
+            stacks
+                .iter()
+                .filter(|other| other.item == stack.item && other.nbt == stack.nbt)
+                .map(|other| i32::from(other.count))
+                .sum()
+        };
+
+        before
+            .iter()
+            .chain(&after)
+            .all(|stack| total(&before, stack) == total(&after, stack))
+    }
+
+    /// The packet which sends the whole inventory to the client after it changed. This increases
+    /// the state id, so clicks the client made before receiving it are rejected.
+    pub fn sync_packet(&mut self) -> play::InventoryS2c<'_> {
+        self.state_id = (self.state_id + 1) & 0x7FFF;
+        self.packet()
+    }
+
+    /// The packet which sends the whole inventory to the client.
+    pub fn packet(&self) -> play::InventoryS2c<'_> {
+        play::InventoryS2c {
+            window_id: WINDOW_ID,
+            state_id: VarInt(self.state_id),
+            slots: Cow::Borrowed(&self.slots),
+            carried_item: Cow::Borrowed(&self.cursor),
+        }
+    }
+
+    /// The packet which sends a single slot to the client.
+    pub fn slot_packet(&self, slot: usize) -> play::ScreenHandlerSlotUpdateS2c<'_> {
+        play::ScreenHandlerSlotUpdateS2c {
+            window_id: WINDOW_ID as i8,
+            state_id: VarInt(self.state_id),
+            slot_idx: slot as i16,
+            slot_data: Cow::Borrowed(&self.slots[slot]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(
+        state_id: i32,
+        changes: Vec<SlotChange>,
+        carried_item: ItemStack,
+    ) -> play::ClickSlotC2s<'static> {
+        play::ClickSlotC2s {
+            window_id: WINDOW_ID,
+            state_id: VarInt(state_id),
+            slot_idx: 0,
+            button: 0,
+            mode: play::click_slot_c2s::ClickMode::Click,
+            slot_changes: changes.into(),
+            carried_item,
+        }
+    }
+
+    fn sword() -> ItemStack {
+        ItemStack::new(ItemKind::NetheriteSword, 1, None)
+    }
+
+    #[test]
+    fn moving_an_item_is_accepted() {
+        let mut inventory = Inventory::netherite();
+        inventory.take_equipment_changed();
+
+        // pick up the sword, then put it into the main inventory
+        let pick_up = click(
+            0,
+            vec![SlotChange {
+                idx: HOTBAR as i16,
+                stack: ItemStack::EMPTY,
+            }],
+            sword(),
+        );
+
+        assert!(inventory.click(&pick_up));
+        assert!(inventory.main_hand().is_empty());
+        assert!(inventory.take_equipment_changed());
+
+        let put_down = click(
+            0,
+            vec![SlotChange {
+                idx: 9,
+                stack: sword(),
+            }],
+            ItemStack::EMPTY,
+        );
+
+        assert!(inventory.click(&put_down));
+        assert_eq!(*inventory.get(9), sword());
+        assert!(!inventory.take_equipment_changed());
+    }
+
+    #[test]
+    fn creating_items_is_rejected() {
+        let mut inventory = Inventory::netherite();
+
+        // copy the sword into another slot
+        let copy = click(
+            0,
+            vec![SlotChange {
+                idx: 9,
+                stack: sword(),
+            }],
+            ItemStack::EMPTY,
+        );
+
+        assert!(!inventory.click(&copy));
+        assert!(inventory.get(9).is_empty());
+
+        // change the same slot twice to duplicate the cursor
+        let twice = click(
+            0,
+            vec![
+                SlotChange {
+                    idx: HOTBAR as i16,
+                    stack: ItemStack::EMPTY,
+                },
+                SlotChange {
+                    idx: HOTBAR as i16,
+                    stack: ItemStack::EMPTY,
+                },
+            ],
+            ItemStack::new(ItemKind::NetheriteSword, 2, None),
+        );
+
+        assert!(!inventory.click(&twice));
+
+        // turn the sword into a different item
+        let swap = click(
+            0,
+            vec![SlotChange {
+                idx: HOTBAR as i16,
+                stack: ItemStack::new(ItemKind::DiamondSword, 1, None),
+            }],
+            ItemStack::EMPTY,
+        );
+
+        assert!(!inventory.click(&swap));
+        assert_eq!(*inventory.main_hand(), sword());
+    }
+
+    #[test]
+    fn outdated_clicks_are_rejected() {
+        let mut inventory = Inventory::netherite();
+        inventory.sync_packet();
+
+        let pick_up = click(
+            0,
+            vec![SlotChange {
+                idx: HOTBAR as i16,
+                stack: ItemStack::EMPTY,
+            }],
+            sword(),
+        );
+
+        assert!(!inventory.click(&pick_up));
+    }
+
+    #[test]
+    fn arrows_are_found_in_the_offhand_first() {
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.find(ItemKind::Arrow), None);
+
+        inventory.set(9, ItemStack::new(ItemKind::Arrow, 2, None));
+        assert_eq!(inventory.find(ItemKind::Arrow), Some(9));
+
+        inventory.set(HOTBAR + 3, ItemStack::new(ItemKind::Arrow, 1, None));
+        assert_eq!(inventory.find(ItemKind::Arrow), Some(HOTBAR + 3));
+
+        inventory.set(OFFHAND, ItemStack::new(ItemKind::Arrow, 1, None));
+        assert_eq!(inventory.find(ItemKind::Arrow), Some(OFFHAND));
+
+        inventory.remove_one(OFFHAND);
+        inventory.remove_one(HOTBAR + 3);
+        assert!(inventory.off_hand().is_empty());
+
+        inventory.remove_one(9);
+        assert_eq!(inventory.get(9).count, 1);
+        assert_eq!(inventory.find(ItemKind::Arrow), Some(9));
+    }
+
+    #[test]
+    fn armor_is_ordered_from_the_boots() {
+        let inventory = Inventory::netherite();
+
+        let armor: Vec<_> = inventory.armor().map(|stack| stack.item).collect();
+        assert_eq!(
+            armor,
+            [
+                ItemKind::NetheriteBoots,
+                ItemKind::NetheriteLeggings,
+                ItemKind::NetheriteChestplate,
+                ItemKind::NetheriteHelmet,
+            ]
+        );
+
+        let equipment = inventory.equipment();
+        assert_eq!(equipment[0].slot, 0);
+        assert_eq!(equipment[0].item, sword());
+        assert_eq!(equipment[5].item.item, ItemKind::NetheriteHelmet);
+    }
+}
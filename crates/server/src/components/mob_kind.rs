@@ -0,0 +1,172 @@
+//! The kinds of [`MinecraftEntity`](super::MinecraftEntity) the server can spawn.
+
+use bvh::aabb::Aabb;
+use evenio::component::Component;
+use generator::EntityType;
+use glam::Vec3;
+use valence_protocol::ItemKind;
+
+/// How a mob reacts to players.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Behavior {
+    /// Chases the closest player and hurts them on contact.
+    Melee,
+    /// Approaches the closest player until it is within [`RANGED_DISTANCE`] blocks and keeps its
+    /// distance from there. Shoots arrows at them every [`RANGED_COOLDOWN`] ticks once they are
+    /// within [`RANGED_ATTACK_DISTANCE`] blocks.
+    Ranged,
+    /// Ignores players and wanders around.
+    Passive,
+}
+
+/// The distance in blocks that [`Behavior::Ranged`] mobs keep from their target.
+pub const RANGED_DISTANCE: f32 = 10.0;
+
+/// The distance in blocks from which [`Behavior::Ranged`] mobs shoot, like vanilla skeletons.
+pub const RANGED_ATTACK_DISTANCE: f32 = 15.0;
+
+/// The number of ticks between two shots of a [`Behavior::Ranged`] mob, like vanilla skeletons on
+/// normal difficulty.
+pub const RANGED_COOLDOWN: i64 = 40;
+
+/// The speed of arrows shot by [`Behavior::Ranged`] mobs in blocks per tick.
+pub const RANGED_ARROW_SPEED: f32 = 1.6;
+
+/// The kind of a mob. Sizes, speeds and health are taken from vanilla. Speeds are in blocks per
+/// tick and relative to a zombie walking at `0.1`.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MobKind {
+    Zombie,
+    Husk,
+    Skeleton,
+    Spider,
+    Creeper,
+    Pig,
+    Cow,
+    Sheep,
+    Chicken,
+}
+
+impl MobKind {
+    /// All mob kinds.
+    pub const ALL: [Self; 9] = [
+        Self::Zombie,
+        Self::Husk,
+        Self::Skeleton,
+        Self::Spider,
+        Self::Creeper,
+        Self::Pig,
+        Self::Cow,
+        Self::Sheep,
+        Self::Chicken,
+    ];
+
+    /// The name used in commands, i.e., `zombie`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Zombie => "zombie",
+            Self::Husk => "husk",
+            Self::Skeleton => "skeleton",
+            Self::Spider => "spider",
+            Self::Creeper => "creeper",
+            Self::Pig => "pig",
+            Self::Cow => "cow",
+            Self::Sheep => "sheep",
+            Self::Chicken => "chicken",
+        }
+    }
+
+    /// Looks up a mob kind by its [`MobKind::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The entity type sent to clients.
+    pub const fn entity_type(self) -> EntityType {
+        match self {
+            Self::Zombie => EntityType::Zombie,
+            Self::Husk => EntityType::Husk,
+            Self::Skeleton => EntityType::Skeleton,
+            Self::Spider => EntityType::Spider,
+            Self::Creeper => EntityType::Creeper,
+            Self::Pig => EntityType::Pig,
+            Self::Cow => EntityType::Cow,
+            Self::Sheep => EntityType::Sheep,
+            Self::Chicken => EntityType::Chicken,
+        }
+    }
+
+    /// The width and height of the bounding box.
+    pub const fn size(self) -> (f32, f32) {
+        match self {
+            Self::Zombie | Self::Husk => (0.6, 1.95),
+            Self::Skeleton => (0.6, 1.99),
+            Self::Spider => (1.4, 0.9),
+            Self::Creeper => (0.6, 1.7),
+            Self::Pig => (0.9, 0.9),
+            Self::Cow => (0.9, 1.4),
+            Self::Sheep => (0.9, 1.3),
+            Self::Chicken => (0.4, 0.7),
+        }
+    }
+
+    /// The bounding box of the mob standing at `feet`.
+    pub fn bounding_box(self, feet: Vec3) -> Aabb {
+        let (width, height) = self.size();
+        Aabb::create(feet, width, height)
+    }
+
+    /// The mean walking speed in blocks per tick.
+    pub const fn speed(self) -> f32 {
+        match self {
+            Self::Zombie | Self::Husk | Self::Sheep => 0.1,
+            Self::Skeleton | Self::Creeper | Self::Pig | Self::Chicken => 0.11,
+            Self::Spider => 0.13,
+            Self::Cow => 0.09,
+        }
+    }
+
+    /// The health the mob spawns with, measured in half hearts.
+    pub const fn max_health(self) -> f32 {
+        match self {
+            Self::Zombie | Self::Husk | Self::Skeleton | Self::Creeper => 20.0,
+            Self::Spider => 16.0,
+            Self::Pig | Self::Cow => 10.0,
+            Self::Sheep => 8.0,
+            Self::Chicken => 4.0,
+        }
+    }
+
+    /// The damage the mob deals to players it touches, measured in half hearts. Ranged mobs deal
+    /// their damage with arrows instead.
+    pub const fn attack_damage(self) -> f32 {
+        match self {
+            Self::Zombie | Self::Husk | Self::Creeper => 3.0,
+            Self::Spider => 2.0,
+            Self::Skeleton | Self::Pig | Self::Cow | Self::Sheep | Self::Chicken => 0.0,
+        }
+    }
+
+    /// How the mob reacts to players.
+    pub const fn behavior(self) -> Behavior {
+        match self {
+            Self::Zombie | Self::Husk | Self::Spider | Self::Creeper => Behavior::Melee,
+            Self::Skeleton => Behavior::Ranged,
+            Self::Pig | Self::Cow | Self::Sheep | Self::Chicken => Behavior::Passive,
+        }
+    }
+
+    /// The item the mob drops when it dies.
+    pub const fn drop(self) -> ItemKind {
+        match self {
+            Self::Zombie | Self::Husk => ItemKind::RottenFlesh,
+            Self::Skeleton => ItemKind::Bone,
+            Self::Spider => ItemKind::String,
+            Self::Creeper => ItemKind::Gunpowder,
+            Self::Pig => ItemKind::Porkchop,
+            Self::Cow => ItemKind::Beef,
+            Self::Sheep => ItemKind::WhiteWool,
+            Self::Chicken => ItemKind::Feather,
+        }
+    }
+}
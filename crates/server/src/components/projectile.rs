@@ -0,0 +1,327 @@
+//! Arrows, snowballs and ender pearls in flight.
+//!
+//! Players shoot arrows by drawing a bow and releasing it, and throw snowballs and ender pearls by
+//! using them. Like in vanilla, a projectile moves by its velocity every tick before drag slows it
+//! down and gravity pulls it down. `system::projectile_physics` checks the segment it moves along
+//! for blocks and entities. Arrows which hit a block stick in it, ender pearls teleport their
+//! thrower and everything else breaks on impact.
+use evenio::{component::Component, entity::EntityId};
+use generator::EntityType;
+use glam::Vec3;
+use valence_protocol::ItemKind;
+
+use crate::components::damage::DamageCause;
+
+/// The factor the velocity of a projectile is multiplied by each tick.
+pub const DRAG: f32 = 0.99;
+
+/// The speed of a fully drawn bow in blocks per tick.
+pub const MAX_BOW_SPEED: f32 = 3.0;
+
+/// The speed of thrown snowballs and ender pearls in blocks per tick.
+pub const THROW_SPEED: f32 = 1.5;
+
+/// The least power a bow has to be drawn with to shoot, like vanilla.
+pub const MIN_BOW_POWER: f32 = 0.1;
+
+/// The damage of an arrow per block per tick of speed, measured in half hearts.
+pub const ARROW_DAMAGE: f32 = 2.0;
+
+/// The damage a player takes when they land after an ender pearl teleport.
+pub const PEARL_DAMAGE: f32 = 5.0;
+
+/// How far boxes of entities are grown when checking for hits. Like vanilla, this makes
+/// projectiles easier to hit with.
+pub const HIT_MARGIN: f32 = 0.3;
+
+/// The number of ticks a projectile cannot hit the entity which shot it, so it does not hit them
+/// right after it left their hand.
+pub const OWNER_GRACE_TICKS: i64 = 5;
+
+/// The number of ticks after which projectiles despawn, both in flight and stuck in a block.
+pub const LIFETIME: i64 = 1200;
+
+/// The height of the eyes of a player above their feet. Projectiles start just below them.
+pub const EYE_HEIGHT: f32 = 1.62;
+
+/// What a projectile is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProjectileKind {
+    /// An arrow shot from a bow.
+    Arrow,
+    /// A thrown snowball, which only knocks back.
+    Snowball,
+    /// A thrown ender pearl, which teleports the thrower where it lands.
+    EnderPearl,
+}
+
+impl ProjectileKind {
+    /// The projectile thrown when a player uses `item`, if any.
+    pub const fn thrown(item: ItemKind) -> Option<Self> {
+        match item {
+            ItemKind::Snowball => Some(Self::Snowball),
+            ItemKind::EnderPearl => Some(Self::EnderPearl),
+            _ => None,
+        }
+    }
+
+    /// The acceleration of the projectile towards the ground in blocks per tick squared.
+    pub const fn gravity(self) -> f32 {
+        match self {
+            Self::Arrow => 0.05,
+            Self::Snowball | Self::EnderPearl => 0.03,
+        }
+    }
+
+    /// The type of the entity shown to clients.
+    pub const fn entity_type(self) -> EntityType {
+        match self {
+            Self::Arrow => EntityType::Arrow,
+            Self::Snowball => EntityType::Snowball,
+            Self::EnderPearl => EntityType::EnderPearl,
+        }
+    }
+
+    /// What deals the damage when the projectile hits.
+    pub const fn cause(self) -> DamageCause {
+        match self {
+            Self::Arrow => DamageCause::Arrow,
+            Self::Snowball | Self::EnderPearl => DamageCause::Thrown,
+        }
+    }
+}
+
+/// A bow a player is drawing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BowDraw {
+    /// The slot of the bow.
+    pub slot: usize,
+    /// The tick the player started drawing.
+    pub start_tick: i64,
+}
+
+/// The power of a bow drawn for `ticks` ticks, from `0.0` to `1.0` once it is fully drawn after
+/// a second. Like vanilla, it grows quadratically.
+pub fn bow_power(ticks: i64) -> f32 {
+    let seconds = ticks.max(0) as f32 / 20.0;
+    ((seconds * seconds + seconds * 2.0) / 3.0).min(1.0)
+}
+
+/// The direction a player with the given yaw and pitch in degrees looks in.
+pub fn look_direction(yaw: f32, pitch: f32) -> Vec3 {
+    let (yaw, pitch) = (yaw.to_radians(), pitch.to_radians());
+    Vec3::new(
+        -yaw.sin() * pitch.cos(),
+        -pitch.sin(),
+        yaw.cos() * pitch.cos(),
+    )
+}
+
+/// The velocity of an arrow shot from `from` at `to` with `speed`. Like vanilla skeletons, it is
+/// aimed a little above the target to make up for gravity over longer distances.
+pub fn aim(from: Vec3, to: Vec3, speed: f32) -> Vec3 {
+    let dif = to - from;
+    let horizontal = dif.x.hypot(dif.z);
+
+    Vec3::new(dif.x, horizontal.mul_add(0.2, dif.y), dif.z).normalize_or_zero() * speed
+}
+
+/// See [`crate::components::projectile`].
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Projectile {
+    /// What the projectile is.
+    pub kind: ProjectileKind,
+    /// The entity which shot the projectile.
+    pub owner: EntityId,
+    /// The UUID clients know the projectile by.
+    pub uuid: uuid::Uuid,
+    /// The position of the projectile.
+    pub position: Vec3,
+    /// The velocity of the projectile in blocks per tick.
+    pub velocity: Vec3,
+    /// The item the projectile was shot with, i.e., a bow.
+    pub weapon: Option<ItemKind>,
+    /// Whether the projectile was shot from a fully drawn bow and deals extra damage.
+    pub critical: bool,
+    /// Whether the projectile is stuck in a block.
+    pub stuck: bool,
+    /// The tick the projectile was shot.
+    pub spawn_tick: i64,
+    /// The tick the projectile despawns.
+    pub despawn_tick: i64,
+}
+
+impl Projectile {
+    /// A projectile shot by `owner` on `tick`.
+    pub fn new(
+        kind: ProjectileKind,
+        owner: EntityId,
+        position: Vec3,
+        velocity: Vec3,
+        tick: i64,
+    ) -> Self {
+        Self {
+            kind,
+            owner,
+            uuid: uuid::Uuid::new_v4(),
+            position,
+            velocity,
+            weapon: None,
+            critical: false,
+            stuck: false,
+            spawn_tick: tick,
+            despawn_tick: tick + LIFETIME,
+        }
+    }
+
+    /// Whether the projectile can hit `target` on `tick`.
+    pub fn can_hit(&self, target: EntityId, tick: i64) -> bool {
+        target != self.owner || tick - self.spawn_tick >= OWNER_GRACE_TICKS
+    }
+
+    /// The damage of a hit before critical hits. Arrows deal more damage the faster they are, thrown
+    /// projectiles only knock back.
+    pub fn damage(&self) -> f32 {
+        match self.kind {
+            ProjectileKind::Arrow => (self.velocity.length() * ARROW_DAMAGE).ceil(),
+            ProjectileKind::Snowball | ProjectileKind::EnderPearl => 0.0,
+        }
+    }
+
+    /// Moves the projectile by its velocity and applies drag and gravity.
+    pub fn fly(&mut self) {
+        self.position += self.velocity;
+        self.velocity *= DRAG;
+        self.velocity.y -= self.kind.gravity();
+    }
+
+    /// Sticks the projectile in a block it hit at `position` on `tick`.
+    pub fn stick(&mut self, position: Vec3, tick: i64) {
+        // like vanilla, back off a little so the arrow pokes out of the block
+        self.position = position - self.velocity.normalize_or_zero() * 0.05;
+        self.velocity = Vec3::ZERO;
+        self.stuck = true;
+        self.despawn_tick = tick + LIFETIME;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evenio::world::World;
+
+    use super::*;
+    use crate::{
+        config::Config,
+        singleton::{
+            phase::Phase,
+            teams::{Team, TeamConfig, Teams},
+        },
+    };
+
+    fn team(name: &str) -> TeamConfig {
+        TeamConfig {
+            name: name.to_owned(),
+            color: name.to_owned(),
+            prefix: String::new(),
+        }
+    }
+
+    #[test]
+    fn bows_are_fully_drawn_after_a_second() {
+        assert!(bow_power(0).abs() < f32::EPSILON);
+        assert!(bow_power(1) < MIN_BOW_POWER);
+        assert!(bow_power(2) > MIN_BOW_POWER);
+        assert!((bow_power(10) - 5.0 / 12.0).abs() < 1.0e-6);
+        assert!((bow_power(20) - 1.0).abs() < f32::EPSILON);
+        assert!((bow_power(100) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn arrows_fall_like_vanilla() {
+        let mut arrow = Projectile::new(
+            ProjectileKind::Arrow,
+            EntityId::NULL,
+            Vec3::ZERO,
+            Vec3::new(MAX_BOW_SPEED, 0.0, 0.0),
+            0,
+        );
+
+        assert!((arrow.damage() - 6.0).abs() < f32::EPSILON);
+
+        arrow.fly();
+        assert_eq!(arrow.position, Vec3::new(3.0, 0.0, 0.0));
+        assert!((arrow.velocity.x - 2.97).abs() < 1.0e-6);
+        assert!((arrow.velocity.y + 0.05).abs() < 1.0e-6);
+
+        arrow.fly();
+        assert!((arrow.position.y + 0.05).abs() < 1.0e-6);
+        assert!((arrow.velocity.y - (-0.05 * DRAG - 0.05)).abs() < 1.0e-6);
+
+        arrow.stick(Vec3::new(10.0, 0.0, 0.0), 5);
+        assert!(arrow.stuck);
+        assert_eq!(arrow.velocity, Vec3::ZERO);
+        assert!(arrow.position.x < 10.0);
+        assert_eq!(arrow.despawn_tick, 5 + LIFETIME);
+    }
+
+    #[test]
+    fn owners_are_only_hit_after_the_grace_period() {
+        let pearl = Projectile::new(
+            ProjectileKind::EnderPearl,
+            EntityId::NULL,
+            Vec3::ZERO,
+            Vec3::Y,
+            10,
+        );
+
+        assert!(!pearl.can_hit(EntityId::NULL, 10));
+        assert!(pearl.can_hit(EntityId::NULL, 10 + OWNER_GRACE_TICKS));
+        assert!(pearl.damage().abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn arrows_hurt_players_like_their_shooter() {
+        let config = Config {
+            teams: vec![team("red"), team("blue")],
+            ..Config::default()
+        };
+        let teams = Teams::from_config(&config).unwrap();
+        let (red, blue) = (Some(Team(Some(0))), Some(Team(Some(1))));
+
+        let mut world = World::new();
+        let shooter = world.spawn();
+
+        let arrow = Projectile::new(
+            ProjectileKind::Arrow,
+            shooter,
+            Vec3::ZERO,
+            Vec3::new(MAX_BOW_SPEED, 0.0, 0.0),
+            0,
+        );
+
+        // `system::damage_entity` takes the team of the entity the damage is from, the shooter
+        let attacker = if arrow.owner == shooter { red } else { None };
+
+        assert!(teams.can_damage(Phase::Battle, attacker, blue));
+        assert!(!teams.can_damage(Phase::Battle, attacker, red));
+        assert!(!teams.can_damage(Phase::Lobby, attacker, blue));
+        assert!(teams.can_damage(Phase::Lobby, attacker, None));
+    }
+
+    #[test]
+    fn arrows_are_aimed_above_distant_targets() {
+        let velocity = aim(Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0), 1.6);
+        assert!((velocity.length() - 1.6).abs() < 1.0e-6);
+        assert!(velocity.y > 0.0);
+
+        let velocity = aim(Vec3::ZERO, Vec3::new(0.0, -5.0, 0.0), 1.6);
+        assert!(velocity.abs_diff_eq(Vec3::new(0.0, -1.6, 0.0), 1.0e-6));
+    }
+
+    #[test]
+    fn players_look_along_z_by_default() {
+        assert!(look_direction(0.0, 0.0).abs_diff_eq(Vec3::Z, 1.0e-6));
+        assert!(look_direction(90.0, 0.0).abs_diff_eq(Vec3::NEG_X, 1.0e-6));
+        assert!(look_direction(0.0, -90.0).abs_diff_eq(Vec3::Y, 1.0e-6));
+    }
+}
@@ -0,0 +1,349 @@
+//! The status effects of an entity, like regeneration from a golden apple.
+//!
+//! Effects are added to [`StatusEffects`] and `system::status_effects` applies them every tick,
+//! removes them once they run out and syncs them to clients.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use evenio::component::Component;
+use serde::{Deserialize, Serialize};
+
+/// How much faster speed makes an entity per level, like vanilla.
+pub const SPEED_PER_LEVEL: f32 = 0.2;
+
+/// A kind of status effect.
+#[derive(
+    Serialize,
+    Deserialize,
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    /// Makes the entity faster by [`SPEED_PER_LEVEL`] per level. Players are sent it as a
+    /// modifier of their movement speed.
+    Speed,
+    /// Increases melee damage.
+    Strength,
+    /// Heals once when it is added.
+    InstantHealth,
+    /// Heals over time.
+    Regeneration,
+    /// Reduces all damage by a fifth per level.
+    Resistance,
+    /// Has no effect yet since there is no fire damage, but is shown to the player.
+    FireResistance,
+    /// Deals damage over time, but never kills.
+    Poison,
+    /// Gives extra health which is lost before the normal health.
+    Absorption,
+}
+
+impl EffectKind {
+    /// The id of the effect in the protocol.
+    pub const fn id(self) -> i32 {
+        match self {
+            Self::Speed => 1,
+            Self::Strength => 5,
+            Self::InstantHealth => 6,
+            Self::Regeneration => 10,
+            Self::Resistance => 11,
+            Self::FireResistance => 12,
+            Self::Poison => 19,
+            Self::Absorption => 22,
+        }
+    }
+
+    /// The color of the particles of the effect.
+    pub const fn color(self) -> u32 {
+        match self {
+            Self::Speed => 0x7C_AF_C6,
+            Self::Strength => 0x93_24_23,
+            Self::InstantHealth => 0xF8_24_23,
+            Self::Regeneration => 0xCD_5C_AB,
+            Self::Resistance => 0x99_45_3A,
+            Self::FireResistance => 0xE4_9A_3A,
+            Self::Poison => 0x4E_93_31,
+            Self::Absorption => 0x25_52_A5,
+        }
+    }
+
+    /// Whether the effect is applied once instead of over its duration.
+    pub const fn is_instant(self) -> bool {
+        matches!(self, Self::InstantHealth)
+    }
+
+    /// The interval in ticks between two heals of regeneration or damages of poison. Higher
+    /// levels act more often.
+    pub const fn interval(self, amplifier: u8) -> Option<i64> {
+        let base = match self {
+            Self::Regeneration => 50,
+            Self::Poison => 25,
+            _ => return None,
+        };
+
+        // the interval is never shorter than a tick
+        let interval = if amplifier >= 6 { 0 } else { base >> amplifier };
+        Some(if interval == 0 { 1 } else { interval })
+    }
+}
+
+/// An effect with a level and a duration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    /// The level of the effect minus one, so `0` is level I.
+    pub amplifier: u8,
+    /// The tick the effect runs out.
+    pub end_tick: i64,
+    /// Whether the effect comes from a beacon, which makes its particles less visible.
+    pub ambient: bool,
+    /// Whether the entity emits particles in the color of the effect.
+    pub particles: bool,
+    /// Whether the player sees an icon of the effect.
+    pub icon: bool,
+}
+
+impl StatusEffect {
+    /// An effect with particles and an icon which lasts `duration` ticks from `tick`.
+    pub const fn new(amplifier: u8, tick: i64, duration: i64) -> Self {
+        Self {
+            amplifier,
+            end_tick: tick + duration,
+            ambient: false,
+            particles: true,
+            icon: true,
+        }
+    }
+
+    /// The level of the effect, `1` for level I.
+    pub fn level(&self) -> u32 {
+        u32::from(self.amplifier) + 1
+    }
+}
+
+/// The status effects an entity has, see [`crate::components::status_effects`].
+#[derive(Component, Debug, Default)]
+pub struct StatusEffects {
+    /// The active effects.
+    effects: BTreeMap<EffectKind, StatusEffect>,
+    /// The effects which were added, changed or removed and have to be synced to clients.
+    changed: BTreeSet<EffectKind>,
+    /// The effects which were added and have not been started yet, i.e., instant health which has
+    /// not healed yet or absorption which has not given its extra health yet.
+    started: BTreeSet<EffectKind>,
+}
+
+impl StatusEffects {
+    /// Adds an effect. Like vanilla, an effect the entity already has is only replaced by a
+    /// higher level or, at the same level, a longer duration. Returns whether it was added.
+    pub fn add(&mut self, kind: EffectKind, effect: StatusEffect) -> bool {
+        if let Some(current) = self.effects.get(&kind) {
+            let stronger = effect.amplifier > current.amplifier;
+            let longer =
+                effect.amplifier == current.amplifier && effect.end_tick > current.end_tick;
+
+            if !stronger && !longer {
+                return false;
+            }
+        }
+
+        self.effects.insert(kind, effect);
+        self.changed.insert(kind);
+        self.started.remove(&kind);
+
+        true
+    }
+
+    /// Removes an effect. Returns whether the entity had it.
+    pub fn remove(&mut self, kind: EffectKind) -> bool {
+        let removed = self.effects.remove(&kind).is_some();
+
+        if removed {
+            self.changed.insert(kind);
+        }
+
+        removed
+    }
+
+    /// Removes all effects, i.e., when a player respawns.
+    pub fn clear(&mut self) {
+        self.changed.extend(self.effects.keys());
+        self.effects.clear();
+    }
+
+    /// The effect of the given kind, if the entity has it.
+    pub fn get(&self, kind: EffectKind) -> Option<&StatusEffect> {
+        self.effects.get(&kind)
+    }
+
+    /// The level of the effect, `0` if the entity does not have it.
+    pub fn level(&self, kind: EffectKind) -> u32 {
+        self.get(kind).map_or(0, StatusEffect::level)
+    }
+
+    /// The factor the movement speed of the entity is multiplied by, i.e., `1.2` with speed I.
+    pub fn speed_multiplier(&self) -> f32 {
+        (self.level(EffectKind::Speed) as f32).mul_add(SPEED_PER_LEVEL, 1.0)
+    }
+
+    /// All active effects.
+    pub fn iter(&self) -> impl Iterator<Item = (EffectKind, &StatusEffect)> {
+        self.effects.iter().map(|(kind, effect)| (*kind, effect))
+    }
+
+    /// Removes the effects which ran out on `tick` and returns their kinds.
+    pub fn expire(&mut self, tick: i64) -> Vec<EffectKind> {
+        let expired: Vec<_> = self
+            .effects
+            .iter()
+            .filter(|(kind, effect)| tick >= effect.end_tick && !kind.is_instant())
+            .map(|(kind, _)| *kind)
+            .collect();
+
+        for kind in &expired {
+            self.remove(*kind);
+        }
+
+        expired
+    }
+
+    /// Returns the effects which were added since this was last called. Instant effects are
+    /// removed since they only apply once.
+    pub fn start(&mut self) -> Vec<(EffectKind, StatusEffect)> {
+        let started: Vec<_> = self
+            .effects
+            .iter()
+            .filter(|(kind, _)| !self.started.contains(kind))
+            .map(|(kind, effect)| (*kind, *effect))
+            .collect();
+
+        for (kind, _) in &started {
+            if kind.is_instant() {
+                self.effects.remove(kind);
+            } else {
+                self.started.insert(*kind);
+            }
+        }
+
+        started
+    }
+
+    /// Returns the effects which changed since this was last called.
+    pub fn take_changed(&mut self) -> BTreeSet<EffectKind> {
+        self.started.retain(|kind| self.effects.contains_key(kind));
+        std::mem::take(&mut self.changed)
+    }
+
+    /// The color of the particles of the entity, mixed from all effects with particles weighted
+    /// by their level. `None` if no effect shows particles.
+    pub fn particle_color(&self) -> Option<i32> {
+        let mut rgb = [0; 3];
+        let mut total = 0;
+
+        for (kind, effect) in self.iter().filter(|(_, effect)| effect.particles) {
+            let color = kind.color();
+            let weight = effect.level();
+
+            rgb[0] += (color >> 16 & 0xFF) * weight;
+            rgb[1] += (color >> 8 & 0xFF) * weight;
+            rgb[2] += (color & 0xFF) * weight;
+            total += weight;
+        }
+
+        if total == 0 {
+            return None;
+        }
+
+        let [r, g, b] = rgb.map(|channel| channel / total);
+        i32::try_from(r << 16 | g << 8 | b).ok()
+    }
+
+    /// Whether all effects with particles are ambient.
+    pub fn is_ambient(&self) -> bool {
+        self.iter()
+            .filter(|(_, effect)| effect.particles)
+            .all(|(_, effect)| effect.ambient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weaker_effects_do_not_replace_stronger_ones() {
+        let mut effects = StatusEffects::default();
+
+        assert!(effects.add(EffectKind::Regeneration, StatusEffect::new(1, 0, 100)));
+        assert!(!effects.add(EffectKind::Regeneration, StatusEffect::new(0, 0, 400)));
+        assert!(!effects.add(EffectKind::Regeneration, StatusEffect::new(1, 0, 50)));
+        assert!(effects.add(EffectKind::Regeneration, StatusEffect::new(1, 0, 200)));
+
+        assert_eq!(effects.level(EffectKind::Regeneration), 2);
+        assert_eq!(effects.get(EffectKind::Regeneration).unwrap().end_tick, 200);
+    }
+
+    #[test]
+    fn effects_start_once_and_expire() {
+        let mut effects = StatusEffects::default();
+        effects.add(EffectKind::InstantHealth, StatusEffect::new(0, 0, 1));
+        effects.add(EffectKind::Absorption, StatusEffect::new(0, 0, 10));
+
+        let started: Vec<_> = effects.start().into_iter().map(|(kind, _)| kind).collect();
+        assert_eq!(started, [EffectKind::InstantHealth, EffectKind::Absorption]);
+        assert!(effects.start().is_empty());
+
+        // instant effects are gone after they started
+        assert_eq!(effects.level(EffectKind::InstantHealth), 0);
+
+        assert!(effects.expire(9).is_empty());
+        assert_eq!(effects.expire(10), [EffectKind::Absorption]);
+
+        let changed = effects.take_changed();
+        assert!(changed.contains(&EffectKind::Absorption));
+        assert!(changed.contains(&EffectKind::InstantHealth));
+        assert!(effects.take_changed().is_empty());
+    }
+
+    #[test]
+    fn higher_levels_act_more_often() {
+        assert_eq!(EffectKind::Regeneration.interval(0), Some(50));
+        assert_eq!(EffectKind::Regeneration.interval(1), Some(25));
+        assert_eq!(EffectKind::Poison.interval(5), Some(1));
+        assert_eq!(EffectKind::Poison.interval(200), Some(1));
+        assert_eq!(EffectKind::Speed.interval(0), None);
+    }
+
+    #[test]
+    fn speed_makes_entities_faster_per_level() {
+        let mut effects = StatusEffects::default();
+        assert!((effects.speed_multiplier() - 1.0).abs() < f32::EPSILON);
+
+        effects.add(EffectKind::Speed, StatusEffect::new(1, 0, 100));
+        assert!((effects.speed_multiplier() - 1.4).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn particle_colors_are_mixed() {
+        let mut effects = StatusEffects::default();
+        assert_eq!(effects.particle_color(), None);
+
+        effects.add(EffectKind::Poison, StatusEffect::new(0, 0, 100));
+        assert_eq!(effects.particle_color(), Some(0x4E_93_31));
+
+        effects.add(
+            EffectKind::Speed,
+            StatusEffect {
+                particles: false,
+                ..StatusEffect::new(0, 0, 100)
+            },
+        );
+        assert_eq!(effects.particle_color(), Some(0x4E_93_31));
+    }
+}
@@ -1,11 +1,23 @@
 //! Configuration for the server.
 
-use std::{fmt::Debug, fs::File, io::Read, path::Path};
+use std::{collections::BTreeMap, fmt::Debug, fs::File, io::Read, path::Path};
 
+use anyhow::ensure;
 use serde::{Deserialize, Serialize};
 use spin::lazy::Lazy;
 use tracing::{info, instrument, warn};
 
+use crate::{
+    combat::Ruleset,
+    singleton::{
+        border::BorderStage,
+        kits::{self, KitConfig},
+        registries::{Biome, DamageType, DimensionType},
+        spawns::SpawnArea,
+        teams::TeamConfig,
+    },
+};
+
 /// The configuration for the server.
 ///
 /// todo: remove static and make this an `Arc` to prevent weird behavior with multiple `Game`s
@@ -14,27 +26,149 @@ pub static CONFIG: Lazy<Config> = Lazy::new(|| Config::load("run/config.toml").u
 /// The configuration for the server representing a `toml` file.
 #[allow(clippy::missing_docs_in_private_items, reason = "self-explanatory")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
 pub struct Config {
     pub border_diameter: Option<f64>,
+    /// The x and z coordinates of the center of the border.
+    pub border_center: [f64; 2],
+    /// The stages in which the border shrinks during the battle.
+    pub border_stages: Vec<BorderStage>,
+    /// The damage players take for every block they are outside the safe zone of the border.
+    pub border_damage_per_block: f64,
+    /// The distance players can be outside the border without taking damage.
+    pub border_safe_zone: f64,
+    /// The distance to the border at which the screen of players turns red.
+    pub border_warning_blocks: i32,
+    /// The number of seconds before a shrinking border reaches players at which their screen turns
+    /// red.
+    pub border_warning_time: i32,
     pub max_players: i32,
     pub view_distance: i32,
     pub simulation_distance: i32,
     pub server_desc: String,
+    /// The biome of all generated chunks. Uses the default biome if not set.
+    pub world_biome: Option<String>,
+    pub dimension: DimensionType,
+    /// Custom biomes added to (or replacing entries of) the biome registry.
+    pub biomes: Vec<Biome>,
+    /// Custom damage types added to (or replacing entries of) the damage type registry.
+    pub damage_types: Vec<DamageType>,
+    /// The maximum number of nodes all path searches may expand per tick.
+    pub pathfinding_budget: usize,
+    /// The maximum number of nodes a single path search may expand.
+    pub pathfinding_max_nodes: usize,
+    /// Whether mobs drop an item when they die.
+    pub mob_drops: bool,
+    /// The combat rules, either `modern` (1.9+ with an attack cooldown) or `legacy` (1.8).
+    pub combat: Ruleset,
+    /// The teams players are split into. Without teams, everyone fights everyone.
+    pub teams: Vec<TeamConfig>,
+    /// Whether players can hurt their teammates.
+    pub friendly_fire: bool,
+    /// The kits players can be given.
+    pub kits: Vec<KitConfig>,
+    /// The kit of players who do not get a kit from their team or the round. Uses the first kit
+    /// if not set.
+    pub default_kit: Option<String>,
+    /// The kits of teams by the name of the team.
+    pub team_kits: BTreeMap<String, String>,
+    /// The kit of every round. Rounds after the last one use the kit of the last round.
+    pub round_kits: Vec<String>,
+    /// The names of the players who may use admin commands like `/kit`.
+    pub admins: Vec<String>,
+    /// The area players spawn in if their team has no spawn points.
+    pub spawn_area: SpawnArea,
+    /// The spawn points of teams by the name of the team. Players spawn at a random one.
+    pub team_spawns: BTreeMap<String, Vec<[f32; 3]>>,
+    /// The number of ticks dead players wait until they respawn.
+    pub respawn_delay: i64,
+    /// The number of ticks players cannot be hurt after they spawned.
+    pub spawn_protection: i64,
+    /// The number of players needed to start the countdown in the lobby.
+    pub lobby_players: usize,
+    /// Where players wait in the lobby and the countdown. Players spawn like in the battle if not
+    /// set.
+    pub lobby_spawn: Option<[f32; 3]>,
+    /// The number of ticks of the countdown before the battle.
+    pub countdown_ticks: i64,
+    /// The number of ticks the battle lasts. The battle only ends with `/phase end` if not set.
+    pub battle_ticks: Option<i64>,
+    /// The number of ticks the winners are announced before the next round starts in the lobby.
+    pub end_ticks: i64,
+    /// The file joins, leaves, hits, kills and phase changes are appended to, see
+    /// [`crate::audit`]. Nothing is recorded if not set.
+    pub audit_log: Option<String>,
+    /// The directory the statistics of each round are exported to as CSV and JSON, see
+    /// [`crate::singleton::stats`]. Nothing is exported if not set.
+    pub results_dir: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             border_diameter: Some(100.0),
+            border_center: [0.0, 0.0],
+            border_stages: Vec::new(),
+            border_damage_per_block: 0.2,
+            border_safe_zone: 5.0,
+            border_warning_blocks: 50,
+            border_warning_time: 200,
             max_players: 10_000,
             view_distance: 32,
             simulation_distance: 10,
             server_desc: "Hyperion Test Server".to_owned(),
+            world_biome: None,
+            dimension: DimensionType::default(),
+            biomes: Vec::new(),
+            damage_types: Vec::new(),
+            pathfinding_budget: 50_000,
+            pathfinding_max_nodes: 2_000,
+            mob_drops: true,
+            combat: Ruleset::default(),
+            teams: Vec::new(),
+            friendly_fire: false,
+            kits: vec![kits::netherite_kit()],
+            default_kit: None,
+            team_kits: BTreeMap::new(),
+            round_kits: Vec::new(),
+            admins: Vec::new(),
+            spawn_area: SpawnArea::default(),
+            team_spawns: BTreeMap::new(),
+            respawn_delay: 100,
+            spawn_protection: 60,
+            lobby_players: 2,
+            lobby_spawn: None,
+            countdown_ticks: 200,
+            battle_ticks: Some(6000),
+            end_ticks: 200,
+            audit_log: None,
+            results_dir: None,
         }
     }
 }
 
 impl Config {
+    /// Whether the player with the given name may use admin commands.
+    pub fn is_admin(&self, name: &str) -> bool {
+        self.admins.iter().any(|admin| admin == name)
+    }
+
+    /// Checks values which would break the game but cannot be ruled out by their types.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.respawn_delay >= 0,
+            "respawn_delay must not be negative, but is {}",
+            self.respawn_delay
+        );
+        ensure!(
+            self.spawn_protection >= 0,
+            "spawn_protection must not be negative, but is {}",
+            self.spawn_protection
+        );
+
+        Ok(())
+    }
+
     #[instrument]
     pub fn load<P: AsRef<Path> + Debug>(path: P) -> anyhow::Result<Self> {
         info!("loading configuration file");
@@ -43,6 +177,7 @@ impl Config {
             let mut contents = String::default();
             file.read_to_string(&mut contents)?;
             let config = toml::from_str::<Self>(contents.as_str())?;
+            config.validate()?;
             Ok(config)
         } else {
             info!("configuration file not found, using defaults");
@@ -1,14 +1,21 @@
 use evenio::{entity::EntityId, event::Event};
-use glam::Vec3;
-use valence_protocol::Hand;
+use glam::{Vec2, Vec3};
+use valence_protocol::{Hand, ItemKind};
 
-use crate::components::FullEntityPose;
+use crate::{
+    components::{
+        damage::DamageCause, mob_kind::MobKind, projectile::ProjectileKind, FullEntityPose,
+    },
+    singleton::phase::Phase,
+};
 
 /// Initialize a Minecraft entity (like a zombie) with a given pose.
 #[derive(Event)]
 pub struct InitEntity {
     /// The pose of the entity.
     pub pose: FullEntityPose,
+    /// The kind of the entity.
+    pub kind: MobKind,
 }
 
 #[derive(Event)]
@@ -51,18 +58,112 @@ pub struct SwingArm {
 
 #[derive(Event)]
 pub struct AttackEntity {
-    /// The [`EntityId`] of the player.
+    /// The [`EntityId`] of the player or mob that is being hit.
     #[event(target)]
     pub target: EntityId,
+    /// The [`EntityId`] of the player that is hitting.
+    pub from: EntityId,
     /// The location of the player that is hitting.
     pub from_pos: Vec3,
 }
 
+/// Deals damage to an entity which was hit by an attack, see [`crate::combat`].
+#[derive(Event)]
+pub struct DamageEntity {
+    /// The [`EntityId`] of the player or mob that is being damaged.
+    #[event(target)]
+    pub target: EntityId,
+    /// The entity which dealt the damage, if any. It is credited if the target dies.
+    pub from: Option<EntityId>,
+    /// What dealt the damage.
+    pub cause: DamageCause,
+    /// The item the attacker held, if any.
+    pub weapon: Option<ItemKind>,
+    /// The location of the attacker. The target is knocked back away from it.
+    pub from_pos: Vec3,
+    /// The damage before invulnerability frames, measured in half hearts.
+    pub damage: f32,
+    /// The knockback in addition to the base knockback. Its length is the strength.
+    pub knockback: Vec2,
+    /// Whether the hit is a critical hit.
+    pub critical: bool,
+}
+
+/// Launches a projectile, see [`crate::components::projectile`].
+#[derive(Event)]
+pub struct ShootProjectile {
+    /// The entity which shot the projectile.
+    pub owner: EntityId,
+    /// What is shot.
+    pub kind: ProjectileKind,
+    /// Where the projectile starts.
+    pub position: Vec3,
+    /// The velocity of the projectile in blocks per tick.
+    pub velocity: Vec3,
+    /// The item the projectile was shot with, i.e., a bow.
+    pub weapon: Option<ItemKind>,
+    /// Whether the projectile was shot from a fully drawn bow.
+    pub critical: bool,
+}
+
+/// A player died. Systems which score kills or announce deaths react to it, see
+/// [`crate::components::damage`].
+#[derive(Event)]
+pub struct PlayerKilled {
+    /// The player who died.
+    pub victim: EntityId,
+    /// The entity credited with the kill, if any. This is the last attacker if they hit the victim
+    /// recently, even if something else dealt the last damage.
+    pub killer: Option<EntityId>,
+    /// What dealt the last damage.
+    pub cause: DamageCause,
+    /// The weapon of the killer if they dealt the last damage with a hit.
+    pub weapon: Option<ItemKind>,
+}
+
 /// An event to kill all minecraft entities (like zombies, skeletons, etc). This will be sent to the equivalent of
 /// `/killall` in the game.
 #[derive(Event)]
 pub struct KillAllEntities;
 
+/// An admin command which gives a player a kit or resets them to the kit assigned to them, see
+/// [`crate::singleton::kits`].
+#[derive(Event)]
+pub struct KitCommand {
+    /// The player who used the command.
+    pub from: EntityId,
+    /// The name of the player whose kit changes. This is the player who used the command if not
+    /// set.
+    pub player: Option<Box<str>>,
+    /// The name of the kit to give, or `None` to reset the kit.
+    pub kit: Option<Box<str>>,
+}
+
+/// An admin command which switches the phase of the match, see [`crate::singleton::phase`].
+#[derive(Event)]
+pub struct PhaseCommand {
+    /// The player who used the command.
+    pub from: EntityId,
+    /// The phase to switch to.
+    pub phase: Phase,
+}
+
+/// A command which shows or hides the debug overlay of the player who used it.
+#[derive(Event)]
+pub struct DebugCommand {
+    /// The player who used the command.
+    pub from: EntityId,
+}
+
+/// An event when the match switches to another phase, see [`crate::singleton::phase`].
+#[derive(Event, Copy, Clone, Debug)]
+pub struct PhaseChanged {
+    /// The phase before the switch.
+    pub from: Phase,
+    /// The phase after the switch.
+    pub to: Phase,
+}
+
 /// An event when server stats are updated.
 #[derive(Event, Copy, Clone)]
 pub struct StatsEvent {
@@ -0,0 +1,233 @@
+//! Items which players can eat.
+//!
+//! Using a [`Food`] starts eating it. After [`Food::eat_ticks`] the item is consumed, restores
+//! [`Hunger`](crate::components::Hunger) and applies the [`FoodEffect`]s of the food.
+//!
+//! Hunger is simulated like in vanilla on normal difficulty: actions add exhaustion, which uses up
+//! saturation and then food. Players with enough food regenerate health and players without food
+//! starve. Rulesets without [`Rules::hunger`](crate::combat::Rules::hunger) keep the food bar full
+//! instead.
+
+use valence_protocol::ItemKind;
+
+use crate::components::status_effects::EffectKind;
+
+/// The number of ticks it takes to eat most food.
+const EAT_TICKS: i64 = 32;
+
+/// The maximum food level.
+pub const MAX_FOOD: u8 = 20;
+
+/// The food level players need to regenerate health.
+pub const REGENERATION_FOOD: u8 = 18;
+
+/// The ticks between two heals or starvation damages.
+pub const HUNGER_INTERVAL: u8 = 80;
+
+/// The ticks between two heals of players with full food and saturation.
+pub const SATURATED_INTERVAL: u8 = 10;
+
+/// The exhaustion which uses up a point of saturation or food.
+pub const EXHAUSTION_PER_POINT: f32 = 4.0;
+
+/// The maximum exhaustion.
+pub const MAX_EXHAUSTION: f32 = 40.0;
+
+/// The exhaustion of regenerating half a heart.
+pub const REGENERATION_EXHAUSTION: f32 = 6.0;
+
+/// The exhaustion of sprinting a block.
+pub const SPRINT_EXHAUSTION: f32 = 0.1;
+
+/// The exhaustion of a jump.
+pub const JUMP_EXHAUSTION: f32 = 0.05;
+
+/// The exhaustion of a jump while sprinting.
+pub const SPRINT_JUMP_EXHAUSTION: f32 = 0.2;
+
+/// The exhaustion of attacking.
+pub const ATTACK_EXHAUSTION: f32 = 0.1;
+
+/// The exhaustion of taking damage.
+pub const DAMAGE_EXHAUSTION: f32 = 0.1;
+
+/// An effect a player gets when they finish eating.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FoodEffect {
+    /// The kind of the effect.
+    pub kind: EffectKind,
+    /// The amplifier of the effect, `0` is level I.
+    pub amplifier: u8,
+    /// The duration in ticks.
+    pub duration: i64,
+}
+
+impl FoodEffect {
+    const fn new(kind: EffectKind, amplifier: u8, duration: i64) -> Self {
+        Self {
+            kind,
+            amplifier,
+            duration,
+        }
+    }
+}
+
+/// What eating an item does.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Food {
+    /// The food points the item restores.
+    pub nutrition: u8,
+    /// Eating the item restores `nutrition * saturation_modifier * 2` saturation.
+    pub saturation_modifier: f32,
+    /// The number of ticks it takes to eat the item.
+    pub eat_ticks: i64,
+    /// Whether the item can be eaten when the food level is full.
+    pub always_edible: bool,
+    /// The effects applied when the item is eaten.
+    pub effects: &'static [FoodEffect],
+}
+
+impl Food {
+    const fn new(nutrition: u8, saturation_modifier: f32) -> Self {
+        Self {
+            nutrition,
+            saturation_modifier,
+            eat_ticks: EAT_TICKS,
+            always_edible: false,
+            effects: &[],
+        }
+    }
+
+    /// A golden apple which can always be eaten.
+    const fn golden_apple(effects: &'static [FoodEffect]) -> Self {
+        Self {
+            always_edible: true,
+            effects,
+            ..Self::new(4, 1.2)
+        }
+    }
+
+    /// The food of an item, if it can be eaten.
+    pub const fn of(kind: ItemKind) -> Option<Self> {
+        let food = match kind {
+            ItemKind::GoldenApple => Self::golden_apple(&[
+                FoodEffect::new(EffectKind::Regeneration, 1, 100),
+                FoodEffect::new(EffectKind::Absorption, 0, 2400),
+            ]),
+            ItemKind::EnchantedGoldenApple => Self::golden_apple(&[
+                FoodEffect::new(EffectKind::Regeneration, 1, 400),
+                FoodEffect::new(EffectKind::Absorption, 3, 2400),
+                FoodEffect::new(EffectKind::Resistance, 0, 6000),
+                FoodEffect::new(EffectKind::FireResistance, 0, 6000),
+            ]),
+            ItemKind::GoldenCarrot => Self::new(6, 1.2),
+
+            ItemKind::CookedBeef | ItemKind::CookedPorkchop => Self::new(8, 0.8),
+            ItemKind::CookedMutton | ItemKind::CookedSalmon => Self::new(6, 0.8),
+            ItemKind::CookedChicken => Self::new(6, 0.6),
+            ItemKind::Bread | ItemKind::BakedPotato | ItemKind::CookedCod => Self::new(5, 0.6),
+            ItemKind::PumpkinPie => Self::new(8, 0.3),
+            ItemKind::Carrot => Self::new(3, 0.6),
+            ItemKind::Apple => Self::new(4, 0.3),
+            ItemKind::MelonSlice => Self::new(2, 0.3),
+            ItemKind::SweetBerries | ItemKind::Cookie => Self::new(2, 0.1),
+            ItemKind::DriedKelp => Self {
+                eat_ticks: 16,
+                ..Self::new(1, 0.3)
+            },
+            _ => return None,
+        };
+
+        Some(food)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+    use crate::{combat::Rules, components::Hunger};
+
+    /// Runs `ticks` ticks of the hunger simulation and returns the health change.
+    fn simulate(hunger: &mut Hunger, rules: &Rules, health: f32, ticks: u32) -> f32 {
+        (0..ticks).map(|_| hunger.update(rules, health)).sum()
+    }
+
+    #[test]
+    fn eating_restores_food_and_saturation() {
+        let mut hunger = Hunger {
+            food: 10,
+            saturation: 0.0,
+            ..Hunger::default()
+        };
+
+        let steak = Food::of(ItemKind::CookedBeef).unwrap();
+        hunger.eat(&steak);
+
+        assert_eq!(hunger.food, 18);
+        assert!((hunger.saturation - 12.8).abs() < 1e-5);
+
+        // saturation is capped by the food level
+        hunger.eat(&steak);
+        assert_eq!(hunger.food, MAX_FOOD);
+        assert!((hunger.saturation - 20.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn only_golden_apples_are_eaten_when_full() {
+        let hunger = Hunger::default();
+
+        assert!(!hunger.can_eat(&Food::of(ItemKind::Bread).unwrap()));
+        assert!(hunger.can_eat(&Food::of(ItemKind::GoldenApple).unwrap()));
+        assert!(Food::of(ItemKind::NetheriteSword).is_none());
+    }
+
+    #[test]
+    fn exhaustion_uses_saturation_then_food() {
+        let mut hunger = Hunger {
+            saturation: 1.0,
+            ..Hunger::default()
+        };
+
+        // sprinting 50 blocks
+        hunger.moved(Vec3::new(30.0, 0.0, 40.0), false, true);
+        assert!((hunger.exhaustion - 5.0).abs() < 1e-5);
+
+        hunger.update(&Rules::MODERN, 20.0);
+        assert!(hunger.saturation.abs() < 1e-5);
+        assert_eq!(hunger.food, MAX_FOOD);
+
+        hunger.exhaust(4.5);
+        hunger.update(&Rules::MODERN, 20.0);
+        assert_eq!(hunger.food, MAX_FOOD - 1);
+    }
+
+    #[test]
+    fn saturation_regenerates_quickly() {
+        let mut hunger = Hunger::default();
+
+        // 1.9+ heals from saturation every 10 ticks
+        let healed = simulate(&mut hunger, &Rules::MODERN, 10.0, 10);
+        assert!((healed - 5.0 / 6.0).abs() < 1e-5);
+        assert!((hunger.exhaustion - 5.0).abs() < 1e-5);
+
+        // 1.8 heals half a heart every 80 ticks
+        let mut hunger = Hunger::default();
+        assert!(simulate(&mut hunger, &Rules::LEGACY, 10.0, 79).abs() < 1e-5);
+        assert!((hunger.update(&Rules::LEGACY, 10.0) - 1.0).abs() < 1e-5);
+        assert!((hunger.exhaustion - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn starving_stops_at_half_a_heart() {
+        let mut hunger = Hunger {
+            food: 0,
+            saturation: 0.0,
+            ..Hunger::default()
+        };
+
+        assert!((simulate(&mut hunger, &Rules::MODERN, 10.0, 80) + 1.0).abs() < 1e-5);
+        assert!(simulate(&mut hunger, &Rules::MODERN, 1.0, 200).abs() < 1e-5);
+    }
+}
@@ -9,6 +9,9 @@ use evenio::component::Component;
 use rayon_local::RayonLocal;
 use valence_protocol::CompressionThreshold;
 
+/// The number of ticks in a second.
+pub const TICKS_PER_SECOND: i64 = 20;
+
 /// Shared data that is shared between the ECS framework and the IO thread.
 pub struct Shared {
     /// realistically, we will never have more than 2^32 = 4,294,967,296 players
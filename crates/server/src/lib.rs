@@ -6,6 +6,7 @@
 #![feature(io_error_more)]
 #![expect(clippy::type_complexity, reason = "evenio uses a lot of complex types")]
 
+pub mod audit;
 mod chunk;
 mod singleton;
 
@@ -32,9 +33,12 @@ use crate::{
     global::Global,
     net::{Server, ServerDef},
     singleton::{
-        broadcast::BroadcastBuf, buffer_allocator::BufferAllocator, fd_lookup::FdLookup,
+        announcements::Announcements, audit_log::AuditLog, blocks::Blocks, border::WorldBorder,
+        boss_bars::BossBars, broadcast::BroadcastBuf, buffer_allocator::BufferAllocator,
+        fd_lookup::FdLookup, kits::Kits, path_cache::PathCache, phase::PhaseController,
         player_aabb_lookup::PlayerBoundingBoxes, player_id_lookup::PlayerIdLookup,
-        player_uuid_lookup::PlayerUuidLookup,
+        player_uuid_lookup::PlayerUuidLookup, registries::Registries, scoreboard::Scoreboard,
+        spawns::Spawns, stats::MatchStats, teams::Teams,
     },
 };
 
@@ -53,6 +57,13 @@ mod tracker;
 
 mod config;
 
+mod combat;
+mod food;
+mod metadata;
+
+mod pathfinding;
+mod physics;
+
 /// History size for sliding average.
 const MSPT_HISTORY_SIZE: usize = 100;
 
@@ -168,19 +179,40 @@ impl Game {
         world.add_handler(system::init_player);
         world.add_handler(system::player_join_world);
         world.add_handler(system::player_kick);
+        world.add_handler(system::player_leave);
         world.add_handler(system::init_entity);
+        world.add_handler(system::entity_pathfinding);
         world.add_handler(system::entity_move_logic);
+        world.add_handler(system::entity_physics);
         world.add_handler(system::entity_detect_collisions);
         world.add_handler(system::sync_entity_position);
         world.add_handler(system::reset_bounding_boxes);
+        world.add_handler(system::projectile_physics);
         world.add_handler(system::update_time);
+        world.add_handler(system::update_phase);
+        world.add_handler(system::change_phase);
+        world.add_handler(system::round_results);
+        world.add_handler(system::update_border);
+        world.add_handler(system::eat);
         world.add_handler(system::update_health);
+        world.add_handler(system::update_stats);
+        world.add_handler(system::status_effects);
         world.add_handler(system::sync_players);
+        world.add_handler(system::sync_scoreboard);
+        world.add_handler(system::sync_boss_bars);
+        world.add_handler(system::send_announcements);
+        world.add_handler(system::sync_equipment);
         world.add_handler(system::rebuild_player_location);
         world.add_handler(system::player_detect_mob_hits);
+        world.add_handler(system::mob_death);
 
         world.add_handler(system::pkt_attack);
+        world.add_handler(system::damage_entity);
+        world.add_handler(system::score_kill);
+        world.add_handler(system::death_message);
+        world.add_handler(system::audit_kill);
         world.add_handler(system::pkt_hand_swing);
+        world.add_handler(system::shoot_projectile);
 
         world.add_handler(system::generate_egress_packets);
 
@@ -189,6 +221,9 @@ impl Game {
         world.add_handler(system::keep_alive);
         world.add_handler(system::stats_message);
         world.add_handler(system::kill_all);
+        world.add_handler(system::kit_command);
+        world.add_handler(system::phase_command);
+        world.add_handler(system::debug_command);
 
         let global = world.spawn();
         world.insert(global, Global::new(shared.clone()));
@@ -208,9 +243,63 @@ impl Game {
         let fd_lookup = world.spawn();
         world.insert(fd_lookup, FdLookup::default());
 
+        let path_cache = world.spawn();
+        world.insert(path_cache, PathCache::default());
+
         let encoder = world.spawn();
         world.insert(encoder, BroadcastBuf::new(shared.compression_level));
 
+        let registries =
+            Registries::from_config(&config::CONFIG).context("invalid registry configuration")?;
+
+        let blocks = world.spawn();
+        world.insert(blocks, Blocks::generate(&registries.dimension));
+
+        let registries_id = world.spawn();
+        world.insert(registries_id, registries);
+
+        let kits = Kits::from_config(&config::CONFIG).context("invalid kit configuration")?;
+
+        let kits_id = world.spawn();
+        world.insert(kits_id, kits);
+
+        let teams = Teams::from_config(&config::CONFIG).context("invalid team configuration")?;
+
+        let scoreboard = world.spawn();
+        world.insert(scoreboard, Scoreboard::new(&teams));
+
+        let teams_id = world.spawn();
+        world.insert(teams_id, teams);
+
+        let spawns = Spawns::from_config(&config::CONFIG).context("invalid spawn configuration")?;
+
+        let spawns_id = world.spawn();
+        world.insert(spawns_id, spawns);
+
+        let phase = world.spawn();
+        world.insert(phase, PhaseController::default());
+
+        let border =
+            WorldBorder::from_config(&config::CONFIG).context("invalid border configuration")?;
+
+        let border_id = world.spawn();
+        world.insert(border_id, border);
+
+        let boss_bars = world.spawn();
+        world.insert(boss_bars, BossBars::default());
+
+        let announcements = world.spawn();
+        world.insert(announcements, Announcements::default());
+
+        let audit_log =
+            AuditLog::from_config(&config::CONFIG).context("invalid audit log configuration")?;
+
+        let audit_log_id = world.spawn();
+        world.insert(audit_log_id, audit_log);
+
+        let stats = world.spawn();
+        world.insert(stats, MatchStats::default());
+
         let mut game = Self {
             shared,
             world,
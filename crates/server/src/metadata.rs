@@ -0,0 +1,92 @@
+//! Entity metadata as sent in [`play::EntityTrackerUpdateS2c`], see
+//! <https://wiki.vg/Entity_metadata>.
+//!
+//! Each entry is the index of a field, the id of its type and its value. The indices depend on the
+//! kind of entity, so they are kept where the field is used.
+use valence_protocol::{packets::play, Encode, ItemStack, VarInt};
+
+/// The type id of a byte.
+const BYTE_TYPE: VarInt = VarInt(0);
+
+/// The type id of a `VarInt`.
+const VAR_INT_TYPE: VarInt = VarInt(1);
+
+/// The type id of a float.
+const FLOAT_TYPE: VarInt = VarInt(3);
+
+/// The type id of an item stack.
+const ITEM_STACK_TYPE: VarInt = VarInt(7);
+
+/// The type id of a boolean.
+const BOOLEAN_TYPE: VarInt = VarInt(8);
+
+/// Marks the end of entity metadata.
+const METADATA_END: u8 = 0xFF;
+
+/// Entity metadata which is built entry by entry and encoded with [`Metadata::finish`].
+#[derive(Debug, Default)]
+pub struct Metadata(Vec<u8>);
+
+impl Metadata {
+    /// Adds a byte, i.e., a set of flags.
+    #[must_use]
+    pub fn byte(self, index: u8, value: u8) -> Self {
+        self.entry(index, BYTE_TYPE, &value)
+    }
+
+    /// Adds a `VarInt`.
+    #[must_use]
+    pub fn var_int(self, index: u8, value: i32) -> Self {
+        self.entry(index, VAR_INT_TYPE, &VarInt(value))
+    }
+
+    /// Adds a float.
+    #[must_use]
+    pub fn float(self, index: u8, value: f32) -> Self {
+        self.entry(index, FLOAT_TYPE, &value)
+    }
+
+    /// Adds an item stack.
+    #[must_use]
+    pub fn item_stack(self, index: u8, value: &ItemStack) -> Self {
+        self.entry(index, ITEM_STACK_TYPE, value)
+    }
+
+    /// Adds a boolean.
+    #[must_use]
+    pub fn boolean(self, index: u8, value: bool) -> Self {
+        self.entry(index, BOOLEAN_TYPE, &value)
+    }
+
+    /// The encoded metadata, which is sent as the `tracked_values` of the packet.
+    #[must_use]
+    pub fn finish(self) -> Vec<u8> {
+        let Self(mut bytes) = self;
+        METADATA_END.encode(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Adds the value of the field `index` with the type `kind`.
+    fn entry(self, index: u8, kind: VarInt, value: &impl Encode) -> Self {
+        let Self(mut bytes) = self;
+
+        // writing to a vector cannot fail
+        index.encode(&mut bytes).unwrap();
+        kind.encode(&mut bytes).unwrap();
+        value.encode(&mut bytes).unwrap();
+
+        Self(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_encoded_in_order() {
+        let metadata = Metadata::default().byte(8, 0x01).boolean(11, true).finish();
+
+        assert_eq!(metadata, [8, 0, 0x01, 11, 8, 1, 0xFF]);
+    }
+}
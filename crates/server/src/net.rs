@@ -183,8 +183,9 @@ pub const MAX_PACKET_SIZE: usize = 0x001F_FFFF;
 /// targets.
 pub const MINECRAFT_VERSION: &str = "1.20.1";
 
-/// Get a [`Uuid`] based on the given user's name.
-fn offline_uuid(username: &str) -> anyhow::Result<Uuid> {
+/// Get a [`Uuid`] based on the given user's name. Players keep their UUID when they reconnect with
+/// the same name.
+pub fn offline_uuid(username: &str) -> anyhow::Result<Uuid> {
     let digest = sha2::Sha256::digest(username);
 
     #[expect(clippy::indexing_slicing, reason = "sha256 is always 32 bytes")]
@@ -14,38 +14,73 @@ pub mod def;
 use std::str::FromStr;
 
 use anyhow::{bail, ensure};
-use bvh::aabb::Aabb;
 use evenio::{entity::EntityId, query::Query};
 use tracing::{debug, info};
 use valence_protocol::{
     decode::PacketFrame,
     math::Vec3,
-    packets::{play, play::player_interact_entity_c2s::EntityInteraction},
-    Decode, Packet,
+    packets::{
+        play,
+        play::{
+            client_command_c2s::ClientCommand, player_action_c2s::PlayerAction,
+            player_interact_entity_c2s::EntityInteraction,
+        },
+    },
+    text::IntoText,
+    Decode, Hand, ItemKind, Packet,
 };
 
 use crate::{
     components::{
-        vitals::{Absorption, Regeneration},
-        FullEntityPose, ImmuneStatus, KeepAlive,
+        inventory::{Inventory, HOTBAR_SIZE, OFFHAND, SLOT_COUNT},
+        mob_kind::MobKind,
+        projectile::{
+            bow_power, look_direction, BowDraw, ProjectileKind, EYE_HEIGHT, MAX_BOW_SPEED,
+            MIN_BOW_POWER, THROW_SPEED,
+        },
+        CombatState, Eating, FullEntityPose, Hunger, ImmuneStatus, KeepAlive, Meal, OnGround,
+        Teleport,
+    },
+    config,
+    events::{
+        AttackEntity, DebugCommand, InitEntity, KillAllEntities, KitCommand, PhaseCommand,
+        ShootProjectile, SwingArm,
     },
-    events::{AttackEntity, InitEntity, KillAllEntities, SwingArm},
+    food::Food,
     global::Global,
     net::LocalEncoder,
-    singleton::player_id_lookup::PlayerIdLookup,
+    singleton::{phase::Phase, player_id_lookup::PlayerIdLookup},
     system::IngressSender,
     Vitals,
 };
 
-const fn confirm_teleport(_pkt: &[u8]) {
-    // ignore
+fn confirm_teleport(mut data: &[u8], teleport: &mut Teleport) -> anyhow::Result<()> {
+    let pkt = play::TeleportConfirmC2s::decode(&mut data)?;
+
+    teleport.confirm(pkt.teleport_id.0);
+
+    Ok(())
 }
 
 const fn custom_payload(_data: &[u8]) {
     // ignore
 }
 
-fn full(mut data: &[u8], full_entity_pose: &mut FullEntityPose) -> anyhow::Result<()> {
+/// Tracks whether the player stands on the ground, how far they fell and how much they exhausted
+/// themselves by moving by `delta`.
+fn update_on_ground(query: &mut PacketSwitchQuery, delta: Vec3, on_ground: bool) {
+    // the client does not tell the server about jumps
+    let jumped = query.on_ground.0 && !on_ground && delta.y > 0.0;
+
+    query.on_ground.0 = on_ground;
+    query.combat.moved(delta.y, on_ground);
+
+    if config::CONFIG.combat.rules().hunger {
+        query.hunger.moved(delta, jumped, query.combat.sprinting);
+    }
+}
+
+fn full(mut data: &[u8], query: &mut PacketSwitchQuery) -> anyhow::Result<()> {
     const MAX_SPEED: f32 = 100.0;
 
     let pkt = play::FullC2s::decode(&mut data)?;
@@ -54,9 +89,11 @@ fn full(mut data: &[u8], full_entity_pose: &mut FullEntityPose) -> anyhow::Resul
         position,
         yaw,
         pitch,
-        ..
+        on_ground,
     } = pkt;
 
+    let full_entity_pose = &mut *query.pose;
+
     // check to see if the player is moving too fast
     // if they are, ignore the packet
 
@@ -71,43 +108,69 @@ fn full(mut data: &[u8], full_entity_pose: &mut FullEntityPose) -> anyhow::Resul
     full_entity_pose.yaw = yaw;
     full_entity_pose.pitch = pitch;
 
+    update_on_ground(query, d_pos, on_ground);
+
     Ok(())
 }
 
-fn look_and_on_ground(
-    mut data: &[u8],
-    full_entity_pose: &mut FullEntityPose,
-) -> anyhow::Result<()> {
+fn look_and_on_ground(mut data: &[u8], query: &mut PacketSwitchQuery) -> anyhow::Result<()> {
     let pkt = play::LookAndOnGroundC2s::decode(&mut data)?;
 
     // debug!("look and on ground packet: {:?}", pkt);
 
-    let play::LookAndOnGroundC2s { yaw, pitch, .. } = pkt;
+    let play::LookAndOnGroundC2s {
+        yaw,
+        pitch,
+        on_ground,
+    } = pkt;
 
-    full_entity_pose.yaw = yaw;
-    full_entity_pose.pitch = pitch;
+    query.pose.yaw = yaw;
+    query.pose.pitch = pitch;
+
+    update_on_ground(query, Vec3::ZERO, on_ground);
+
+    Ok(())
+}
+
+fn on_ground_only(mut data: &[u8], query: &mut PacketSwitchQuery) -> anyhow::Result<()> {
+    let pkt = play::OnGroundOnlyC2s::decode(&mut data)?;
+
+    update_on_ground(query, Vec3::ZERO, pkt.on_ground);
 
     Ok(())
 }
 
-const fn player_command(data: &[u8]) {
-    // let pkt = play::ClientCommandC2s::decode(&mut data)?;
+fn player_command(mut data: &[u8], combat: &mut CombatState) -> anyhow::Result<()> {
+    let pkt = play::ClientCommandC2s::decode(&mut data)?;
 
     // debug!("player command packet: {:?}", pkt);
+
+    match pkt.action {
+        ClientCommand::StartSprinting => combat.sprinting = true,
+        ClientCommand::StopSprinting => combat.sprinting = false,
+        _ => {}
+    }
+
+    Ok(())
 }
 
-fn position_and_on_ground(
-    mut data: &[u8],
-    full_entity_pose: &mut FullEntityPose,
-) -> anyhow::Result<()> {
+fn position_and_on_ground(mut data: &[u8], query: &mut PacketSwitchQuery) -> anyhow::Result<()> {
     let pkt = play::PositionAndOnGroundC2s::decode(&mut data)?;
 
     // debug!("position and on ground packet: {:?}", pkt);
 
-    let play::PositionAndOnGroundC2s { position, .. } = pkt;
+    let play::PositionAndOnGroundC2s {
+        position,
+        on_ground,
+    } = pkt;
+
+    let position = position.as_vec3();
+    let delta = position - query.pose.position;
 
     // todo: handle like full
-    full_entity_pose.move_to(position.as_vec3());
+    query.pose.move_to(position);
+
+    update_on_ground(query, delta, on_ground);
 
     Ok(())
 }
@@ -120,11 +183,222 @@ fn update_player_abilities(mut data: &[u8]) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn update_selected_slot(mut data: &[u8]) -> anyhow::Result<()> {
+fn update_selected_slot(
+    mut data: &[u8],
+    global: &Global,
+    query: &mut PacketSwitchQuery,
+) -> anyhow::Result<()> {
     let pkt = play::UpdateSelectedSlotC2s::decode(&mut data)?;
 
     // debug!("update selected slot packet: {:?}", pkt);
 
+    let Some(slot) = u8::try_from(pkt.slot)
+        .ok()
+        .filter(|&slot| slot < HOTBAR_SIZE)
+    else {
+        // move the client back to the slot it had selected
+        let pkt = play::UpdateSelectedSlotS2c {
+            slot: query.inventory.selected(),
+        };
+        query.encoder.append(&pkt, global)?;
+        return Ok(());
+    };
+
+    // switching items resets the attack cooldown
+    if query.inventory.select(slot) {
+        query.combat.last_reset = global.tick;
+    }
+
+    // switching items also stops eating and drawing a bow
+    query.eating.meal = None;
+    query.combat.drawing = None;
+
+    Ok(())
+}
+
+fn player_interact_item(
+    mut data: &[u8],
+    global: &Global,
+    query: &mut PacketSwitchQuery,
+    sender: &mut IngressSender,
+) -> anyhow::Result<()> {
+    let pkt = play::PlayerInteractItemC2s::decode(&mut data)?;
+
+    // the client predicts using the item until the server acknowledges it
+    query.encoder.append(
+        &play::PlayerActionResponseS2c {
+            sequence: pkt.sequence,
+        },
+        global,
+    )?;
+
+    let slot = match pkt.hand {
+        Hand::Main => query.inventory.main_hand_slot(),
+        Hand::Off => OFFHAND,
+    };
+
+    let item = query.inventory.get(slot).item;
+
+    // the arrow is shot once the bow is released in `player_action`
+    if item == ItemKind::Bow {
+        if query.inventory.find(ItemKind::Arrow).is_some() {
+            query.combat.drawing = Some(BowDraw {
+                slot,
+                start_tick: global.tick,
+            });
+        }
+
+        return Ok(());
+    }
+
+    if let Some(kind) = ProjectileKind::thrown(item) {
+        query.inventory.remove_one(slot);
+        query
+            .encoder
+            .append(&query.inventory.slot_packet(slot), global)?;
+
+        shoot(query, sender, kind, THROW_SPEED, None, false);
+
+        return Ok(());
+    }
+
+    let Some(food) = Food::of(item) else {
+        return Ok(());
+    };
+
+    if !query.hunger.can_eat(&food) {
+        return Ok(());
+    }
+
+    // eating is finished in `system::eat`
+    query.eating.meal = Some(Meal {
+        slot,
+        item,
+        finish_tick: global.tick + food.eat_ticks,
+    });
+
+    Ok(())
+}
+
+fn player_action(
+    mut data: &[u8],
+    global: &Global,
+    query: &mut PacketSwitchQuery,
+    sender: &mut IngressSender,
+) -> anyhow::Result<()> {
+    let pkt = play::PlayerActionC2s::decode(&mut data)?;
+
+    if !matches!(pkt.action, PlayerAction::ReleaseUseItem) {
+        return Ok(());
+    }
+
+    // releasing right click cancels eating
+    query.eating.meal = None;
+
+    // and shoots the bow the player is drawing
+    let Some(draw) = query.combat.drawing.take() else {
+        return Ok(());
+    };
+
+    if query.inventory.get(draw.slot).item != ItemKind::Bow {
+        return Ok(());
+    }
+
+    let power = bow_power(global.tick - draw.start_tick);
+
+    if power < MIN_BOW_POWER {
+        return Ok(());
+    }
+
+    let Some(arrow) = query.inventory.find(ItemKind::Arrow) else {
+        return Ok(());
+    };
+
+    query.inventory.remove_one(arrow);
+    query
+        .encoder
+        .append(&query.inventory.slot_packet(arrow), global)?;
+
+    shoot(
+        query,
+        sender,
+        ProjectileKind::Arrow,
+        power * MAX_BOW_SPEED,
+        Some(ItemKind::Bow),
+        power >= 1.0,
+    );
+
+    Ok(())
+}
+
+/// Shoots a projectile from just below the eyes of the player in the direction they look.
+fn shoot(
+    query: &PacketSwitchQuery,
+    sender: &mut IngressSender,
+    kind: ProjectileKind,
+    speed: f32,
+    weapon: Option<ItemKind>,
+    critical: bool,
+) {
+    let pose = &query.pose;
+
+    sender.send(ShootProjectile {
+        owner: query.id,
+        kind,
+        position: pose.position + Vec3::new(0.0, EYE_HEIGHT - 0.1, 0.0),
+        velocity: look_direction(pose.yaw, pose.pitch) * speed,
+        weapon,
+        critical,
+    });
+}
+
+fn click_slot(
+    mut data: &[u8],
+    global: &Global,
+    query: &mut PacketSwitchQuery,
+) -> anyhow::Result<()> {
+    let pkt = play::ClickSlotC2s::decode(&mut data)?;
+
+    let main_hand = query.inventory.main_hand().clone();
+
+    if !query.inventory.click(&pkt) {
+        // undo the click on the client
+        query
+            .encoder
+            .append(&query.inventory.sync_packet(), global)?;
+        return Ok(());
+    }
+
+    if *query.inventory.main_hand() != main_hand {
+        query.combat.last_reset = global.tick;
+    }
+
+    Ok(())
+}
+
+fn creative_inventory_action(
+    mut data: &[u8],
+    global: &Global,
+    query: &mut PacketSwitchQuery,
+) -> anyhow::Result<()> {
+    let pkt = play::CreativeInventoryActionC2s::decode(&mut data)?;
+
+    // players are always in survival, so the creative inventory cannot be used. Items dropped from
+    // it (slot -1) are simply never created.
+    let Ok(slot) = usize::try_from(pkt.slot) else {
+        return Ok(());
+    };
+
+    if slot < SLOT_COUNT {
+        query
+            .encoder
+            .append(&query.inventory.slot_packet(slot), global)?;
+    } else {
+        query
+            .encoder
+            .append(&query.inventory.sync_packet(), global)?;
+    }
+
     Ok(())
 }
 
@@ -163,11 +437,23 @@ impl FromStr for HybridPos {
     }
 }
 
+/// Sends `message` to the player in the chat, i.e., to explain how to use a command.
+fn reply(encoder: &mut LocalEncoder, global: &Global, message: &str) -> anyhow::Result<()> {
+    let pkt = play::GameMessageS2c {
+        chat: message.into_cow_text(),
+        overlay: false,
+    };
+
+    encoder.append(&pkt, global)
+}
+
 fn chat_command(
     mut data: &[u8],
     global: &Global,
     // query: PacketSwitchQuery,
+    id: EntityId,
     pose: &FullEntityPose,
+    encoder: &mut LocalEncoder,
     sender: &mut IngressSender,
 ) -> anyhow::Result<()> {
     const BASE_RADIUS: f32 = 4.0;
@@ -180,27 +466,47 @@ fn chat_command(
 
     if first == Some("ka") {
         sender.send(KillAllEntities);
+    } else if first == Some("kit") {
+        let args: Vec<_> = cmd.collect();
+
+        let (kit, player) = match args.as_slice() {
+            ["give", kit] => (Some(*kit), None),
+            ["give", kit, player] => (Some(*kit), Some(*player)),
+            ["reset"] => (None, None),
+            ["reset", player] => (None, Some(*player)),
+            _ => {
+                return reply(
+                    encoder,
+                    global,
+                    "Usage: /kit give <kit> [player] or /kit reset [player]",
+                );
+            }
+        };
+
+        sender.send(KitCommand {
+            from: id,
+            player: player.map(Box::from),
+            kit: kit.map(Box::from),
+        });
+    } else if first == Some("phase") {
+        let args: Vec<_> = cmd.collect();
+
+        let phase = match args.as_slice() {
+            [name] => Phase::from_name(name),
+            _ => None,
+        };
+
+        let Some(phase) = phase else {
+            let phases: Vec<_> = Phase::ALL.iter().map(|phase| phase.name()).collect();
+            let usage = format!("Usage: /phase <{}>", phases.join("|"));
+            return reply(encoder, global, &usage);
+        };
+
+        sender.send(PhaseCommand { from: id, phase });
+    } else if first == Some("debug") {
+        sender.send(DebugCommand { from: id });
     }
-    // else if first == Some("golden_apple") {
-    //     let vitals = query.vitals;
-    //
-    //     let Vitals::Alive {
-    //         absorption,
-    //         regeneration,
-    //         ..
-    //     } = vitals
-    //     else {
-    //         return Ok(());
-    //     };
-    //
-    //     *absorption = Absorption {
-    //         end_tick: tick + 2400,
-    //         bonus_health: 4.0,
-    //     };
-    //     *regeneration = Regeneration {
-    //         end_tick: tick + 100,
-    //     };
-    // } else if first == Some("heal") {
+    // else if first == Some("heal") {
     //     let args: Vec<_> = cmd.collect();
     //     let [amount] = args.as_slice() else {
     //         anyhow::bail!("expected 1 number");
@@ -213,7 +519,16 @@ fn chat_command(
     //     };
     //     query.vitals.hurt(global, amount.parse()?, query.immunity);
     else if first == Some("spawn") {
-        let args: Vec<_> = cmd.collect();
+        let mut args: Vec<_> = cmd.collect();
+
+        // `/spawn <type> ...` spawns the given kind, everything else spawns zombies
+        let kind = match args.first().and_then(|name| MobKind::from_name(name)) {
+            Some(kind) => {
+                args.remove(0);
+                kind
+            }
+            None => MobKind::Zombie,
+        };
 
         let loc = pose.position;
         // let loc = query.pose.position;
@@ -238,8 +553,9 @@ fn chat_command(
                             position: Vec3::new(x, y, z),
                             yaw: 0.0,
                             pitch: 0.0,
-                            bounding: Aabb::create(Vec3::new(x, y, z), 0.6, 1.8),
+                            bounding: kind.bounding_box(Vec3::new(x, y, z)),
                         },
+                        kind,
                     });
                 }
 
@@ -269,8 +585,9 @@ fn chat_command(
                 position: Vec3::new(x, y, z),
                 yaw: 0.0,
                 pitch: 0.0,
-                bounding: Aabb::create(Vec3::new(x, y, z), 0.6, 1.8),
+                bounding: kind.bounding_box(Vec3::new(x, y, z)),
             },
+            kind,
         });
     }
 
@@ -299,6 +616,7 @@ fn chat_command(
 fn player_interact_entity(
     mut data: &[u8],
     id_lookup: &PlayerIdLookup,
+    from: EntityId,
     from_pos: Vec3,
     sender: &mut IngressSender,
 ) -> anyhow::Result<()> {
@@ -312,28 +630,37 @@ fn player_interact_entity(
     let target = packet.entity_id.0;
 
     if let Some(&target) = id_lookup.inner.get(&target) {
-        sender.send(AttackEntity { target, from_pos });
+        if target != from {
+            sender.send(AttackEntity {
+                target,
+                from,
+                from_pos,
+            });
+        }
     }
 
     Ok(())
 }
 
-// #[derive(Query)]
-// pub struct PacketSwitchQuery<'a> {
-//     id: EntityId,
-//     pose: &'a mut FullEntityPose,
-//     vitals: &'a mut Vitals,
-//     encoder: &'a mut LocalEncoder,
-//     keep_alive: &'a mut KeepAlive,
-//     immunity: &'a mut ImmuneStatus,
-// }
-//
+/// The player who sent a packet.
+pub struct PacketSwitchQuery<'a> {
+    pub id: EntityId,
+    pub pose: &'a mut FullEntityPose,
+    pub on_ground: &'a mut OnGround,
+    pub combat: &'a mut CombatState,
+    pub inventory: &'a mut Inventory,
+    pub eating: &'a mut Eating,
+    pub hunger: &'a mut Hunger,
+    pub teleport: &'a mut Teleport,
+    pub encoder: &'a mut LocalEncoder,
+}
+
 pub fn switch(
     raw: PacketFrame,
     global: &Global,
     sender: &mut IngressSender,
-    pose: &mut FullEntityPose,
-    // query: PacketSwitchQuery,
+    id_lookup: &PlayerIdLookup,
+    mut query: PacketSwitchQuery,
 ) -> anyhow::Result<()> {
     let packet_id = raw.id;
     let data = raw.body;
@@ -341,21 +668,33 @@ pub fn switch(
 
     match packet_id {
         // play::HandSwingC2s::ID => hand_swing(data, &query, sender)?,
-        // play::TeleportConfirmC2s::ID => confirm_teleport(data),
+        play::TeleportConfirmC2s::ID => confirm_teleport(data, query.teleport)?,
+        play::FullC2s::ID | play::PositionAndOnGroundC2s::ID if query.teleport.is_pending() => {
+            // the client moved before it knew about the teleport
+        }
         // // play::ClientSettingsC2s::ID => client_settings(data, player)?,
         // play::CustomPayloadC2s::ID => custom_payload(data),
-        play::FullC2s::ID => full(data, pose)?,
-        play::PositionAndOnGroundC2s::ID => position_and_on_ground(data, pose)?,
-        play::LookAndOnGroundC2s::ID => look_and_on_ground(data, pose)?,
-        // play::ClientCommandC2s::ID => player_command(data),
+        play::FullC2s::ID => full(data, &mut query)?,
+        play::PositionAndOnGroundC2s::ID => position_and_on_ground(data, &mut query)?,
+        play::LookAndOnGroundC2s::ID => look_and_on_ground(data, &mut query)?,
+        play::OnGroundOnlyC2s::ID => on_ground_only(data, &mut query)?,
+        play::ClientCommandC2s::ID => player_command(data, query.combat)?,
         // play::UpdatePlayerAbilitiesC2s::ID => update_player_abilities(data)?,
-        // play::UpdateSelectedSlotC2s::ID => update_selected_slot(data)?,
-        // play::PlayerInteractEntityC2s::ID => {
-        //     player_interact_entity(data, id_lookup, query.pose.position, sender)?;
-        // }
+        play::UpdateSelectedSlotC2s::ID => update_selected_slot(data, global, &mut query)?,
+        play::ClickSlotC2s::ID => click_slot(data, global, &mut query)?,
+        play::CreativeInventoryActionC2s::ID => {
+            creative_inventory_action(data, global, &mut query)?;
+        }
+        play::PlayerInteractItemC2s::ID => {
+            player_interact_item(data, global, &mut query, sender)?;
+        }
+        play::PlayerActionC2s::ID => player_action(data, global, &mut query, sender)?,
+        play::PlayerInteractEntityC2s::ID => {
+            player_interact_entity(data, id_lookup, query.id, query.pose.position, sender)?;
+        }
         // play::KeepAliveC2s::ID => keep_alive(query.keep_alive)?,
         play::CommandExecutionC2s::ID => {
-            chat_command(data, global, pose, sender)?;
+            chat_command(data, global, query.id, query.pose, query.encoder, sender)?;
         }
         _ => {
             // info!("unknown packet id: 0x{:02X}", packet_id)
@@ -0,0 +1,291 @@
+//! A* pathfinding over the block grid for mobs which are two blocks tall.
+//!
+//! A position is walkable if the block at the feet and the block above are not solid and the block
+//! below is solid. From every position a mob can walk to one of the four horizontal neighbours,
+//! jump up one block or drop down up to [`MAX_DROP`] blocks.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use fxhash::FxHashMap;
+use glam::IVec3;
+
+/// The cost of walking to a neighbouring block on the same level.
+const WALK_COST: u32 = 10;
+
+/// The cost of jumping up onto a neighbouring block.
+const JUMP_COST: u32 = 20;
+
+/// The cost of walking off a ledge, excluding the cost of falling.
+const DROP_COST: u32 = 10;
+
+/// The cost of falling one block.
+const FALL_COST: u32 = 5;
+
+/// The maximum number of blocks a mob is willing to drop down.
+pub const MAX_DROP: i32 = 3;
+
+/// The horizontal directions a mob can move in.
+const DIRECTIONS: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+/// The outcome of [`find_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The waypoints from the start (exclusive) to the goal (inclusive).
+    Found(Vec<IVec3>),
+    /// Every reachable position was explored without reaching the goal.
+    Unreachable,
+    /// The node budget ran out before the goal was reached.
+    Exhausted,
+}
+
+/// The result of [`find_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search {
+    /// The outcome of the search.
+    pub outcome: Outcome,
+    /// The number of nodes which were expanded. This is never more than the node budget.
+    pub expanded: usize,
+}
+
+/// Whether a mob can stand at `pos`.
+pub fn is_walkable(pos: IVec3, is_solid: impl Fn(IVec3) -> bool) -> bool {
+    !is_solid(pos) && !is_solid(pos + IVec3::Y) && is_solid(pos - IVec3::Y)
+}
+
+/// Whether the search for `goal` is finished at `pos`. The goal is the block at the feet of the
+/// target, which can be in the air if the target is jumping.
+fn reached(pos: IVec3, goal: IVec3) -> bool {
+    pos.x == goal.x && pos.z == goal.z && (0..=2).contains(&(goal.y - pos.y))
+}
+
+/// An admissible estimate of the cost from `pos` to `goal`.
+fn heuristic(pos: IVec3, goal: IVec3) -> u32 {
+    let diff = goal - pos;
+    let horizontal = diff.x.unsigned_abs() + diff.z.unsigned_abs();
+    horizontal * WALK_COST + diff.y.unsigned_abs() * FALL_COST
+}
+
+/// Calls `process` with every position a mob can move to from `pos` and the cost of the move.
+fn neighbours(pos: IVec3, is_solid: &impl Fn(IVec3) -> bool, mut process: impl FnMut(IVec3, u32)) {
+    for direction in DIRECTIONS {
+        let next = pos + direction;
+
+        if is_walkable(next, is_solid) {
+            process(next, WALK_COST);
+            continue;
+        }
+
+        if is_solid(next) {
+            let above = next + IVec3::Y;
+            let head_clear = !is_solid(pos + IVec3::Y * 2);
+
+            if head_clear && is_walkable(above, is_solid) {
+                process(above, JUMP_COST);
+            }
+
+            continue;
+        }
+
+        if is_solid(next + IVec3::Y) {
+            continue;
+        }
+
+        for height in 1..=MAX_DROP {
+            let below = next - IVec3::Y * height;
+
+            if is_solid(below) {
+                break;
+            }
+
+            if is_walkable(below, is_solid) {
+                process(below, DROP_COST + FALL_COST * height.unsigned_abs());
+                break;
+            }
+        }
+    }
+}
+
+/// A node in the search.
+struct Node {
+    pos: IVec3,
+    cost: u32,
+    parent: Option<usize>,
+    closed: bool,
+}
+
+/// Finds a path from `start` to `goal`, expanding at most `max_nodes` nodes.
+pub fn find_path(
+    start: IVec3,
+    goal: IVec3,
+    max_nodes: usize,
+    is_solid: impl Fn(IVec3) -> bool,
+) -> Search {
+    let mut nodes = vec![Node {
+        pos: start,
+        cost: 0,
+        parent: None,
+        closed: false,
+    }];
+
+    let mut lookup = FxHashMap::default();
+    lookup.insert(start, 0_usize);
+
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(start, goal), 0_usize)));
+
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, idx))) = open.pop() {
+        let Some(node) = nodes.get_mut(idx) else {
+            continue;
+        };
+
+        if node.closed {
+            continue;
+        }
+
+        node.closed = true;
+
+        let pos = node.pos;
+        let cost = node.cost;
+
+        if reached(pos, goal) {
+            return Search {
+                outcome: Outcome::Found(reconstruct(&nodes, idx)),
+                expanded,
+            };
+        }
+
+        if expanded == max_nodes {
+            return Search {
+                outcome: Outcome::Exhausted,
+                expanded,
+            };
+        }
+
+        expanded += 1;
+
+        neighbours(pos, &is_solid, |next, step| {
+            let next_cost = cost + step;
+
+            let next_idx = *lookup.entry(next).or_insert_with(|| {
+                nodes.push(Node {
+                    pos: next,
+                    cost: u32::MAX,
+                    parent: None,
+                    closed: false,
+                });
+                nodes.len() - 1
+            });
+
+            let Some(next_node) = nodes.get_mut(next_idx) else {
+                return;
+            };
+
+            if next_node.closed || next_cost >= next_node.cost {
+                return;
+            }
+
+            next_node.cost = next_cost;
+            next_node.parent = Some(idx);
+
+            open.push(Reverse((next_cost + heuristic(next, goal), next_idx)));
+        });
+    }
+
+    Search {
+        outcome: Outcome::Unreachable,
+        expanded,
+    }
+}
+
+/// Follows the parents from `idx` back to the start.
+fn reconstruct(nodes: &[Node], mut idx: usize) -> Vec<IVec3> {
+    let mut path = Vec::new();
+
+    while let Some(node) = nodes.get(idx) {
+        let Some(parent) = node.parent else {
+            break;
+        };
+
+        path.push(node.pos);
+        idx = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A floor at `y = -1` with the given extra solid blocks.
+    fn world(solid: &[IVec3]) -> impl Fn(IVec3) -> bool + '_ {
+        move |pos| pos.y == -1 || solid.contains(&pos)
+    }
+
+    #[test]
+    fn straight_line() {
+        let search = find_path(IVec3::ZERO, IVec3::new(5, 0, 0), 100, world(&[]));
+
+        let Outcome::Found(path) = search.outcome else {
+            panic!("expected a path, got {:?}", search.outcome);
+        };
+
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last(), Some(&IVec3::new(5, 0, 0)));
+    }
+
+    #[test]
+    fn walks_around_wall() {
+        let wall: Vec<_> = (-3..=3)
+            .flat_map(|z| {
+                [
+                    IVec3::new(2, 0, z),
+                    IVec3::new(2, 1, z),
+                    IVec3::new(2, 2, z),
+                ]
+            })
+            .collect();
+
+        let search = find_path(IVec3::ZERO, IVec3::new(4, 0, 0), 1000, world(&wall));
+
+        let Outcome::Found(path) = search.outcome else {
+            panic!("expected a path, got {:?}", search.outcome);
+        };
+
+        assert!(path.iter().all(|pos| !wall.contains(pos)));
+        assert_eq!(path.last(), Some(&IVec3::new(4, 0, 0)));
+    }
+
+    #[test]
+    fn jumps_onto_block() {
+        let step = [IVec3::new(1, 0, 0)];
+        let search = find_path(IVec3::ZERO, IVec3::new(1, 1, 0), 100, world(&step));
+
+        assert_eq!(search.outcome, Outcome::Found(vec![IVec3::new(1, 1, 0)]));
+    }
+
+    #[test]
+    fn respects_budget() {
+        let search = find_path(IVec3::ZERO, IVec3::new(100, 0, 0), 10, world(&[]));
+
+        assert_eq!(search.outcome, Outcome::Exhausted);
+        assert_eq!(search.expanded, 10);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        // A closed box around the start.
+        let walls: Vec<_> = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z]
+            .into_iter()
+            .flat_map(|dir| [dir, dir + IVec3::Y, dir + IVec3::Y * 2])
+            .chain([IVec3::Y * 2])
+            .collect();
+
+        let search = find_path(IVec3::ZERO, IVec3::new(5, 0, 0), 100, world(&walls));
+
+        assert_eq!(search.outcome, Outcome::Unreachable);
+    }
+}
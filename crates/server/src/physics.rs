@@ -0,0 +1,267 @@
+//! Movement of bounding boxes through the world with collision against static obstacles.
+//!
+//! Like in vanilla, movement is resolved one axis at a time (y, then x, then z). Each axis is
+//! clipped so the box stops exactly at the first obstacle in its way.
+
+use bvh::aabb::Aabb;
+use glam::Vec3;
+
+/// The maximum height of an obstacle an entity walks up on its own. Mobs do not jump yet, so this
+/// lets them climb single blocks.
+pub const STEP_HEIGHT: f32 = 1.0;
+
+/// The gravitational acceleration of entities in blocks per tick squared.
+pub const GRAVITY: f32 = 0.08;
+
+/// The factor the vertical velocity is multiplied by each tick.
+pub const VERTICAL_DRAG: f32 = 0.98;
+
+/// The factor the horizontal velocity is multiplied by each tick while in the air.
+pub const AIR_DRAG: f32 = 0.91;
+
+/// The factor the horizontal velocity is multiplied by each tick while on the ground.
+pub const GROUND_DRAG: f32 = 0.6 * 0.91;
+
+/// Used to ignore obstacles which only touch the moving box.
+const EPSILON: f32 = 1.0e-5;
+
+/// The result of [`move_aabb`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Movement {
+    /// How far the box actually moved.
+    pub offset: Vec3,
+    /// Whether the box landed on an obstacle.
+    pub on_ground: bool,
+    /// Whether the movement was clipped on the x axis.
+    pub collided_x: bool,
+    /// Whether the movement was clipped on the y axis.
+    pub collided_y: bool,
+    /// Whether the movement was clipped on the z axis.
+    pub collided_z: bool,
+}
+
+/// The region which `aabb` passes through when it is moved by `movement`.
+pub fn swept(aabb: Aabb, movement: Vec3) -> Aabb {
+    let moved = aabb.move_by(movement);
+    Aabb::new(aabb.min.min(moved.min), aabb.max.max(moved.max))
+}
+
+/// Moves `aabb` by `movement` without passing through any of the `obstacles`.
+///
+/// If the box is blocked horizontally and `on_ground` is set (or the box lands this movement), it
+/// tries to step up on obstacles of at most `step_height`.
+pub fn move_aabb(
+    aabb: Aabb,
+    movement: Vec3,
+    step_height: f32,
+    on_ground: bool,
+    obstacles: &[Aabb],
+) -> Movement {
+    let offset = clip(aabb, movement, obstacles);
+    let mut result = result(movement, offset);
+
+    let can_step = on_ground || result.on_ground;
+    let blocked = result.collided_x || result.collided_z;
+
+    if step_height <= 0.0 || !can_step || !blocked {
+        return result;
+    }
+
+    // Move up, then horizontally, then back down onto whatever we stepped on.
+    let up = clip_axis(aabb, 1, step_height, obstacles);
+    let raised = aabb.move_by(Vec3::new(0.0, up, 0.0));
+
+    let horizontal = clip(raised, Vec3::new(movement.x, 0.0, movement.z), obstacles);
+    let moved = raised.move_by(horizontal);
+
+    let down = clip_axis(moved, 1, movement.y.min(0.0) - up, obstacles);
+    let stepped = Vec3::new(horizontal.x, up + down, horizontal.z);
+
+    if stepped.x.hypot(stepped.z) > offset.x.hypot(offset.z) + EPSILON {
+        result = self::result(movement, stepped);
+        // Stepping always ends on top of an obstacle or back at the original height.
+        result.on_ground = true;
+    }
+
+    result
+}
+
+fn result(movement: Vec3, offset: Vec3) -> Movement {
+    #[expect(
+        clippy::float_cmp,
+        reason = "clipped axes are set to a different value"
+    )]
+    let (collided_x, collided_y, collided_z) = (
+        offset.x != movement.x,
+        offset.y != movement.y,
+        offset.z != movement.z,
+    );
+
+    Movement {
+        offset,
+        on_ground: collided_y && movement.y < 0.0,
+        collided_x,
+        collided_y,
+        collided_z,
+    }
+}
+
+/// Clips `movement` one axis at a time in the order y, x, z.
+fn clip(aabb: Aabb, movement: Vec3, obstacles: &[Aabb]) -> Vec3 {
+    let mut aabb = aabb;
+    let mut offset = Vec3::ZERO;
+
+    for axis in [1, 0, 2] {
+        let amount = clip_axis(aabb, axis, movement[axis], obstacles);
+        let mut delta = Vec3::ZERO;
+        delta[axis] = amount;
+
+        aabb = aabb.move_by(delta);
+        offset[axis] = amount;
+    }
+
+    offset
+}
+
+/// Clips the movement of `aabb` by `amount` along `axis` so that it stops at the first obstacle.
+fn clip_axis(aabb: Aabb, axis: usize, mut amount: f32, obstacles: &[Aabb]) -> f32 {
+    if amount == 0.0 {
+        return 0.0;
+    }
+
+    let others = [(axis + 1) % 3, (axis + 2) % 3];
+
+    for obstacle in obstacles {
+        let overlaps = others.into_iter().all(|other| {
+            obstacle.max[other] - EPSILON > aabb.min[other]
+                && obstacle.min[other] + EPSILON < aabb.max[other]
+        });
+
+        if !overlaps {
+            continue;
+        }
+
+        if amount > 0.0 && obstacle.min[axis] >= aabb.max[axis] - EPSILON {
+            amount = amount.min(obstacle.min[axis] - aabb.max[axis]);
+        } else if amount < 0.0 && obstacle.max[axis] <= aabb.min[axis] + EPSILON {
+            amount = amount.max(obstacle.max[axis] - aabb.min[axis]);
+        }
+    }
+
+    amount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(x: f32, y: f32, z: f32) -> Aabb {
+        Aabb::new(Vec3::new(x, y, z), Vec3::new(x + 1.0, y + 1.0, z + 1.0))
+    }
+
+    fn zombie(x: f32, y: f32, z: f32) -> Aabb {
+        Aabb::create(Vec3::new(x, y, z), 0.6, 1.95)
+    }
+
+    #[test]
+    fn falls_onto_floor() {
+        let floor = [block(0.0, 0.0, 0.0)];
+        let result = move_aabb(
+            zombie(0.5, 1.5, 0.5),
+            Vec3::new(0.0, -1.0, 0.0),
+            STEP_HEIGHT,
+            false,
+            &floor,
+        );
+
+        assert!((result.offset.y + 0.5).abs() < 1.0e-5);
+        assert!(result.on_ground);
+        assert!(result.collided_y);
+    }
+
+    #[test]
+    fn walks_into_wall() {
+        let wall = [
+            block(1.0, 0.0, 0.0),
+            block(1.0, 1.0, 0.0),
+            block(1.0, 2.0, 0.0),
+        ];
+        let result = move_aabb(
+            zombie(0.5, 0.0, 0.5),
+            Vec3::new(0.5, 0.0, 0.0),
+            STEP_HEIGHT,
+            true,
+            &wall,
+        );
+
+        assert!((result.offset.x - 0.2).abs() < 1.0e-5);
+        assert!(result.collided_x);
+        assert!(!result.collided_z);
+    }
+
+    #[test]
+    fn steps_up_slab() {
+        let slab = [Aabb::new(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.5, 1.0),
+        )];
+        let result = move_aabb(
+            zombie(0.5, 0.0, 0.5),
+            Vec3::new(0.5, -0.08, 0.0),
+            STEP_HEIGHT,
+            true,
+            &slab,
+        );
+
+        assert!((result.offset.x - 0.5).abs() < 1.0e-5);
+        assert!((result.offset.y - 0.5).abs() < 1.0e-5);
+        assert!(result.on_ground);
+    }
+
+    #[test]
+    fn steps_up_single_block() {
+        let step = [block(1.0, 0.0, 0.0)];
+        let result = move_aabb(
+            zombie(0.5, 0.0, 0.5),
+            Vec3::new(0.5, -0.08, 0.0),
+            STEP_HEIGHT,
+            true,
+            &step,
+        );
+
+        assert!((result.offset.x - 0.5).abs() < 1.0e-5);
+        assert!((result.offset.y - 1.0).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn does_not_step_up_two_blocks() {
+        let step = [block(1.0, 0.0, 0.0), block(1.0, 1.0, 0.0)];
+        let result = move_aabb(
+            zombie(0.5, 0.0, 0.5),
+            Vec3::new(0.5, 0.0, 0.0),
+            STEP_HEIGHT,
+            true,
+            &step,
+        );
+
+        assert!((result.offset.x - 0.2).abs() < 1.0e-5);
+        assert!(result.offset.y.abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn no_step_in_air() {
+        let slab = [Aabb::new(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.5, 1.0),
+        )];
+        let result = move_aabb(
+            zombie(0.5, 0.0, 0.5),
+            Vec3::new(0.5, 0.0, 0.0),
+            STEP_HEIGHT,
+            false,
+            &slab,
+        );
+
+        assert!((result.offset.x - 0.2).abs() < 1.0e-5);
+    }
+}
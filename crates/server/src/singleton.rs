@@ -1,9 +1,22 @@
 //! All singletons that are used with [`evenio::fetch::Single`].
 
+pub mod announcements;
+pub mod audit_log;
+pub mod blocks;
+pub mod border;
+pub mod boss_bars;
 pub mod bounding_box;
 pub mod broadcast;
 pub mod buffer_allocator;
 pub mod fd_lookup;
+pub mod kits;
+pub mod path_cache;
+pub mod phase;
 pub mod player_aabb_lookup;
 pub mod player_id_lookup;
 pub mod player_uuid_lookup;
+pub mod registries;
+pub mod scoreboard;
+pub mod spawns;
+pub mod stats;
+pub mod teams;
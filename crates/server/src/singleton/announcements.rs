@@ -0,0 +1,349 @@
+//! Chat messages, action bar messages and titles for selected players.
+//!
+//! Systems queue an [`Announcement`] for an [`Audience`] and `system::send_announcements` sends it
+//! to every player in the audience once per tick. The action bar and the title only show one
+//! message at a time, so each player has a [`Screen`] which remembers the [`Priority`] of what it
+//! shows. A message does not replace a message with a higher priority until that one faded away,
+//! so the debug overlay cannot hide the countdown.
+use evenio::{component::Component, entity::EntityId};
+use glam::Vec3;
+use valence_protocol::{
+    packets::play,
+    text::{IntoText, Text},
+};
+
+use crate::{global::Global, net::LocalEncoder, singleton::teams::Team};
+
+/// The number of ticks vanilla shows a message in the action bar.
+const ACTION_BAR_TICKS: i64 = 60;
+
+/// The players who receive an announcement.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Audience {
+    /// Every player.
+    Everyone,
+    /// The players of a team.
+    Team(Team),
+    /// The players within `radius` blocks of `center`.
+    Region {
+        /// The center of the region.
+        center: Vec3,
+        /// The distance from the center.
+        radius: f32,
+    },
+    /// A single player.
+    Player(EntityId),
+}
+
+impl Audience {
+    /// Whether the player `id` of `team` at `position` is in the audience.
+    pub fn includes(self, id: EntityId, team: Team, position: Vec3) -> bool {
+        match self {
+            Self::Everyone => true,
+            Self::Team(audience) => audience == team,
+            Self::Region { center, radius } => center.distance_squared(position) <= radius * radius,
+            Self::Player(player) => player == id,
+        }
+    }
+}
+
+/// How important a message in the action bar or title is.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Debug information, i.e., the ms per tick.
+    Debug,
+    /// Regular messages.
+    #[default]
+    Info,
+    /// Messages about the match, i.e., the countdown.
+    Important,
+    /// Messages which must be seen, i.e., the death screen.
+    Critical,
+}
+
+/// The number of ticks a title fades in, stays and fades out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fade {
+    /// The number of ticks the title fades in.
+    pub fade_in: i32,
+    /// The number of ticks the title stays.
+    pub stay: i32,
+    /// The number of ticks the title fades out.
+    pub fade_out: i32,
+}
+
+impl Default for Fade {
+    /// The timings vanilla uses.
+    fn default() -> Self {
+        Self {
+            fade_in: 10,
+            stay: 70,
+            fade_out: 20,
+        }
+    }
+}
+
+impl Fade {
+    /// The number of ticks the title is visible.
+    const fn ticks(self) -> i64 {
+        self.fade_in as i64 + self.stay as i64 + self.fade_out as i64
+    }
+}
+
+/// A title in the middle of the screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Title {
+    /// The large text.
+    pub title: Text,
+    /// The smaller text below the title.
+    pub subtitle: Option<Text>,
+    /// How long the title is shown.
+    pub fade: Fade,
+    /// How important the title is.
+    pub priority: Priority,
+}
+
+impl Title {
+    /// A title without subtitle with the vanilla timings.
+    pub fn new(title: impl IntoText<'static>) -> Self {
+        Self {
+            title: title.into_text(),
+            subtitle: None,
+            fade: Fade::default(),
+            priority: Priority::default(),
+        }
+    }
+
+    /// Sets the text below the title.
+    #[must_use]
+    pub fn subtitle(mut self, subtitle: impl IntoText<'static>) -> Self {
+        self.subtitle = Some(subtitle.into_text());
+        self
+    }
+
+    /// Sets how long the title is shown.
+    #[must_use]
+    pub const fn fade(mut self, fade: Fade) -> Self {
+        self.fade = fade;
+        self
+    }
+
+    /// Sets how important the title is.
+    #[must_use]
+    pub const fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// A message for an [`Audience`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Announcement {
+    /// A message in the chat.
+    Chat(Text),
+    /// A message above the hotbar.
+    ActionBar {
+        /// The message.
+        text: Text,
+        /// How important the message is.
+        priority: Priority,
+    },
+    /// A title in the middle of the screen.
+    Title(Title),
+}
+
+/// What the action bar or the title of a player shows.
+#[derive(Copy, Clone, Debug, Default)]
+struct Slot {
+    /// The priority of the message.
+    priority: Priority,
+    /// The tick the message is gone.
+    until: i64,
+}
+
+impl Slot {
+    /// Shows a message with `priority` for `ticks` from `tick` on if it does not hide a more
+    /// important message and returns whether it did.
+    fn claim(&mut self, priority: Priority, tick: i64, ticks: i64) -> bool {
+        if tick < self.until && priority < self.priority {
+            return false;
+        }
+
+        *self = Self {
+            priority,
+            until: tick + ticks,
+        };
+
+        true
+    }
+}
+
+/// What the action bar and the title of a player show, see [`crate::singleton::announcements`].
+#[derive(Component, Debug, Default)]
+pub struct Screen {
+    /// The message in the action bar.
+    action_bar: Slot,
+    /// The title.
+    title: Slot,
+    /// Whether the player sees the ms per tick in their action bar.
+    pub debug_overlay: bool,
+}
+
+impl Screen {
+    /// Forgets the title, i.e., after it was cleared.
+    pub fn clear_title(&mut self) {
+        self.title = Slot::default();
+    }
+
+    /// Sends `announcement` to a player with this screen unless it would hide a more important
+    /// message.
+    pub fn show(
+        &mut self,
+        announcement: &Announcement,
+        encoder: &mut LocalEncoder,
+        global: &Global,
+    ) -> anyhow::Result<()> {
+        let tick = global.tick;
+
+        match announcement {
+            Announcement::Chat(text) => {
+                let pkt = play::GameMessageS2c {
+                    chat: text.clone().into(),
+                    overlay: false,
+                };
+                encoder.append(&pkt, global)?;
+            }
+            Announcement::ActionBar { text, priority } => {
+                if self.action_bar.claim(*priority, tick, ACTION_BAR_TICKS) {
+                    let pkt = play::OverlayMessageS2c {
+                        action_bar_text: text.clone().into(),
+                    };
+                    encoder.append(&pkt, global)?;
+                }
+            }
+            Announcement::Title(title) => {
+                if !self.title.claim(title.priority, tick, title.fade.ticks()) {
+                    return Ok(());
+                }
+
+                let Fade {
+                    fade_in,
+                    stay,
+                    fade_out,
+                } = title.fade;
+
+                encoder.append(
+                    &play::TitleFadeS2c {
+                        fade_in,
+                        stay,
+                        fade_out,
+                    },
+                    global,
+                )?;
+
+                // the subtitle of the last title would stay otherwise
+                let subtitle = title.subtitle.clone().unwrap_or_default();
+                encoder.append(
+                    &play::SubtitleS2c {
+                        subtitle_text: subtitle.into(),
+                    },
+                    global,
+                )?;
+
+                encoder.append(
+                    &play::TitleS2c {
+                        title_text: title.title.clone().into(),
+                    },
+                    global,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// See [`crate::singleton::announcements`].
+#[derive(Component, Debug, Default)]
+pub struct Announcements {
+    /// The announcements which are sent in the next tick.
+    queue: Vec<(Audience, Announcement)>,
+}
+
+impl Announcements {
+    /// Queues an announcement for an audience.
+    pub fn send(&mut self, audience: Audience, announcement: Announcement) {
+        self.queue.push((audience, announcement));
+    }
+
+    /// Queues a chat message for an audience.
+    pub fn chat(&mut self, audience: Audience, text: impl IntoText<'static>) {
+        self.send(audience, Announcement::Chat(text.into_text()));
+    }
+
+    /// Queues a message in the action bar for an audience.
+    pub fn action_bar(
+        &mut self,
+        audience: Audience,
+        text: impl IntoText<'static>,
+        priority: Priority,
+    ) {
+        let text = text.into_text();
+        self.send(audience, Announcement::ActionBar { text, priority });
+    }
+
+    /// Queues a title for an audience.
+    pub fn title(&mut self, audience: Audience, title: Title) {
+        self.send(audience, Announcement::Title(title));
+    }
+
+    /// Takes all queued announcements.
+    pub fn take(&mut self) -> Vec<(Audience, Announcement)> {
+        std::mem::take(&mut self.queue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn important_messages_are_not_hidden() {
+        let mut slot = Slot::default();
+
+        assert!(slot.claim(Priority::Debug, 0, ACTION_BAR_TICKS));
+        assert!(slot.claim(Priority::Important, 1, ACTION_BAR_TICKS));
+
+        // the debug overlay waits until the countdown faded away
+        assert!(!slot.claim(Priority::Debug, 2, ACTION_BAR_TICKS));
+        assert!(!slot.claim(Priority::Info, 60, ACTION_BAR_TICKS));
+        assert!(slot.claim(Priority::Debug, 61, ACTION_BAR_TICKS));
+
+        // newer messages replace messages which are as important
+        assert!(slot.claim(Priority::Debug, 62, ACTION_BAR_TICKS));
+    }
+
+    #[test]
+    fn audiences_select_players() {
+        let player = EntityId::NULL;
+        let red = Team(Some(0));
+        let position = Vec3::new(10.0, 64.0, 0.0);
+
+        assert!(Audience::Everyone.includes(player, Team(None), position));
+        assert!(Audience::Team(red).includes(player, red, position));
+        assert!(!Audience::Team(red).includes(player, Team(Some(1)), position));
+        assert!(Audience::Player(player).includes(player, red, position));
+
+        let near = Audience::Region {
+            center: Vec3::new(0.0, 64.0, 0.0),
+            radius: 10.0,
+        };
+        let far = Audience::Region {
+            center: Vec3::new(0.0, 64.0, 0.0),
+            radius: 9.0,
+        };
+
+        assert!(near.includes(player, red, position));
+        assert!(!far.includes(player, red, position));
+    }
+}
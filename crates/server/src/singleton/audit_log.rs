@@ -0,0 +1,110 @@
+//! Appends [`Record`]s to the audit log without slowing down ticks, see [`crate::audit`].
+//!
+//! Systems hand records to [`AuditLog::record`], which only sends them to a thread that owns the
+//! file. The thread writes whatever arrived since it last woke up and flushes once it caught up, so
+//! the file is at most a few records behind the game. Dropping the log waits until the thread
+//! wrote every queued record.
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    sync::mpsc::{self, Receiver, Sender},
+    thread::JoinHandle,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use evenio::component::Component;
+use tracing::{info, warn};
+
+use crate::{audit::Record, config::Config};
+
+/// See [`crate::singleton::audit_log`].
+#[derive(Component, Debug)]
+pub struct AuditLog {
+    /// The channel to the writing thread, `None` if no audit log is configured.
+    sender: Option<Sender<Record>>,
+    /// The writing thread, which is joined on drop so queued records are not lost.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AuditLog {
+    /// Opens the audit log of the configuration for appending and starts the writing thread.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let Some(path) = &config.audit_log else {
+            return Ok(Self {
+                sender: None,
+                thread: None,
+            });
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open the audit log {path}"))?;
+
+        let (sender, receiver) = mpsc::channel();
+
+        let thread = std::thread::Builder::new()
+            .name("audit-log".to_owned())
+            .spawn(move || write(file, &receiver))
+            .context("failed to start the audit log thread")?;
+
+        info!("appending the audit log to {path}");
+
+        let log = Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        };
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        log.record(Record::Start { time });
+
+        Ok(log)
+    }
+
+    /// Queues a record to be written, if an audit log is configured.
+    pub fn record(&self, record: Record) {
+        if let Some(sender) = &self.sender {
+            // the thread only stops once the sender is dropped
+            let _ = sender.send(record);
+        }
+    }
+}
+
+impl Drop for AuditLog {
+    fn drop(&mut self) {
+        // the thread writes what is left and stops once the sender is dropped
+        self.sender = None;
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                warn!("the audit log thread panicked");
+            }
+        }
+    }
+}
+
+/// Writes records to `file` as they arrive until the game stops.
+fn write(file: File, receiver: &Receiver<Record>) {
+    let mut writer = BufWriter::new(file);
+
+    while let Ok(record) = receiver.recv() {
+        for record in std::iter::once(record).chain(receiver.try_iter()) {
+            let result = serde_json::to_writer(&mut writer, &record)
+                .map_err(std::io::Error::from)
+                .and_then(|()| writer.write_all(b"\n"));
+
+            if let Err(error) = result {
+                warn!("failed to write to the audit log: {error}");
+            }
+        }
+
+        if let Err(error) = writer.flush() {
+            warn!("failed to flush the audit log: {error}");
+        }
+    }
+}
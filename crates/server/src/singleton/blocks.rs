@@ -0,0 +1,203 @@
+//! Defines a singleton which stores the blocks of the world.
+//!
+//! The world is currently a flat arena where every chunk within [`Blocks::radius`] chunks of the
+//! origin has the same column of sections. Everything outside the generated area is air.
+use bvh::aabb::Aabb;
+use chunk::chunk::{BlockStateContainer, SECTION_BLOCK_COUNT};
+use evenio::component::Component;
+use glam::IVec3;
+use valence_protocol::BlockState;
+
+use crate::singleton::registries::DimensionType;
+
+/// The number of stone sections below the ground section.
+const STONE_SECTIONS: usize = 4;
+
+/// The number of layers of surface blocks in the ground section.
+const GROUND_HEIGHT: usize = 5;
+
+/// The radius in chunks of the generated area.
+const GENERATED_RADIUS: i32 = 16;
+
+trait Array3d {
+    type Item;
+    fn get3_mut(&mut self, x: usize, y: usize, z: usize) -> &mut Self::Item;
+}
+
+#[expect(
+    clippy::indexing_slicing,
+    reason = "the signature of the trait allows for panics"
+)]
+impl<T, const N: usize> Array3d for [T; N] {
+    type Item = T;
+
+    fn get3_mut(&mut self, x: usize, y: usize, z: usize) -> &mut Self::Item {
+        &mut self[x + z * 16 + y * 16 * 16]
+    }
+}
+
+/// See [`crate::singleton::blocks`].
+#[derive(Component)]
+pub struct Blocks {
+    /// The lowest y coordinate of the world.
+    min_y: i32,
+    /// The radius in chunks of the generated area around the origin.
+    radius: i32,
+    /// The sections of the chunk column, from bottom to top.
+    sections: Vec<BlockStateContainer>,
+}
+
+impl Blocks {
+    /// Generates the flat arena for the given dimension.
+    pub fn generate(dimension: &DimensionType) -> Self {
+        let section_count = dimension.section_count();
+        let stone_sections = STONE_SECTIONS.min(section_count - 1);
+
+        let mut sections = Vec::with_capacity(section_count);
+
+        for _ in 0..stone_sections {
+            sections.push(BlockStateContainer::Single(BlockState::STONE));
+        }
+
+        sections.push(ground_section());
+
+        sections.resize_with(section_count, || {
+            BlockStateContainer::Single(BlockState::AIR)
+        });
+
+        Self {
+            min_y: dimension.min_y,
+            radius: GENERATED_RADIUS,
+            sections,
+        }
+    }
+
+    /// The radius in chunks of the generated area around the origin. Chunks from `-radius` to
+    /// `radius` (inclusive) on both axes are generated.
+    pub const fn radius(&self) -> i32 {
+        self.radius
+    }
+
+    /// The sections of the chunk column which every generated chunk has, from bottom to top.
+    pub fn sections(&self) -> &[BlockStateContainer] {
+        &self.sections
+    }
+
+    /// Gets the block state at the given position. Positions outside the world are air.
+    pub fn get(&self, pos: IVec3) -> BlockState {
+        let chunk_x = pos.x >> 4;
+        let chunk_z = pos.z >> 4;
+
+        if chunk_x.abs() > self.radius || chunk_z.abs() > self.radius {
+            return BlockState::AIR;
+        }
+
+        let Ok(y) = usize::try_from(pos.y - self.min_y) else {
+            return BlockState::AIR;
+        };
+
+        let Some(section) = self.sections.get(y >> 4) else {
+            return BlockState::AIR;
+        };
+
+        let x = pos.x.rem_euclid(16).unsigned_abs() as usize;
+        let z = pos.z.rem_euclid(16).unsigned_abs() as usize;
+        let y = y & 15;
+
+        section.get(x + z * 16 + y * 16 * 16)
+    }
+
+    /// Whether the block at `pos` has a collision box.
+    pub fn is_solid(&self, pos: IVec3) -> bool {
+        self.get(pos).collision_shapes().next().is_some()
+    }
+
+    /// The y coordinate above the highest solid block of the column at `x` and `z`, like the
+    /// `MOTION_BLOCKING` heightmap. `None` if the column has no solid block.
+    pub fn height(&self, x: i32, z: i32) -> Option<i32> {
+        let height = i32::try_from(self.sections.len() * 16).unwrap_or(i32::MAX);
+
+        (self.min_y..self.min_y.saturating_add(height))
+            .rev()
+            .find(|&y| self.is_solid(IVec3::new(x, y, z)))
+            .map(|y| y + 1)
+    }
+
+    /// Calls `process` with the collision boxes of all blocks which intersect with `aabb`.
+    pub fn collisions(&self, aabb: Aabb, mut process: impl FnMut(Aabb)) {
+        let min = aabb.min.floor().as_ivec3();
+        let max = aabb.max.floor().as_ivec3();
+
+        for x in min.x..=max.x {
+            for z in min.z..=max.z {
+                for y in min.y..=max.y {
+                    let pos = IVec3::new(x, y, z);
+                    let state = self.get(pos);
+
+                    if state.is_air() {
+                        continue;
+                    }
+
+                    let offset = pos.as_vec3();
+
+                    for shape in state.collision_shapes() {
+                        let shape = Aabb::new(
+                            shape.min().as_vec3() + offset,
+                            shape.max().as_vec3() + offset,
+                        );
+
+                        if shape.collides(&aabb) {
+                            process(shape);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn ground_section() -> BlockStateContainer {
+    use rand::seq::SliceRandom;
+
+    let mut blocks = [BlockState::AIR; { SECTION_BLOCK_COUNT as usize }];
+
+    let surface_blocks = [
+        BlockState::END_STONE,
+        BlockState::SAND,
+        BlockState::GRAVEL,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+        BlockState::END_STONE,
+    ];
+
+    let mut rnd = rand::thread_rng();
+
+    for x in 0..16 {
+        for z in 0..16 {
+            for y in 0..GROUND_HEIGHT {
+                let block = surface_blocks.choose(&mut rnd).unwrap();
+                *blocks.get3_mut(x, y, z) = *block;
+            }
+        }
+    }
+
+    BlockStateContainer::Direct(Box::new(blocks))
+}
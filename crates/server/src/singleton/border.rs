@@ -0,0 +1,356 @@
+//! The world border, which shrinks in stages during the battle.
+//!
+//! The border starts with the diameter of the [`crate::config::Config`]. Once the battle starts,
+//! each [`BorderStage`] waits for its delay and then shrinks the border to its diameter. Clients
+//! interpolate the size themselves, so the server only sends the start of each stage. Like in
+//! vanilla, players who are further outside the border than its safe zone take damage for every
+//! block they are outside of it. The border returns to its initial size in the lobby.
+use anyhow::ensure;
+use evenio::component::Component;
+use glam::{DVec2, DVec3};
+use serde::{Deserialize, Serialize};
+use valence_protocol::{packets::play, VarInt, VarLong};
+
+use crate::config::Config;
+
+/// The diameter of the border if none is configured, which is the largest vanilla allows.
+const MAX_DIAMETER: f64 = 59_999_968.0;
+
+/// The distance from the center at which nether portals do not teleport players, like vanilla.
+const PORTAL_TELEPORT_BOUNDARY: i32 = 29_999_984;
+
+/// The number of milliseconds in a tick.
+const MILLIS_PER_TICK: i64 = 50;
+
+/// A stage of the shrinking border, as defined in the configuration.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BorderStage {
+    /// The number of ticks to wait after the previous stage, or the start of the battle, before
+    /// shrinking.
+    #[serde(default)]
+    pub delay: i64,
+    /// The diameter the border shrinks to.
+    pub diameter: f64,
+    /// The number of ticks the border takes to shrink.
+    pub duration: i64,
+    /// The x and z coordinates the center moves to when the stage starts. The center stays where
+    /// it is if not set.
+    #[serde(default)]
+    pub center: Option<[f64; 2]>,
+}
+
+/// See [`crate::singleton::border`].
+#[derive(Component, Debug)]
+pub struct WorldBorder {
+    /// The diameter the border has in the lobby.
+    initial_diameter: f64,
+    /// The center the border has in the lobby.
+    initial_center: DVec2,
+    /// The stages of the battle.
+    stages: Vec<BorderStage>,
+    /// The damage per block a player is outside the safe zone.
+    damage_per_block: f64,
+    /// The distance players can be outside the border without taking damage.
+    safe_zone: f64,
+    /// The distance to the border at which the screen of players turns red.
+    warning_blocks: i32,
+    /// The number of seconds before a shrinking border reaches players at which their screen turns
+    /// red.
+    warning_time: i32,
+    /// The current center.
+    center: DVec2,
+    /// The diameter at the start of the current interpolation.
+    from: f64,
+    /// The diameter at the end of the current interpolation.
+    to: f64,
+    /// The tick the current interpolation started.
+    start_tick: i64,
+    /// The tick the current interpolation ends.
+    end_tick: i64,
+    /// The index of the next stage.
+    next_stage: usize,
+    /// The tick the next stage starts, `None` outside of the battle or after the last stage.
+    next_stage_tick: Option<i64>,
+}
+
+impl WorldBorder {
+    /// Validates the border of the given configuration.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let valid = |diameter: f64| diameter.is_finite() && diameter > 0.0;
+
+        let diameter = match config.border_diameter {
+            Some(diameter) => {
+                ensure!(
+                    valid(diameter),
+                    "the border diameter {diameter} is not positive"
+                );
+                diameter
+            }
+            None => {
+                ensure!(
+                    config.border_stages.is_empty(),
+                    "border stages need a border diameter"
+                );
+                MAX_DIAMETER
+            }
+        };
+
+        for stage in &config.border_stages {
+            ensure!(
+                valid(stage.diameter),
+                "the border stage diameter {} is not positive",
+                stage.diameter
+            );
+            ensure!(
+                stage.delay >= 0 && stage.duration >= 0,
+                "border stages cannot have a negative delay or duration"
+            );
+        }
+
+        ensure!(
+            config.border_damage_per_block >= 0.0 && config.border_safe_zone >= 0.0,
+            "the border damage and safe zone cannot be negative"
+        );
+
+        let center = DVec2::from(config.border_center);
+
+        Ok(Self {
+            initial_diameter: diameter,
+            initial_center: center,
+            stages: config.border_stages.clone(),
+            damage_per_block: config.border_damage_per_block,
+            safe_zone: config.border_safe_zone,
+            warning_blocks: config.border_warning_blocks,
+            warning_time: config.border_warning_time,
+            center,
+            from: diameter,
+            to: diameter,
+            start_tick: 0,
+            end_tick: 0,
+            next_stage: 0,
+            next_stage_tick: None,
+        })
+    }
+
+    /// The diameter on `tick`.
+    pub fn diameter(&self, tick: i64) -> f64 {
+        match self.shrink_progress(tick) {
+            Some(progress) => self.from + (self.to - self.from) * progress,
+            None => self.to,
+        }
+    }
+
+    /// The distance of `pos` to the closest side of the border on `tick`. It is negative if `pos`
+    /// is outside.
+    pub fn distance_inside(&self, pos: DVec3, tick: i64) -> f64 {
+        let radius = self.diameter(tick) / 2.0;
+        let offset = (DVec2::new(pos.x, pos.z) - self.center).abs();
+
+        radius - offset.max_element()
+    }
+
+    /// The damage a player at `pos` takes on `tick`, if any. Like vanilla, it grows by the damage
+    /// per block for every block outside the safe zone and is at least half a heart.
+    pub fn damage(&self, pos: DVec3, tick: i64) -> Option<f32> {
+        let distance = self.distance_inside(pos, tick) + self.safe_zone;
+
+        if distance >= 0.0 || self.damage_per_block <= 0.0 {
+            return None;
+        }
+
+        #[expect(clippy::cast_possible_truncation, reason = "damage is small")]
+        let damage = (-distance * self.damage_per_block).floor().max(1.0) as f32;
+
+        Some(damage)
+    }
+
+    /// The number of ticks until the next stage starts, if one is scheduled.
+    pub fn next_stage_in(&self, tick: i64) -> Option<i64> {
+        self.next_stage_tick.map(|start| (start - tick).max(0))
+    }
+
+    /// How far the border shrank in the current stage from `0.0` to `1.0`, `None` if it is not
+    /// shrinking.
+    pub fn shrink_progress(&self, tick: i64) -> Option<f64> {
+        if tick >= self.end_tick {
+            return None;
+        }
+
+        #[expect(clippy::cast_precision_loss, reason = "stages are short")]
+        let progress = (tick - self.start_tick) as f64 / (self.end_tick - self.start_tick) as f64;
+
+        Some(progress)
+    }
+
+    /// Schedules the stages once the battle starts on `tick`.
+    pub fn start(&mut self, tick: i64) {
+        self.next_stage = 0;
+        self.next_stage_tick = self.stages.first().map(|stage| tick + stage.delay);
+    }
+
+    /// Stops the stages and returns to the initial size and center on `tick`.
+    pub fn reset(&mut self, tick: i64) {
+        self.center = self.initial_center;
+        self.from = self.initial_diameter;
+        self.to = self.initial_diameter;
+        self.start_tick = tick;
+        self.end_tick = tick;
+        self.next_stage_tick = None;
+    }
+
+    /// Starts the next stage if it is due on `tick` and returns whether it did.
+    pub fn update(&mut self, tick: i64) -> bool {
+        if !matches!(self.next_stage_tick, Some(start) if tick >= start) {
+            return false;
+        }
+
+        let Some(stage) = self.stages.get(self.next_stage).copied() else {
+            self.next_stage_tick = None;
+            return false;
+        };
+
+        self.from = self.diameter(tick);
+        self.to = stage.diameter;
+        self.start_tick = tick;
+        self.end_tick = tick + stage.duration;
+
+        if let Some(center) = stage.center {
+            self.center = DVec2::from(center);
+        }
+
+        self.next_stage += 1;
+        self.next_stage_tick = self
+            .stages
+            .get(self.next_stage)
+            .map(|next| self.end_tick + next.delay);
+
+        true
+    }
+
+    /// The number of milliseconds until the current interpolation ends.
+    fn remaining_millis(&self, tick: i64) -> VarLong {
+        VarLong((self.end_tick - tick).max(0) * MILLIS_PER_TICK)
+    }
+
+    /// The packet which shows the border to a player who joins on `tick`.
+    pub fn initialize_packet(&self, tick: i64) -> play::WorldBorderInitializeS2c {
+        play::WorldBorderInitializeS2c {
+            x: self.center.x,
+            z: self.center.y,
+            old_diameter: self.diameter(tick),
+            new_diameter: self.to,
+            duration_millis: self.remaining_millis(tick),
+            portal_teleport_boundary: VarInt(PORTAL_TELEPORT_BOUNDARY),
+            warning_blocks: VarInt(self.warning_blocks),
+            warning_time: VarInt(self.warning_time),
+        }
+    }
+
+    /// The packets which show the current center and interpolation to players on `tick`.
+    pub fn update_packets(
+        &self,
+        tick: i64,
+    ) -> (
+        play::WorldBorderCenterChangedS2c,
+        play::WorldBorderInterpolateSizeS2c,
+    ) {
+        let center = play::WorldBorderCenterChangedS2c {
+            x_pos: self.center.x,
+            z_pos: self.center.y,
+        };
+
+        let size = play::WorldBorderInterpolateSizeS2c {
+            old_diameter: self.diameter(tick),
+            new_diameter: self.to,
+            duration_millis: self.remaining_millis(tick),
+        };
+
+        (center, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn border() -> WorldBorder {
+        let config = Config {
+            border_diameter: Some(100.0),
+            border_stages: vec![
+                BorderStage {
+                    delay: 100,
+                    diameter: 50.0,
+                    duration: 200,
+                    center: None,
+                },
+                BorderStage {
+                    delay: 0,
+                    diameter: 10.0,
+                    duration: 0,
+                    center: Some([20.0, 0.0]),
+                },
+            ],
+            border_damage_per_block: 0.2,
+            border_safe_zone: 5.0,
+            ..Config::default()
+        };
+
+        WorldBorder::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn stages_shrink_the_border_in_order() {
+        let mut border = border();
+        border.start(1000);
+
+        assert!(!border.update(1099));
+        assert!((border.diameter(1099) - 100.0).abs() < f64::EPSILON);
+
+        assert!(border.update(1100));
+        assert!((border.diameter(1200) - 75.0).abs() < f64::EPSILON);
+        assert_eq!(border.shrink_progress(1250), Some(0.75));
+        assert_eq!(border.next_stage_in(1250), Some(50));
+        assert!((border.diameter(1300) - 50.0).abs() < f64::EPSILON);
+
+        // the next stage starts once the previous one is done
+        assert!(!border.update(1299));
+        assert!(border.update(1300));
+        assert!((border.diameter(1300) - 10.0).abs() < f64::EPSILON);
+        assert_eq!(border.center, DVec2::new(20.0, 0.0));
+
+        assert!(!border.update(5000));
+
+        border.reset(6000);
+        assert!((border.diameter(6000) - 100.0).abs() < f64::EPSILON);
+        assert_eq!(border.center, DVec2::ZERO);
+    }
+
+    #[test]
+    fn players_outside_the_safe_zone_take_damage() {
+        let border = border();
+
+        // the border is 50 blocks from the center
+        assert!((border.distance_inside(DVec3::new(0.0, 64.0, 40.0), 0) - 10.0).abs() < 1e-9);
+        assert!((border.distance_inside(DVec3::new(-60.0, 64.0, 0.0), 0) + 10.0).abs() < 1e-9);
+
+        assert_eq!(border.damage(DVec3::new(52.0, 64.0, 0.0), 0), None);
+        assert_eq!(border.damage(DVec3::new(0.0, 64.0, 56.0), 0), Some(1.0));
+        assert_eq!(border.damage(DVec3::new(0.0, 64.0, -75.0), 0), Some(4.0));
+    }
+
+    #[test]
+    fn stages_need_a_border() {
+        let config = Config {
+            border_diameter: None,
+            border_stages: vec![BorderStage {
+                delay: 0,
+                diameter: 10.0,
+                duration: 20,
+                center: None,
+            }],
+            ..Config::default()
+        };
+
+        assert!(WorldBorder::from_config(&config).is_err());
+    }
+}
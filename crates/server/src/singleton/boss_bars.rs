@@ -0,0 +1,355 @@
+//! Boss bars shown at the top of the screen, i.e., the round timer.
+//!
+//! Systems show a [`BossBar`] by its UUID and change it as often as they like. Once per tick,
+//! `system::sync_boss_bars` works out which players should see which bars, adds bars to players who
+//! joined or entered their [`Audience`], sends what changed to the players who see a bar and
+//! removes bars players should no longer see. Players who left are forgotten.
+//!
+//! Progress is sent at most every [`PROGRESS_INTERVAL`] ticks, so bars which change every tick,
+//! like the round timer, cost each viewer at most one packet per second.
+use std::collections::{BTreeMap, BTreeSet};
+
+use evenio::{component::Component, entity::EntityId};
+use glam::Vec3;
+use uuid::Uuid;
+use valence_protocol::{
+    packets::play::{
+        self,
+        boss_bar_s2c::{BossBarAction, BossBarColor, BossBarDivision, BossBarFlags},
+    },
+    text::{IntoText, Text},
+};
+
+use crate::{
+    global::TICKS_PER_SECOND,
+    singleton::{announcements::Audience, teams::Team},
+};
+
+/// The least number of ticks between two progress updates of a bar.
+pub const PROGRESS_INTERVAL: i64 = TICKS_PER_SECOND;
+
+/// A boss bar, see [`crate::singleton::boss_bars`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BossBar {
+    /// The text above the bar.
+    pub title: Text,
+    /// How full the bar is from `0.0` to `1.0`.
+    pub progress: f32,
+    /// The color of the bar.
+    pub color: BossBarColor,
+    /// The notches of the bar.
+    pub division: BossBarDivision,
+    /// The players who see the bar.
+    pub audience: Audience,
+}
+
+impl BossBar {
+    /// A full white bar without notches which every player sees.
+    pub fn new(title: impl IntoText<'static>) -> Self {
+        Self {
+            title: title.into_text(),
+            progress: 1.0,
+            color: BossBarColor::White,
+            division: BossBarDivision::NoDivision,
+            audience: Audience::Everyone,
+        }
+    }
+
+    /// Sets how full the bar is, clamped to `0.0` to `1.0`.
+    #[must_use]
+    pub fn progress(mut self, progress: f32) -> Self {
+        self.progress = progress.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the color of the bar.
+    #[must_use]
+    pub const fn color(mut self, color: BossBarColor) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the notches of the bar.
+    #[must_use]
+    pub const fn division(mut self, division: BossBarDivision) -> Self {
+        self.division = division;
+        self
+    }
+
+    /// Only shows the bar to `audience`.
+    #[must_use]
+    pub const fn audience(mut self, audience: Audience) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    /// The packet which adds the bar to a player.
+    fn add_packet(&self, id: Uuid) -> play::BossBarS2c<'static> {
+        play::BossBarS2c {
+            id,
+            action: BossBarAction::Add {
+                title: self.title.clone().into(),
+                health: self.progress,
+                color: self.color,
+                division: self.division,
+                flags: BossBarFlags::new(),
+            },
+        }
+    }
+
+    /// The packets which update a player who sees `old` to this bar. The progress is only sent
+    /// if `progress` is set.
+    fn update_packets(
+        &self,
+        old: &Self,
+        id: Uuid,
+        progress: bool,
+    ) -> Vec<play::BossBarS2c<'static>> {
+        let mut actions = Vec::new();
+
+        if self.title != old.title {
+            actions.push(BossBarAction::UpdateTitle(self.title.clone().into()));
+        }
+
+        if progress && (self.progress - old.progress).abs() > f32::EPSILON {
+            actions.push(BossBarAction::UpdateHealth(self.progress));
+        }
+
+        if self.color != old.color || self.division != old.division {
+            actions.push(BossBarAction::UpdateStyle(self.color, self.division));
+        }
+
+        actions
+            .into_iter()
+            .map(|action| play::BossBarS2c { id, action })
+            .collect()
+    }
+}
+
+/// A bar and what its viewers were last sent.
+#[derive(Debug)]
+struct Entry {
+    /// The bar as it should be.
+    bar: BossBar,
+    /// The bar as it was last sent.
+    sent: BossBar,
+    /// The players who see the bar.
+    viewers: BTreeSet<EntityId>,
+    /// The tick the progress was last sent.
+    progress_tick: i64,
+}
+
+/// See [`crate::singleton::boss_bars`].
+#[derive(Component, Debug, Default)]
+pub struct BossBars {
+    /// All bars by their UUID.
+    bars: BTreeMap<Uuid, Entry>,
+    /// The viewers of bars which were hidden since the last sync.
+    hidden: Vec<(Uuid, BTreeSet<EntityId>)>,
+}
+
+impl BossBars {
+    /// Shows a bar, or changes it if a bar with the same UUID is shown already.
+    pub fn show(&mut self, id: Uuid, bar: BossBar) {
+        match self.bars.get_mut(&id) {
+            Some(entry) => entry.bar = bar,
+            None => {
+                self.bars.insert(
+                    id,
+                    Entry {
+                        sent: bar.clone(),
+                        bar,
+                        viewers: BTreeSet::new(),
+                        progress_tick: i64::MIN,
+                    },
+                );
+            }
+        }
+    }
+
+    /// The bar with the given UUID, if it is shown.
+    pub fn get(&self, id: Uuid) -> Option<&BossBar> {
+        self.bars.get(&id).map(|entry| &entry.bar)
+    }
+
+    /// Changes the bar with the given UUID, if it is shown.
+    pub fn get_mut(&mut self, id: Uuid) -> Option<&mut BossBar> {
+        self.bars.get_mut(&id).map(|entry| &mut entry.bar)
+    }
+
+    /// Hides the bar with the given UUID from every player.
+    pub fn hide(&mut self, id: Uuid) {
+        if let Some(entry) = self.bars.remove(&id) {
+            self.hidden.push((id, entry.viewers));
+        }
+    }
+
+    /// Returns the packets which bring the `players` who are online with their team and position
+    /// up to date on `tick`. Players who are not in `players` left and are forgotten.
+    pub fn sync(
+        &mut self,
+        players: &[(EntityId, Team, Vec3)],
+        tick: i64,
+    ) -> Vec<(EntityId, play::BossBarS2c<'static>)> {
+        let mut packets = Vec::new();
+
+        let online: BTreeSet<_> = players.iter().map(|(id, ..)| *id).collect();
+
+        for (id, viewers) in std::mem::take(&mut self.hidden) {
+            for viewer in viewers.intersection(&online) {
+                let pkt = play::BossBarS2c {
+                    id,
+                    action: BossBarAction::Remove,
+                };
+                packets.push((*viewer, pkt));
+            }
+        }
+
+        for (&id, entry) in &mut self.bars {
+            entry.viewers.retain(|viewer| online.contains(viewer));
+
+            // empty and full bars are sent right away so they never look a second behind
+            let progress = entry.bar.progress;
+            let send_progress = tick.saturating_sub(entry.progress_tick) >= PROGRESS_INTERVAL
+                || progress == 0.0
+                || progress == 1.0;
+
+            let updates = entry.bar.update_packets(&entry.sent, id, send_progress);
+
+            for &(player, team, position) in players {
+                let sees = entry.bar.audience.includes(player, team, position);
+                let saw = entry.viewers.contains(&player);
+
+                match (saw, sees) {
+                    (false, true) => {
+                        entry.viewers.insert(player);
+                        packets.push((player, entry.bar.add_packet(id)));
+                    }
+                    (true, false) => {
+                        entry.viewers.remove(&player);
+
+                        let pkt = play::BossBarS2c {
+                            id,
+                            action: BossBarAction::Remove,
+                        };
+                        packets.push((player, pkt));
+                    }
+                    (true, true) => {
+                        let updates = updates.iter().cloned().map(|pkt| (player, pkt));
+                        packets.extend(updates);
+                    }
+                    (false, false) => {}
+                }
+            }
+
+            let mut sent = entry.bar.clone();
+
+            if !send_progress {
+                sent.progress = entry.sent.progress;
+            } else if (progress - entry.sent.progress).abs() > f32::EPSILON {
+                entry.progress_tick = tick;
+            }
+
+            entry.sent = sent;
+        }
+
+        packets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(packets: &[(EntityId, play::BossBarS2c<'static>)]) -> Vec<(EntityId, String)> {
+        packets
+            .iter()
+            .map(|(player, pkt)| {
+                let action = match &pkt.action {
+                    BossBarAction::Add { .. } => "add",
+                    BossBarAction::Remove => "remove",
+                    BossBarAction::UpdateHealth(_) => "health",
+                    BossBarAction::UpdateTitle(_) => "title",
+                    BossBarAction::UpdateStyle(..) => "style",
+                    BossBarAction::UpdateFlags(_) => "flags",
+                };
+
+                (*player, action.to_owned())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bars_are_only_sent_to_their_audience() {
+        let red = Team(Some(0));
+        let blue = Team(Some(1));
+        let alice = EntityId::NULL;
+
+        let mut bars = BossBars::default();
+        let timer = Uuid::from_u128(1);
+        let flag = Uuid::from_u128(2);
+
+        bars.show(timer, BossBar::new("Time left"));
+        bars.show(
+            flag,
+            BossBar::new("Capture the flag").audience(Audience::Team(blue)),
+        );
+
+        let packets = bars.sync(&[(alice, red, Vec3::ZERO)], 0);
+        assert_eq!(actions(&packets), [(alice, "add".to_owned())]);
+
+        // only what changed is sent
+        assert!(bars.sync(&[(alice, red, Vec3::ZERO)], 0).is_empty());
+
+        bars.get_mut(timer).unwrap().progress = 0.5;
+        let packets = bars.sync(&[(alice, red, Vec3::ZERO)], 0);
+        assert_eq!(actions(&packets), [(alice, "health".to_owned())]);
+
+        // switching teams swaps the team bars
+        let packets = bars.sync(&[(alice, blue, Vec3::ZERO)], 0);
+        assert_eq!(actions(&packets), [(alice, "add".to_owned())]);
+        assert_eq!(packets[0].1.id, flag);
+
+        bars.hide(timer);
+        let packets = bars.sync(&[(alice, blue, Vec3::ZERO)], 0);
+        assert_eq!(actions(&packets), [(alice, "remove".to_owned())]);
+        assert_eq!(packets[0].1.id, timer);
+    }
+
+    #[test]
+    fn progress_is_sent_at_most_once_per_interval() {
+        let mut bars = BossBars::default();
+        let timer = Uuid::from_u128(1);
+        let player = [(EntityId::NULL, Team(None), Vec3::ZERO)];
+
+        bars.show(timer, BossBar::new("Time left"));
+        bars.sync(&player, 0);
+
+        let mut updates = 0;
+        for tick in 1..=PROGRESS_INTERVAL * 5 / 2 {
+            bars.get_mut(timer).unwrap().progress = 0.9 - tick as f32 / 1000.0;
+            updates += bars.sync(&player, tick).len();
+        }
+        assert_eq!(updates, 3);
+
+        // running out is sent right away
+        bars.get_mut(timer).unwrap().progress = 0.0;
+        assert_eq!(bars.sync(&player, PROGRESS_INTERVAL * 5 / 2 + 1).len(), 1);
+    }
+
+    #[test]
+    fn players_who_left_are_forgotten() {
+        let mut bars = BossBars::default();
+        let timer = Uuid::from_u128(1);
+        let player = EntityId::NULL;
+
+        bars.show(timer, BossBar::new("Time left"));
+        assert_eq!(bars.sync(&[(player, Team(None), Vec3::ZERO)], 0).len(), 1);
+
+        assert!(bars.sync(&[], 0).is_empty());
+        assert!(bars.bars[&timer].viewers.is_empty());
+
+        bars.hide(timer);
+        assert!(bars.sync(&[], 0).is_empty());
+    }
+}
@@ -0,0 +1,486 @@
+//! The kits players are given when they join or respawn.
+//!
+//! Kits are defined in the [`crate::config::Config`]. Every player gets the kit of their team, or
+//! the kit of the current round if their team has none, or else the default kit. Admins can give
+//! a player any kit with `/kit give <kit> [player]`, which they keep until `/kit reset [player]`.
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure, Context};
+use evenio::component::Component;
+use serde::{Deserialize, Serialize};
+use valence_protocol::{
+    nbt::{Compound, List, Value},
+    ItemKind, ItemStack,
+};
+
+use crate::{
+    components::{
+        inventory::{Inventory, BOOTS, HELMET, HOTBAR, HOTBAR_SIZE, OFFHAND, SLOT_COUNT},
+        status_effects::{EffectKind, StatusEffect, StatusEffects},
+    },
+    config::Config,
+};
+
+/// The first slot of the main inventory.
+const MAIN_INVENTORY: usize = 9;
+
+/// A kit as defined in the configuration.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KitConfig {
+    /// The name used to refer to the kit, i.e., in `/kit give`.
+    pub name: String,
+    /// The items in the hotbar from the first slot on.
+    #[serde(default)]
+    pub hotbar: Vec<KitItem>,
+    /// The items in the main inventory from its top left slot on.
+    #[serde(default)]
+    pub inventory: Vec<KitItem>,
+    pub helmet: Option<KitItem>,
+    pub chestplate: Option<KitItem>,
+    pub leggings: Option<KitItem>,
+    pub boots: Option<KitItem>,
+    pub offhand: Option<KitItem>,
+    /// The effects players get with the kit.
+    #[serde(default)]
+    pub effects: Vec<KitEffect>,
+}
+
+/// An item stack in a [`KitConfig`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KitItem {
+    /// The name of the item, i.e., `netherite_sword`.
+    pub item: String,
+    /// The number of items in the stack.
+    #[serde(default = "KitItem::default_count")]
+    pub count: i8,
+    /// The levels of enchantments by their name, i.e., `sharpness = 5`.
+    #[serde(default)]
+    pub enchantments: BTreeMap<String, i16>,
+    /// Additional NBT of the item. Integers become `int` tags, which vanilla also accepts where it
+    /// expects a `byte` or `short`.
+    pub nbt: Option<toml::Table>,
+}
+
+impl KitItem {
+    const fn default_count() -> i8 {
+        1
+    }
+
+    /// A single item without enchantments.
+    fn new(item: &str) -> Self {
+        Self {
+            item: item.to_owned(),
+            count: 1,
+            enchantments: BTreeMap::new(),
+            nbt: None,
+        }
+    }
+
+    fn to_stack(&self) -> anyhow::Result<ItemStack> {
+        let kind = ItemKind::from_str(&self.item)
+            .with_context(|| format!("unknown item {}", self.item))?;
+
+        let mut nbt = match &self.nbt {
+            Some(table) => table_to_nbt(table)?,
+            None => Compound::new(),
+        };
+
+        if !self.enchantments.is_empty() {
+            let enchantments = self
+                .enchantments
+                .iter()
+                .map(|(name, &level)| {
+                    let mut enchantment = Compound::new();
+                    enchantment.insert("id", format!("minecraft:{name}"));
+                    enchantment.insert("lvl", level);
+                    enchantment
+                })
+                .collect();
+
+            nbt.insert("Enchantments", List::Compound(enchantments));
+        }
+
+        let nbt = (!nbt.is_empty()).then_some(nbt);
+
+        Ok(ItemStack::new(kind, self.count, nbt))
+    }
+}
+
+/// A status effect in a [`KitConfig`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KitEffect {
+    /// The kind of the effect, i.e., `absorption`.
+    pub effect: EffectKind,
+    /// The amplifier of the effect, `0` is level I.
+    #[serde(default)]
+    pub amplifier: u8,
+    /// The duration of the effect in ticks.
+    pub duration: i64,
+    /// Whether the player emits particles.
+    #[serde(default = "KitEffect::default_shown")]
+    pub particles: bool,
+    /// Whether the player sees an icon of the effect.
+    #[serde(default = "KitEffect::default_shown")]
+    pub icon: bool,
+}
+
+impl KitEffect {
+    const fn default_shown() -> bool {
+        true
+    }
+}
+
+/// A kit which was validated and turned into item stacks.
+#[derive(Debug)]
+pub struct Kit {
+    /// See [`KitConfig::name`].
+    pub name: String,
+    /// The items by their inventory slot.
+    slots: Vec<(usize, ItemStack)>,
+    /// The effects the kit gives.
+    effects: Vec<KitEffect>,
+}
+
+impl Kit {
+    fn new(config: &KitConfig) -> anyhow::Result<Self> {
+        ensure!(
+            config.hotbar.len() <= usize::from(HOTBAR_SIZE),
+            "kit {} has more than {HOTBAR_SIZE} hotbar items",
+            config.name
+        );
+        ensure!(
+            config.inventory.len() <= HOTBAR - MAIN_INVENTORY,
+            "kit {} has more than {} inventory items",
+            config.name,
+            HOTBAR - MAIN_INVENTORY
+        );
+
+        let hotbar = (HOTBAR..).zip(&config.hotbar);
+        let inventory = (MAIN_INVENTORY..).zip(&config.inventory);
+
+        let equipment = [
+            (HELMET, &config.helmet),
+            (HELMET + 1, &config.chestplate),
+            (HELMET + 2, &config.leggings),
+            (BOOTS, &config.boots),
+            (OFFHAND, &config.offhand),
+        ]
+        .into_iter()
+        .filter_map(|(slot, item)| Some((slot, item.as_ref()?)));
+
+        let slots = hotbar
+            .chain(inventory)
+            .chain(equipment)
+            .map(|(slot, item)| Ok((slot, item.to_stack()?)))
+            .collect::<anyhow::Result<_>>()
+            .with_context(|| format!("invalid item in kit {}", config.name))?;
+
+        Ok(Self {
+            name: config.name.clone(),
+            slots,
+            effects: config.effects.clone(),
+        })
+    }
+
+    /// Replaces the inventory of a player with the kit and gives them its effects. The client has
+    /// to be sent the inventory afterwards.
+    pub fn apply(&self, inventory: &mut Inventory, effects: &mut StatusEffects, tick: i64) {
+        for slot in 0..SLOT_COUNT {
+            inventory.set(slot, ItemStack::EMPTY);
+        }
+
+        for (slot, stack) in &self.slots {
+            inventory.set(*slot, stack.clone());
+        }
+
+        for effect in &self.effects {
+            let status_effect = StatusEffect {
+                particles: effect.particles,
+                icon: effect.icon,
+                ..StatusEffect::new(effect.amplifier, tick, effect.duration)
+            };
+
+            effects.add(effect.effect, status_effect);
+        }
+    }
+}
+
+/// The kit an admin gave a player with `/kit give`. It replaces the kit assigned to the player
+/// until it is reset.
+#[derive(Component, Debug, Default)]
+pub struct GivenKit(pub Option<usize>);
+
+/// See [`crate::singleton::kits`].
+#[derive(Component, Debug)]
+pub struct Kits {
+    /// All kits.
+    kits: Vec<Kit>,
+    /// The kit of players without any other kit.
+    default: Option<usize>,
+    /// The kits of teams by the name of the team.
+    teams: BTreeMap<String, usize>,
+    /// The kit of every round. Rounds after the last one use the kit of the last round.
+    rounds: Vec<usize>,
+    /// The current round, starting at `0`.
+    pub round: usize,
+}
+
+impl Kits {
+    /// Validates the kits of the given configuration.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let kits: Vec<_> = config.kits.iter().map(Kit::new).collect::<Result<_, _>>()?;
+
+        let find = |name: &str| {
+            kits.iter()
+                .position(|kit| kit.name == name)
+                .with_context(|| format!("kit {name} does not exist"))
+        };
+
+        let default = match &config.default_kit {
+            Some(name) => Some(find(name)?),
+            None => (!kits.is_empty()).then_some(0),
+        };
+
+        let teams = config
+            .team_kits
+            .iter()
+            .map(|(team, kit)| Ok((team.clone(), find(kit)?)))
+            .collect::<anyhow::Result<_>>()?;
+
+        let rounds = config
+            .round_kits
+            .iter()
+            .map(|kit| find(kit))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            kits,
+            default,
+            teams,
+            rounds,
+            round: 0,
+        })
+    }
+
+    /// The index of the kit with the given name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.kits.iter().position(|kit| kit.name == name)
+    }
+
+    /// The names of all kits.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.kits.iter().map(|kit| kit.name.as_str())
+    }
+
+    /// The kit of a player in the given team who was given `given`.
+    pub fn kit_for(&self, given: &GivenKit, team: Option<&str>) -> Option<&Kit> {
+        let round = || {
+            let last = self.rounds.len().checked_sub(1)?;
+            self.rounds.get(self.round.min(last)).copied()
+        };
+
+        let idx = given
+            .0
+            .or_else(|| self.teams.get(team?).copied())
+            .or_else(round)
+            .or(self.default)?;
+
+        self.kits.get(idx)
+    }
+}
+
+/// The kit every player gets if no other kits are configured.
+pub fn netherite_kit() -> KitConfig {
+    KitConfig {
+        name: "netherite".to_owned(),
+        hotbar: vec![KitItem::new("netherite_sword")],
+        inventory: Vec::new(),
+        helmet: Some(KitItem::new("netherite_helmet")),
+        chestplate: Some(KitItem::new("netherite_chestplate")),
+        leggings: Some(KitItem::new("netherite_leggings")),
+        boots: Some(KitItem::new("netherite_boots")),
+        offhand: None,
+        effects: Vec::new(),
+    }
+}
+
+/// Converts a TOML table to NBT.
+fn table_to_nbt(table: &toml::Table) -> anyhow::Result<Compound> {
+    let mut compound = Compound::new();
+
+    for (key, value) in table {
+        let value = toml_to_nbt(value).with_context(|| format!("invalid NBT in {key}"))?;
+        compound.insert(key.as_str(), value);
+    }
+
+    Ok(compound)
+}
+
+fn toml_to_nbt(value: &toml::Value) -> anyhow::Result<Value> {
+    let value = match value {
+        toml::Value::String(string) => Value::String(string.clone()),
+        toml::Value::Integer(integer) => match i32::try_from(*integer) {
+            Ok(integer) => Value::Int(integer),
+            Err(_) => Value::Long(*integer),
+        },
+        toml::Value::Float(float) => Value::Double(*float),
+        toml::Value::Boolean(boolean) => Value::Byte(i8::from(*boolean)),
+        toml::Value::Table(table) => Value::Compound(table_to_nbt(table)?),
+        toml::Value::Array(array) => Value::List(array_to_nbt(array)?),
+        toml::Value::Datetime(_) => bail!("dates cannot be NBT"),
+    };
+
+    Ok(value)
+}
+
+/// NBT lists must only contain one type, so all elements have to be converted to the type of
+/// the first one.
+fn array_to_nbt(array: &[toml::Value]) -> anyhow::Result<List> {
+    let list = match array.first() {
+        None => List::End,
+        Some(toml::Value::String(_)) => List::String(
+            array
+                .iter()
+                .map(|value| match value {
+                    toml::Value::String(string) => Ok(string.clone()),
+                    _ => bail!("mixed types in a list"),
+                })
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Some(toml::Value::Integer(_)) => List::Int(
+            array
+                .iter()
+                .map(|value| match value {
+                    toml::Value::Integer(integer) => Ok(i32::try_from(*integer)?),
+                    _ => bail!("mixed types in a list"),
+                })
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Some(toml::Value::Table(_)) => List::Compound(
+            array
+                .iter()
+                .map(|value| match value {
+                    toml::Value::Table(table) => table_to_nbt(table),
+                    _ => bail!("mixed types in a list"),
+                })
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Some(_) => bail!("only lists of strings, integers and tables are supported"),
+    };
+
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::{enchantment_level, PROTECTION, SHARPNESS};
+
+    fn config(kits: &str) -> Config {
+        toml::from_str(kits).unwrap()
+    }
+
+    #[test]
+    fn kit_items_become_stacks() {
+        let config = config(
+            r#"
+            default_kit = "archer"
+
+            [[kits]]
+            name = "archer"
+            hotbar = [
+                { item = "iron_sword", enchantments = { sharpness = 2 } },
+                { item = "bow" },
+                { item = "arrow", count = 32 },
+            ]
+            helmet = { item = "iron_helmet", enchantments = { protection = 4 } }
+            offhand = { item = "shield", nbt = { display = { Name = '{"text":"Wall"}' } } }
+            effects = [{ effect = "regeneration", amplifier = 1, duration = 100 }]
+            "#,
+        );
+
+        let kits = Kits::from_config(&config).unwrap();
+        let kit = kits.kit_for(&GivenKit::default(), None).unwrap();
+
+        let mut inventory = Inventory::netherite();
+        let mut effects = StatusEffects::default();
+        kit.apply(&mut inventory, &mut effects, 10);
+
+        assert_eq!(enchantment_level(inventory.main_hand(), SHARPNESS), 2);
+        assert_eq!(inventory.get(HOTBAR + 2).count, 32);
+        assert_eq!(enchantment_level(inventory.get(HELMET), PROTECTION), 4);
+        assert!(inventory.get(BOOTS).is_empty());
+        assert_eq!(inventory.off_hand().item, ItemKind::Shield);
+        assert!(inventory.off_hand().nbt.is_some());
+
+        let regeneration = effects.get(EffectKind::Regeneration).unwrap();
+        assert_eq!(regeneration.amplifier, 1);
+        assert_eq!(regeneration.end_tick, 110);
+    }
+
+    #[test]
+    fn kits_are_assigned_by_team_and_round() {
+        let config = config(
+            r#"
+            default_kit = "a"
+            round_kits = ["b", "c"]
+
+            [team_kits]
+            red = "d"
+
+            [[kits]]
+            name = "a"
+
+            [[kits]]
+            name = "b"
+
+            [[kits]]
+            name = "c"
+
+            [[kits]]
+            name = "d"
+            "#,
+        );
+
+        let mut kits = Kits::from_config(&config).unwrap();
+        let name = |kits: &Kits, given, team| {
+            kits.kit_for(&GivenKit(given), team)
+                .map(|kit| kit.name.clone())
+        };
+
+        assert_eq!(name(&kits, None, Some("red")).as_deref(), Some("d"));
+        assert_eq!(name(&kits, None, Some("blue")).as_deref(), Some("b"));
+
+        kits.round = 5;
+        assert_eq!(name(&kits, None, None).as_deref(), Some("c"));
+
+        assert_eq!(
+            name(&kits, kits.find("a"), Some("red")).as_deref(),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn invalid_kits_are_rejected() {
+        let unknown_item = config(
+            r#"
+            [[kits]]
+            name = "a"
+            hotbar = [{ item = "laser_sword" }]
+            "#,
+        );
+        assert!(Kits::from_config(&unknown_item).is_err());
+
+        let unknown_kit = config(r#"default_kit = "b""#);
+        assert!(Kits::from_config(&unknown_kit).is_err());
+
+        let unknown_effect = toml::from_str::<Config>(
+            r#"
+            [[kits]]
+            name = "a"
+            effects = [{ effect = "flight", duration = 10 }]
+            "#,
+        );
+        assert!(unknown_effect.is_err());
+    }
+}
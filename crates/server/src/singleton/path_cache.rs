@@ -0,0 +1,87 @@
+//! Paths which were recently found by [`crate::system::entity_pathfinding`].
+//!
+//! Mobs chasing the same target usually walk along the same route, so before searching for a new
+//! path a mob checks whether it is standing next to a waypoint of a cached path with the same goal
+//! and joins that path instead.
+use std::sync::Arc;
+
+use evenio::component::Component;
+use fxhash::FxHashMap;
+use glam::IVec3;
+
+/// The number of ticks a path is shared for after it was found.
+pub const PATH_TTL: i64 = 40;
+
+/// A path found by [`crate::pathfinding::find_path`].
+#[derive(Debug)]
+pub struct Path {
+    /// The block the path leads to.
+    pub goal: IVec3,
+    /// The positions to walk through in order. The last one is next to the goal.
+    pub waypoints: Vec<IVec3>,
+    /// The tick the path was found in.
+    pub created: i64,
+}
+
+/// The cached paths for one goal.
+#[derive(Debug, Default)]
+struct Entry {
+    paths: Vec<Arc<Path>>,
+    /// Maps every waypoint to the path and index of the waypoint.
+    waypoints: FxHashMap<IVec3, (usize, usize)>,
+}
+
+/// See [`crate::singleton::path_cache`].
+#[derive(Component, Debug, Default)]
+pub struct PathCache {
+    entries: FxHashMap<IVec3, Entry>,
+    /// The number of nodes which may still be expanded this tick.
+    pub budget: usize,
+}
+
+impl PathCache {
+    /// Finds a cached path to `goal` which passes next to `pos`. Returns the path and the index of
+    /// the waypoint to continue from.
+    pub fn find(&self, goal: IVec3, pos: IVec3) -> Option<(Arc<Path>, usize)> {
+        let entry = self.entries.get(&goal)?;
+
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let near = pos + IVec3::new(x, 0, z);
+
+                let Some(&(path_idx, waypoint)) = entry.waypoints.get(&near) else {
+                    continue;
+                };
+
+                let path = entry.paths.get(path_idx)?;
+                return Some((path.clone(), waypoint));
+            }
+        }
+
+        None
+    }
+
+    /// Caches a path so other mobs can use it.
+    pub fn insert(&mut self, path: Arc<Path>) {
+        let entry = self.entries.entry(path.goal).or_default();
+        let path_idx = entry.paths.len();
+
+        for (idx, &waypoint) in path.waypoints.iter().enumerate() {
+            entry.waypoints.entry(waypoint).or_insert((path_idx, idx));
+        }
+
+        entry.paths.push(path);
+    }
+
+    /// Removes all goals whose first path is older than [`PATH_TTL`] and resets the budget.
+    pub fn start_tick(&mut self, tick: i64, budget: usize) {
+        self.entries.retain(|_, entry| {
+            entry
+                .paths
+                .first()
+                .is_some_and(|path| tick - path.created < PATH_TTL)
+        });
+
+        self.budget = budget;
+    }
+}
@@ -0,0 +1,197 @@
+//! The phases of a match.
+//!
+//! A match starts in the [`Phase::Lobby`], where players wait until enough of them joined. The
+//! [`Phase::Countdown`] announces the start, the [`Phase::Battle`] is the only phase in which
+//! players can hurt each other and the [`Phase::End`] announces the winners before the next round
+//! starts in the lobby. Phases switch by themselves as configured in the
+//! [`crate::config::Config`] or when an admin uses `/phase <phase>`. Every switch sends a
+//! [`PhaseChanged`](crate::events::PhaseChanged) event.
+use evenio::component::Component;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// A phase of a match, see [`crate::singleton::phase`].
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Players wait for enough players to join.
+    Lobby,
+    /// The battle is about to start.
+    Countdown,
+    /// Players fight.
+    Battle,
+    /// The winners are announced and combat is frozen.
+    End,
+}
+
+impl Phase {
+    /// All phases in the order they follow each other.
+    pub const ALL: [Self; 4] = [Self::Lobby, Self::Countdown, Self::Battle, Self::End];
+
+    /// The name of the phase, i.e., in `/phase battle`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Lobby => "lobby",
+            Self::Countdown => "countdown",
+            Self::Battle => "battle",
+            Self::End => "end",
+        }
+    }
+
+    /// The phase with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+
+    /// Whether players can hurt each other.
+    pub const fn pvp(self) -> bool {
+        matches!(self, Self::Battle)
+    }
+}
+
+/// See [`crate::singleton::phase`].
+#[derive(Component, Debug)]
+pub struct PhaseController {
+    /// The current phase.
+    phase: Phase,
+    /// The tick the current phase started.
+    start_tick: i64,
+    /// The number of players online when they were last counted.
+    players: usize,
+}
+
+impl Default for PhaseController {
+    fn default() -> Self {
+        Self {
+            phase: Phase::Lobby,
+            start_tick: 0,
+            players: 0,
+        }
+    }
+}
+
+impl PhaseController {
+    /// The current phase.
+    pub const fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// The number of ticks since the current phase started.
+    pub const fn elapsed(&self, tick: i64) -> i64 {
+        tick - self.start_tick
+    }
+
+    /// The number of ticks until the current phase ends by itself, if it does.
+    pub fn remaining(&self, tick: i64, config: &Config) -> Option<i64> {
+        let duration = match self.phase {
+            Phase::Lobby => return None,
+            Phase::Countdown => config.countdown_ticks,
+            Phase::Battle => config.battle_ticks?,
+            Phase::End => config.end_ticks,
+        };
+
+        Some((duration - self.elapsed(tick)).max(0))
+    }
+
+    /// The phase the match switches to by itself on `tick` with `players` online, if any.
+    pub fn next(&self, tick: i64, players: usize, config: &Config) -> Option<Phase> {
+        let enough_players = players >= config.lobby_players;
+        let over = self.remaining(tick, config) == Some(0);
+
+        match self.phase {
+            Phase::Lobby if enough_players => Some(Phase::Countdown),
+            // the countdown stops if players leave
+            Phase::Countdown if !enough_players => Some(Phase::Lobby),
+            Phase::Countdown if over => Some(Phase::Battle),
+            Phase::Battle if over => Some(Phase::End),
+            Phase::End if over => Some(Phase::Lobby),
+            _ => None,
+        }
+    }
+
+    /// Records the number of players online and returns whether it changed.
+    pub fn count_players(&mut self, players: usize) -> bool {
+        let changed = self.players != players;
+        self.players = players;
+        changed
+    }
+
+    /// Switches to `phase` on `tick` and returns the previous phase.
+    pub fn switch(&mut self, phase: Phase, tick: i64) -> Phase {
+        let previous = self.phase;
+
+        self.phase = phase;
+        self.start_tick = tick;
+
+        previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            lobby_players: 2,
+            countdown_ticks: 100,
+            battle_ticks: Some(1000),
+            end_ticks: 50,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn phases_follow_each_other() {
+        let config = config();
+        let mut controller = PhaseController::default();
+
+        assert_eq!(controller.next(0, 1, &config), None);
+        assert_eq!(controller.next(0, 2, &config), Some(Phase::Countdown));
+        assert_eq!(controller.switch(Phase::Countdown, 0), Phase::Lobby);
+
+        assert_eq!(controller.remaining(40, &config), Some(60));
+        assert_eq!(controller.next(99, 2, &config), None);
+        assert_eq!(controller.next(100, 2, &config), Some(Phase::Battle));
+        controller.switch(Phase::Battle, 100);
+
+        // players leaving do not stop the battle
+        assert_eq!(controller.next(500, 1, &config), None);
+        assert_eq!(controller.next(1100, 1, &config), Some(Phase::End));
+        controller.switch(Phase::End, 1100);
+
+        assert_eq!(controller.next(1150, 0, &config), Some(Phase::Lobby));
+    }
+
+    #[test]
+    fn countdown_stops_without_enough_players() {
+        let config = config();
+        let mut controller = PhaseController::default();
+        controller.switch(Phase::Countdown, 0);
+
+        assert_eq!(controller.next(10, 1, &config), Some(Phase::Lobby));
+    }
+
+    #[test]
+    fn battles_without_a_time_limit_never_end() {
+        let config = Config {
+            battle_ticks: None,
+            ..config()
+        };
+        let mut controller = PhaseController::default();
+        controller.switch(Phase::Battle, 0);
+
+        assert_eq!(controller.remaining(1_000_000, &config), None);
+        assert_eq!(controller.next(1_000_000, 5, &config), None);
+    }
+
+    #[test]
+    fn phases_are_found_by_name() {
+        for phase in Phase::ALL {
+            assert_eq!(Phase::from_name(phase.name()), Some(phase));
+        }
+
+        assert_eq!(Phase::from_name("intermission"), None);
+    }
+}
@@ -6,7 +6,6 @@ use evenio::{entity::EntityId, prelude::Component};
 #[derive(Debug, Copy, Clone)]
 pub struct LookupData {
     /// The entity id of the player
-    #[expect(dead_code, reason = "this is not being used yet")]
     pub id: EntityId,
     /// The bounding box of the player
     pub aabb: Aabb,
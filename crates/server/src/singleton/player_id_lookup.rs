@@ -1,4 +1,4 @@
-//! Lookup players by their UUID
+//! Lookup players and mobs by their protocol entity id
 
 use evenio::{entity::EntityId, prelude::Component};
 use fxhash::FxHashMap;
@@ -6,6 +6,6 @@ use fxhash::FxHashMap;
 /// See [`crate::singleton::player_uuid_lookup`].
 #[derive(Component, Default, Debug)]
 pub struct PlayerIdLookup {
-    /// The UUID of all players
+    /// The protocol entity ids of all players and mobs
     pub inner: FxHashMap<i32, EntityId>,
 }
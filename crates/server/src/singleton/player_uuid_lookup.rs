@@ -1,5 +1,5 @@
 //! Lookup players by their UUID
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 
 use evenio::{entity::EntityId, prelude::Component};
 use uuid::Uuid;
@@ -12,9 +12,16 @@ pub struct PlayerUuidLookup {
 }
 
 impl PlayerUuidLookup {
-    /// Insert a player into the lookup.
-    pub fn insert(&mut self, uuid: Uuid, entity: EntityId) {
-        self.inner.insert(uuid, entity);
+    /// Insert a player into the lookup unless another player with the same UUID is online, i.e.,
+    /// someone already joined with the same name. Returns whether the player was inserted.
+    pub fn try_insert(&mut self, uuid: Uuid, entity: EntityId) -> bool {
+        match self.inner.entry(uuid) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(entity);
+                true
+            }
+        }
     }
 
     /// Remove a player from the lookup.
@@ -27,3 +34,28 @@ impl PlayerUuidLookup {
         self.inner.get(uuid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use evenio::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn players_with_the_same_name_are_not_online_twice() {
+        let mut world = World::new();
+        let (first, second) = (world.spawn(), world.spawn());
+
+        let uuid = crate::net::offline_uuid("alice").unwrap();
+        let mut lookup = PlayerUuidLookup::default();
+
+        assert!(lookup.try_insert(uuid, first));
+        assert!(!lookup.try_insert(uuid, second));
+        assert_eq!(lookup.get(&uuid), Some(&first));
+
+        // once the first player left, the name is free again
+        lookup.remove(&uuid);
+        assert!(lookup.try_insert(uuid, second));
+        assert_eq!(lookup.get(&uuid), Some(&second));
+    }
+}
@@ -0,0 +1,376 @@
+//! The registries sent to clients when they join and the dimension they are sent to.
+//!
+//! Custom dimension types, biomes and damage types can either come from the [`crate::config::Config`]
+//! or be set from code by replacing the [`Registries`] singleton before the first player joins
+//! (the registry codec is cached when the first player joins the world).
+use anyhow::{bail, Context};
+use chunk::bit_width;
+use evenio::component::Component;
+use serde::{Deserialize, Serialize};
+use valence_protocol::nbt::{compound, Compound, List, Value};
+use valence_registry::{biome::BiomeId, RegistryCodec, RegistryIdx};
+
+use crate::config::Config;
+
+/// The key of the dimension type registry.
+const DIMENSION_TYPE_KEY: &str = "minecraft:dimension_type";
+/// The key of the biome registry.
+const BIOME_KEY: &str = "minecraft:worldgen/biome";
+/// The key of the damage type registry.
+const DAMAGE_TYPE_KEY: &str = "minecraft:damage_type";
+
+/// The dimension type that new entries are based on if they do not override a vanilla entry.
+const TEMPLATE_DIMENSION_TYPE: &str = "minecraft:overworld";
+/// The biome that new entries are based on if they do not override a vanilla entry.
+const TEMPLATE_BIOME: &str = "minecraft:plains";
+
+/// A dimension type in the `minecraft:dimension_type` registry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DimensionType {
+    /// The name of the dimension type, i.e., `minecraft:overworld`. This is also used as the name
+    /// of the dimension players are sent to.
+    pub name: String,
+    /// The lowest y coordinate of the world. Must be a multiple of 16.
+    pub min_y: i32,
+    /// The height of the world in blocks. Must be a multiple of 16.
+    pub height: u32,
+    /// How much light the dimension has without any light sources, from `0.0` to `1.0`.
+    pub ambient_light: f32,
+    /// If set, the time of day is frozen at this value.
+    pub fixed_time: Option<i64>,
+    /// Whether the dimension has a sky light.
+    pub has_skylight: bool,
+    /// Whether the dimension has a bedrock ceiling.
+    pub has_ceiling: bool,
+    /// Whether compasses and clocks work.
+    pub natural: bool,
+    /// The sky effects of the dimension, i.e., `minecraft:overworld`, `minecraft:the_nether` or
+    /// `minecraft:the_end`.
+    pub effects: String,
+}
+
+impl Default for DimensionType {
+    fn default() -> Self {
+        Self {
+            name: "minecraft:overworld".to_owned(),
+            min_y: -64,
+            height: 384,
+            ambient_light: 0.0,
+            fixed_time: None,
+            has_skylight: true,
+            has_ceiling: false,
+            natural: true,
+            effects: "minecraft:overworld".to_owned(),
+        }
+    }
+}
+
+impl DimensionType {
+    /// The number of 16 block tall chunk sections in the dimension.
+    pub const fn section_count(&self) -> usize {
+        self.height as usize / 16
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.min_y % 16 != 0 {
+            bail!("dimension min_y {} is not a multiple of 16", self.min_y);
+        }
+
+        if self.height == 0 || self.height % 16 != 0 {
+            bail!("dimension height {} is not a multiple of 16", self.height);
+        }
+
+        Ok(())
+    }
+
+    fn patch(&self, element: &mut Compound) {
+        element.insert("min_y", self.min_y);
+        element.insert("height", self.height as i32);
+        element.insert("logical_height", self.height as i32);
+        element.insert("ambient_light", self.ambient_light);
+        element.insert("has_skylight", i8::from(self.has_skylight));
+        element.insert("has_ceiling", i8::from(self.has_ceiling));
+        element.insert("natural", i8::from(self.natural));
+        element.insert("effects", self.effects.clone());
+
+        match self.fixed_time {
+            Some(fixed_time) => {
+                element.insert("fixed_time", fixed_time);
+            }
+            None => {
+                element.remove("fixed_time");
+            }
+        }
+    }
+}
+
+/// A biome in the `minecraft:worldgen/biome` registry. Only the colors are configurable; everything
+/// else is taken from the vanilla biome of the same name (or `minecraft:plains` for new biomes).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Biome {
+    /// The name of the biome, i.e., `hyperion:arena`.
+    pub name: String,
+    /// The color of the sky as `0xRRGGBB`.
+    pub sky_color: Option<i32>,
+    /// The color of the fog as `0xRRGGBB`.
+    pub fog_color: Option<i32>,
+    /// The color of water as `0xRRGGBB`.
+    pub water_color: Option<i32>,
+    /// The color of the fog when underwater as `0xRRGGBB`.
+    pub water_fog_color: Option<i32>,
+}
+
+impl Biome {
+    fn patch(&self, element: &mut Compound) {
+        let Some(Value::Compound(effects)) = element.get_mut("effects") else {
+            return;
+        };
+
+        let colors = [
+            ("sky_color", self.sky_color),
+            ("fog_color", self.fog_color),
+            ("water_color", self.water_color),
+            ("water_fog_color", self.water_fog_color),
+        ];
+
+        for (key, color) in colors {
+            if let Some(color) = color {
+                effects.insert(key, color);
+            }
+        }
+    }
+}
+
+/// A damage type in the `minecraft:damage_type` registry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DamageType {
+    /// The name of the damage type, i.e., `hyperion:border`.
+    pub name: String,
+    /// The translation key suffix of the death message, i.e., `outsideBorder`.
+    pub message_id: String,
+    /// The amount of hunger exhaustion caused by this damage type.
+    pub exhaustion: f32,
+    /// Whether the damage scales with difficulty: `never`, `always` or
+    /// `when_caused_by_living_non_player`.
+    pub scaling: String,
+}
+
+impl DamageType {
+    fn to_nbt(&self) -> Compound {
+        compound! {
+            "message_id" => self.message_id.clone(),
+            "exhaustion" => self.exhaustion,
+            "scaling" => self.scaling.clone(),
+        }
+    }
+}
+
+/// See [`crate::singleton::registries`].
+#[derive(Component, Debug)]
+pub struct Registries {
+    /// The dimension type players are sent to.
+    pub dimension: DimensionType,
+    /// The biome used for all generated chunks.
+    pub biome: BiomeId,
+    /// The raw registry codec sent in the game join packet.
+    codec: Compound,
+    /// The number of entries in the biome registry.
+    biome_count: usize,
+    /// The names of all entries in the damage type registry in protocol order.
+    damage_types: Vec<String>,
+}
+
+impl Registries {
+    /// Creates the registries from the vanilla registry codec with the given custom entries
+    /// added. Entries with the same name as a vanilla entry replace it.
+    ///
+    /// `biome` is the name of the biome used for generated chunks; if it is `None`, the default
+    /// biome is used.
+    pub fn new(
+        dimension: DimensionType,
+        biomes: &[Biome],
+        damage_types: &[DamageType],
+        biome: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        dimension.validate()?;
+
+        let codec = RegistryCodec::default();
+        let mut codec = registry_codec_raw(&codec)?;
+
+        let dimension_types = registry_entries(&mut codec, DIMENSION_TYPE_KEY)?;
+        let element = entry_element(dimension_types, &dimension.name, TEMPLATE_DIMENSION_TYPE)?;
+        dimension.patch(element);
+
+        let biome_entries = registry_entries(&mut codec, BIOME_KEY)?;
+        for custom in biomes {
+            let element = entry_element(biome_entries, &custom.name, TEMPLATE_BIOME)?;
+            custom.patch(element);
+        }
+
+        let damage_type_entries = registry_entries(&mut codec, DAMAGE_TYPE_KEY)?;
+        for custom in damage_types {
+            set_entry(damage_type_entries, &custom.name, custom.to_nbt());
+        }
+
+        let biome_names = entry_names(registry_entries(&mut codec, BIOME_KEY)?);
+        let damage_types = entry_names(registry_entries(&mut codec, DAMAGE_TYPE_KEY)?);
+
+        let biome = match biome {
+            Some(name) => {
+                let idx = biome_names
+                    .iter()
+                    .position(|elem| elem == name)
+                    .with_context(|| format!("biome {name} is not registered"))?;
+                BiomeId::from_index(idx)
+            }
+            None => BiomeId::DEFAULT,
+        };
+
+        Ok(Self {
+            dimension,
+            biome,
+            codec,
+            biome_count: biome_names.len(),
+            damage_types,
+        })
+    }
+
+    /// Creates the registries from the given configuration.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        Self::new(
+            config.dimension.clone(),
+            &config.biomes,
+            &config.damage_types,
+            config.world_biome.as_deref(),
+        )
+    }
+
+    /// The raw registry codec which is sent in the game join packet.
+    pub const fn codec(&self) -> &Compound {
+        &self.codec
+    }
+
+    /// The number of bits needed to encode a biome in a chunk section.
+    pub const fn biome_bits(&self) -> usize {
+        bit_width(self.biome_count.saturating_sub(1))
+    }
+
+    /// The protocol id of a damage type, i.e., `minecraft:player_attack`.
+    pub fn damage_type_id(&self, name: &str) -> Option<i32> {
+        let idx = self.damage_types.iter().position(|elem| elem == name)?;
+        i32::try_from(idx).ok()
+    }
+}
+
+fn registry_codec_raw(codec: &RegistryCodec) -> anyhow::Result<Compound> {
+    let mut compound = Compound::default();
+
+    for (reg_name, reg) in &codec.registries {
+        let mut value = vec![];
+
+        for (id, v) in reg.iter().enumerate() {
+            let id = i32::try_from(id).context("id too large")?;
+            value.push(compound! {
+                "id" => id,
+                "name" => v.name.as_str(),
+                "element" => v.element.clone(),
+            });
+        }
+
+        let registry = compound! {
+            "type" => reg_name.as_str(),
+            "value" => List::Compound(value),
+        };
+
+        compound.insert(reg_name.as_str(), registry);
+    }
+
+    Ok(compound)
+}
+
+/// The entries of the registry `key` in a raw registry codec.
+fn registry_entries<'a>(
+    codec: &'a mut Compound,
+    key: &str,
+) -> anyhow::Result<&'a mut Vec<Compound>> {
+    let Some(Value::Compound(registry)) = codec.get_mut(key) else {
+        bail!("registry {key} is missing");
+    };
+
+    let Some(Value::List(List::Compound(entries))) = registry.get_mut("value") else {
+        bail!("registry {key} has no entries");
+    };
+
+    Ok(entries)
+}
+
+fn entry_name(entry: &Compound) -> Option<&str> {
+    match entry.get("name")? {
+        Value::String(name) => Some(name.as_str()),
+        _ => None,
+    }
+}
+
+fn entry_names(entries: &[Compound]) -> Vec<String> {
+    entries
+        .iter()
+        .filter_map(entry_name)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The element of the entry `name`. If there is no such entry, one is added with a copy of the
+/// element of `template`.
+fn entry_element<'a>(
+    entries: &'a mut Vec<Compound>,
+    name: &str,
+    template: &str,
+) -> anyhow::Result<&'a mut Compound> {
+    let idx = match entries
+        .iter()
+        .position(|entry| entry_name(entry) == Some(name))
+    {
+        Some(idx) => idx,
+        None => {
+            let template = entries
+                .iter()
+                .find(|entry| entry_name(entry) == Some(template))
+                .and_then(|entry| match entry.get("element") {
+                    Some(Value::Compound(element)) => Some(element.clone()),
+                    _ => None,
+                })
+                .with_context(|| format!("template entry {template} is missing"))?;
+
+            set_entry(entries, name, template)
+        }
+    };
+
+    let Some(Value::Compound(element)) = entries[idx].get_mut("element") else {
+        bail!("entry {name} has no element");
+    };
+
+    Ok(element)
+}
+
+/// Sets the element of the entry `name`, adding it to the end of the registry if it does not exist.
+/// Returns the index of the entry.
+fn set_entry(entries: &mut Vec<Compound>, name: &str, element: Compound) -> usize {
+    if let Some(idx) = entries
+        .iter()
+        .position(|entry| entry_name(entry) == Some(name))
+    {
+        entries[idx].insert("element", element);
+        return idx;
+    }
+
+    let idx = entries.len();
+
+    entries.push(compound! {
+        "id" => idx as i32,
+        "name" => name,
+        "element" => element,
+    });
+
+    idx
+}
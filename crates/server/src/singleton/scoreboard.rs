@@ -0,0 +1,274 @@
+//! The scoreboard shown to players.
+//!
+//! The sidebar shows the kills and deaths of the player, the scores of the teams and the number of
+//! players left, and the health of players is shown below their names. Scores are changed on an
+//! [`Objective`] and `system::sync_scoreboard` sends the scores which changed once per tick, so a
+//! score which changes many times in a tick is only sent once.
+use std::collections::{BTreeMap, BTreeSet};
+
+use evenio::component::Component;
+use valence_protocol::{
+    packets::play::{
+        self,
+        scoreboard_display_s2c::ScoreboardPosition,
+        scoreboard_objective_update_s2c::{ObjectiveMode, ObjectiveRenderType},
+        scoreboard_player_update_s2c::ScoreboardPlayerUpdateAction,
+    },
+    text::IntoText,
+    VarInt,
+};
+
+use crate::singleton::{stats::PlayerStats, teams::Teams};
+
+/// The name of the objective shown in the sidebar.
+pub const SIDEBAR: &str = "sidebar";
+
+/// The name of the objective shown below the names of players.
+pub const HEALTH: &str = "health";
+
+/// The title of the sidebar.
+const SIDEBAR_TITLE: &str = "Hyperion";
+
+/// The line of the sidebar with the kills of the player. Like all fixed lines, it starts with a
+/// formatting code which players do not see, so it cannot be confused with the name of a player or
+/// a team.
+pub const KILLS_LINE: &str = "§rKills";
+
+/// The line of the sidebar with the deaths of the player.
+pub const DEATHS_LINE: &str = "§rDeaths";
+
+/// The line of the sidebar with the number of players who are alive.
+pub const PLAYERS_LEFT_LINE: &str = "§rPlayers left";
+
+/// The formatting code in front of the names of teams in the sidebar. White is the default color,
+/// so it does not change how they look, but it keeps them apart from the fixed lines and from the
+/// names of players.
+const TEAM_LINE_PREFIX: &str = "§f";
+
+/// The line of the sidebar with the score of the team `name`.
+pub fn team_line(name: &str) -> String {
+    format!("{TEAM_LINE_PREFIX}{name}")
+}
+
+/// The kills and deaths of a player, which only they see in their sidebar.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlayerScore {
+    /// The number of players the player killed.
+    pub kills: i32,
+    /// The number of times the player died.
+    pub deaths: i32,
+}
+
+impl From<&PlayerStats> for PlayerScore {
+    fn from(stats: &PlayerStats) -> Self {
+        Self {
+            kills: i32::try_from(stats.kills).unwrap_or(i32::MAX),
+            deaths: i32::try_from(stats.deaths).unwrap_or(i32::MAX),
+        }
+    }
+}
+
+/// Scores which are shown to clients, see [`crate::singleton::scoreboard`].
+#[derive(Debug)]
+pub struct Objective {
+    /// The name the client knows the objective by.
+    name: &'static str,
+    /// The scores by the name of their holder.
+    scores: BTreeMap<String, i32>,
+    /// The holders whose score changed since the changes were last taken.
+    changed: BTreeSet<String>,
+}
+
+impl Objective {
+    /// An objective without scores.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            scores: BTreeMap::new(),
+            changed: BTreeSet::new(),
+        }
+    }
+
+    /// The name the client knows the objective by.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The score of a holder, `0` if they have none.
+    pub fn get(&self, holder: &str) -> i32 {
+        self.scores.get(holder).copied().unwrap_or_default()
+    }
+
+    /// Sets the score of a holder.
+    pub fn set(&mut self, holder: &str, score: i32) {
+        if self.scores.get(holder) == Some(&score) {
+            return;
+        }
+
+        self.scores.insert(holder.to_owned(), score);
+        self.changed.insert(holder.to_owned());
+    }
+
+    /// Adds to the score of a holder.
+    pub fn add(&mut self, holder: &str, amount: i32) {
+        self.set(holder, self.get(holder).saturating_add(amount));
+    }
+
+    /// Removes the score of a holder, i.e., when a player leaves.
+    pub fn remove(&mut self, holder: &str) {
+        if self.scores.remove(holder).is_some() {
+            self.changed.insert(holder.to_owned());
+        }
+    }
+
+    /// All scores by the name of their holder.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.scores
+            .iter()
+            .map(|(holder, score)| (holder.as_str(), *score))
+    }
+
+    /// Returns the scores which changed since this was last called, `None` for removed scores.
+    pub fn take_changes(&mut self) -> Vec<(String, Option<i32>)> {
+        std::mem::take(&mut self.changed)
+            .into_iter()
+            .map(|holder| {
+                let score = self.scores.get(&holder).copied();
+                (holder, score)
+            })
+            .collect()
+    }
+}
+
+/// See [`crate::singleton::scoreboard`].
+#[derive(Component, Debug)]
+pub struct Scoreboard {
+    /// The lines of the sidebar every player sees. The lines of the player are in
+    /// [`PlayerScore`].
+    pub sidebar: Objective,
+    /// The health of every player by their name.
+    pub health: Objective,
+}
+
+impl Scoreboard {
+    /// A scoreboard with the score of every team at `0`.
+    pub fn new(teams: &Teams) -> Self {
+        let mut sidebar = Objective::new(SIDEBAR);
+
+        for team in teams.iter() {
+            sidebar.set(&team_line(&team.name), 0);
+        }
+
+        Self {
+            sidebar,
+            health: Objective::new(HEALTH),
+        }
+    }
+}
+
+/// The packets which create the objectives and show them in their display slots.
+pub fn objective_packets() -> [(
+    play::ScoreboardObjectiveUpdateS2c<'static>,
+    play::ScoreboardDisplayS2c<'static>,
+); 2] {
+    let objective = |name, title: &'static str, render_type, position| {
+        let update = play::ScoreboardObjectiveUpdateS2c {
+            objective_name: name,
+            mode: ObjectiveMode::Create {
+                objective_display_name: title.into_text(),
+                render_type,
+            },
+        };
+
+        let display = play::ScoreboardDisplayS2c {
+            position,
+            score_name: name,
+        };
+
+        (update, display)
+    };
+
+    [
+        objective(
+            SIDEBAR,
+            SIDEBAR_TITLE,
+            ObjectiveRenderType::Integer,
+            ScoreboardPosition::Sidebar,
+        ),
+        objective(
+            HEALTH,
+            "❤",
+            ObjectiveRenderType::Hearts,
+            ScoreboardPosition::BelowName,
+        ),
+    ]
+}
+
+/// The packet which sets or, if `score` is `None`, removes the score of a holder.
+pub fn score_packet<'a>(
+    objective: &'a str,
+    holder: &'a str,
+    score: Option<i32>,
+) -> play::ScoreboardPlayerUpdateS2c<'a> {
+    let action = match score {
+        Some(score) => ScoreboardPlayerUpdateAction::Update {
+            objective_name: objective,
+            objective_score: VarInt(score),
+        },
+        None => ScoreboardPlayerUpdateAction::Remove {
+            objective_name: objective,
+        },
+    };
+
+    play::ScoreboardPlayerUpdateS2c {
+        entity_name: holder,
+        action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changed_scores_are_sent() {
+        let mut objective = Objective::new(SIDEBAR);
+
+        objective.set("red", 0);
+        objective.add("red", 1);
+        objective.add("red", 1);
+        objective.set("blue", 3);
+
+        assert_eq!(
+            objective.take_changes(),
+            [("blue".to_owned(), Some(3)), ("red".to_owned(), Some(2))]
+        );
+        assert!(objective.take_changes().is_empty());
+
+        // setting the same score is not a change
+        objective.set("red", 2);
+        assert!(objective.take_changes().is_empty());
+
+        objective.remove("blue");
+        objective.remove("green");
+        assert_eq!(objective.take_changes(), [("blue".to_owned(), None)]);
+        assert_eq!(objective.get("blue"), 0);
+    }
+
+    #[test]
+    fn teams_do_not_replace_fixed_lines() {
+        let mut sidebar = Objective::new(SIDEBAR);
+
+        // teams named like the fixed lines
+        for name in ["Kills", "Deaths", "Players left"] {
+            sidebar.set(&team_line(name), 1);
+        }
+
+        for line in [KILLS_LINE, DEATHS_LINE, PLAYERS_LEFT_LINE] {
+            sidebar.set(line, 2);
+        }
+
+        assert_eq!(sidebar.iter().count(), 6);
+        assert_eq!(sidebar.get(&team_line("Kills")), 1);
+    }
+}
@@ -0,0 +1,276 @@
+//! Where players spawn when they join and respawn after dying.
+//!
+//! Spawns are defined in the [`crate::config::Config`]. Players spawn at a random spawn point of
+//! their team, or at a random position in the spawn area if their team has none. Either way, they
+//! are moved to the closest safe spot where they stand on solid ground without being stuck in
+//! blocks or hurt by them. Outside of the battle, players spawn at the lobby spawn if there is one.
+use std::collections::BTreeMap;
+
+use anyhow::ensure;
+use evenio::component::Component;
+use glam::{IVec3, Vec3};
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use valence_protocol::{BlockKind, BlockState};
+
+use crate::{
+    config::Config,
+    singleton::{blocks::Blocks, phase::Phase},
+};
+
+/// The maximum horizontal distance in blocks a spawn is moved to find a safe spot.
+const SEARCH_RADIUS: i32 = 8;
+
+/// The area players spawn in, as defined in the configuration.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnArea {
+    /// The x and z coordinates of the center of the area.
+    pub center: [f32; 2],
+    /// The maximum distance from the center on both axes.
+    pub radius: f32,
+}
+
+impl Default for SpawnArea {
+    fn default() -> Self {
+        Self {
+            center: [0.0, 0.0],
+            radius: 0.0,
+        }
+    }
+}
+
+/// See [`crate::singleton::spawns`].
+#[derive(Component, Debug)]
+pub struct Spawns {
+    /// The area of players whose team has no spawn points.
+    area: SpawnArea,
+    /// The spawn points of teams by the name of the team.
+    teams: BTreeMap<String, Vec<Vec3>>,
+    /// Where players wait outside of the battle.
+    lobby: Option<Vec3>,
+}
+
+impl Spawns {
+    /// Validates the spawns of the given configuration.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let area = config.spawn_area;
+
+        ensure!(
+            area.radius.is_finite() && area.radius >= 0.0,
+            "the spawn area radius {} is not a positive number",
+            area.radius
+        );
+
+        for (team, points) in &config.team_spawns {
+            ensure!(!points.is_empty(), "team {team} has no spawn points");
+        }
+
+        let teams = config
+            .team_spawns
+            .iter()
+            .map(|(team, points)| (team.clone(), points.iter().map(|&p| p.into()).collect()))
+            .collect();
+
+        Ok(Self {
+            area,
+            teams,
+            lobby: config.lobby_spawn.map(Vec3::from),
+        })
+    }
+
+    /// A random spawn for a player of `team` in `phase`, moved to the closest safe spot.
+    pub fn spawn_for(
+        &self,
+        team: Option<&str>,
+        phase: Phase,
+        blocks: &Blocks,
+        rng: &mut impl Rng,
+    ) -> Vec3 {
+        if let Some(lobby) = self.lobby.filter(|_| phase != Phase::Battle) {
+            return safe_spot(blocks, lobby);
+        }
+
+        let point = team
+            .and_then(|team| self.teams.get(team))
+            .and_then(|points| points.choose(rng));
+
+        let pos = match point {
+            Some(point) => *point,
+            None => {
+                let [x, z] = self.area.center;
+                let radius = self.area.radius;
+
+                let x = x + rng.gen_range(-radius..=radius);
+                let z = z + rng.gen_range(-radius..=radius);
+
+                let column = Vec3::new(x, 0.0, z).floor().as_ivec3();
+                let y = blocks.height(column.x, column.z).unwrap_or(0);
+
+                Vec3::new(x, y as f32, z)
+            }
+        };
+
+        safe_spot(blocks, pos)
+    }
+}
+
+/// The closest safe spot to `pos`. A safe `pos` is kept as is, otherwise the player is moved onto
+/// the surface of the closest column which is safe to stand on. Returns `pos` if there is no safe
+/// spot nearby.
+fn safe_spot(blocks: &Blocks, pos: Vec3) -> Vec3 {
+    let feet = pos.floor().as_ivec3();
+
+    if is_safe(blocks, feet) {
+        return pos;
+    }
+
+    let columns = (-SEARCH_RADIUS..=SEARCH_RADIUS)
+        .cartesian_product(-SEARCH_RADIUS..=SEARCH_RADIUS)
+        .sorted_by_key(|(x, z)| x * x + z * z);
+
+    for (x, z) in columns {
+        let (x, z) = (feet.x + x, feet.z + z);
+
+        let Some(y) = blocks.height(x, z) else {
+            continue;
+        };
+
+        let spot = IVec3::new(x, y, z);
+
+        if !is_safe(blocks, spot) {
+            continue;
+        }
+
+        // keep the position within the block if the player stays in the same column
+        if x == feet.x && z == feet.z {
+            return Vec3::new(pos.x, y as f32, pos.z);
+        }
+
+        return spot.as_vec3() + Vec3::new(0.5, 0.0, 0.5);
+    }
+
+    pos
+}
+
+/// Whether a player with their feet in the block at `feet` stands on solid ground, is not stuck in
+/// blocks and is not hurt by the blocks around them.
+fn is_safe(blocks: &Blocks, feet: IVec3) -> bool {
+    let ground = feet - IVec3::Y;
+    let head = feet + IVec3::Y;
+
+    let passable = |pos: IVec3| !blocks.is_solid(pos) && !is_harmful(blocks.get(pos));
+
+    blocks.is_solid(ground) && !is_harmful(blocks.get(ground)) && passable(feet) && passable(head)
+}
+
+/// Whether a block hurts players who touch it.
+fn is_harmful(state: BlockState) -> bool {
+    matches!(
+        state.to_kind(),
+        BlockKind::Lava
+            | BlockKind::Fire
+            | BlockKind::SoulFire
+            | BlockKind::Cactus
+            | BlockKind::MagmaBlock
+            | BlockKind::Campfire
+            | BlockKind::SoulCampfire
+            | BlockKind::SweetBerryBush
+            | BlockKind::PowderSnow
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singleton::registries::DimensionType;
+
+    /// The y coordinate of the surface of the generated arena.
+    const SURFACE: f32 = 5.0;
+
+    fn spawns(area: SpawnArea, team_spawns: &[(&str, [f32; 3])]) -> Spawns {
+        let config = Config {
+            spawn_area: area,
+            team_spawns: team_spawns
+                .iter()
+                .map(|(team, point)| ((*team).to_owned(), vec![*point]))
+                .collect(),
+            ..Config::default()
+        };
+
+        Spawns::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn players_spawn_on_the_surface_of_the_area() {
+        let blocks = Blocks::generate(&DimensionType::default());
+        let area = SpawnArea {
+            center: [10.0, -20.0],
+            radius: 5.0,
+        };
+        let spawns = spawns(area, &[]);
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..10 {
+            let pos = spawns.spawn_for(None, Phase::Battle, &blocks, &mut rng);
+
+            assert!((pos.x - 10.0).abs() <= 5.0);
+            assert!((pos.z + 20.0).abs() <= 5.0);
+            assert!((pos.y - SURFACE).abs() < f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn team_spawn_points_are_moved_to_safe_spots() {
+        let blocks = Blocks::generate(&DimensionType::default());
+        let spawns = spawns(
+            SpawnArea::default(),
+            &[("red", [3.5, SURFACE, 3.5]), ("blue", [-3.5, 40.0, 3.5])],
+        );
+
+        let mut rng = rand::thread_rng();
+
+        // safe points are kept
+        let red = spawns.spawn_for(Some("red"), Phase::Battle, &blocks, &mut rng);
+        assert_eq!(red, Vec3::new(3.5, SURFACE, 3.5));
+
+        // players are not dropped from the sky
+        let blue = spawns.spawn_for(Some("blue"), Phase::Battle, &blocks, &mut rng);
+        assert_eq!(blue, Vec3::new(-3.5, SURFACE, 3.5));
+
+        // teams without spawn points use the area
+        let green = spawns.spawn_for(Some("green"), Phase::Battle, &blocks, &mut rng);
+        assert_eq!(green, Vec3::new(0.0, SURFACE, 0.0));
+    }
+
+    #[test]
+    fn players_wait_in_the_lobby_outside_of_the_battle() {
+        let blocks = Blocks::generate(&DimensionType::default());
+        let config = Config {
+            lobby_spawn: Some([20.5, SURFACE, 20.5]),
+            ..Config::default()
+        };
+        let spawns = Spawns::from_config(&config).unwrap();
+
+        let mut rng = rand::thread_rng();
+        let lobby = Vec3::new(20.5, SURFACE, 20.5);
+
+        for phase in [Phase::Lobby, Phase::Countdown, Phase::End] {
+            assert_eq!(spawns.spawn_for(None, phase, &blocks, &mut rng), lobby);
+        }
+
+        let battle = spawns.spawn_for(None, Phase::Battle, &blocks, &mut rng);
+        assert_eq!(battle, Vec3::new(0.0, SURFACE, 0.0));
+    }
+
+    #[test]
+    fn teams_need_spawn_points() {
+        let config = Config {
+            team_spawns: [("red".to_owned(), Vec::new())].into(),
+            ..Config::default()
+        };
+
+        assert!(Spawns::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! The statistics of each player in the current round.
+//!
+//! Damage is counted where it is dealt, kills and deaths by `system::score_kill` and the time
+//! alive by `system::update_stats`. Statistics are kept by UUID, so players who reconnect during a
+//! round keep theirs. They start from zero when the battle starts and `system::round_results`
+//! shows them and exports them as CSV and JSON when it ends.
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use evenio::component::Component;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// The statistics of a player, see [`crate::singleton::stats`].
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PlayerStats {
+    /// The UUID of the player.
+    pub uuid: Uuid,
+    /// The name the player had last.
+    pub name: String,
+    /// The number of players the player killed.
+    pub kills: u32,
+    /// The number of times the player died.
+    pub deaths: u32,
+    /// The damage the player dealt in half hearts.
+    pub damage_dealt: f32,
+    /// The damage the player took in half hearts.
+    pub damage_taken: f32,
+    /// The number of ticks the player was alive in the battle.
+    pub ticks_alive: i64,
+    /// The most kills in a row without dying.
+    pub longest_streak: u32,
+    /// The kills since the player last died.
+    #[serde(skip)]
+    streak: u32,
+}
+
+impl PlayerStats {
+    /// The kills per death, or the kills if the player never died.
+    pub fn ratio(&self) -> f32 {
+        self.kills as f32 / self.deaths.max(1) as f32
+    }
+}
+
+/// See [`crate::singleton::stats`].
+#[derive(Component, Debug, Default)]
+pub struct MatchStats {
+    /// The statistics by the UUID of the player.
+    players: BTreeMap<Uuid, PlayerStats>,
+}
+
+impl MatchStats {
+    /// The statistics of a player, which start from zero if they have none yet.
+    fn player(&mut self, uuid: Uuid) -> &mut PlayerStats {
+        self.players.entry(uuid).or_insert_with(|| PlayerStats {
+            uuid,
+            ..PlayerStats::default()
+        })
+    }
+
+    /// The statistics of a player, if they have any.
+    pub fn get(&self, uuid: Uuid) -> Option<&PlayerStats> {
+        self.players.get(&uuid)
+    }
+
+    /// Remembers the name of a player.
+    pub fn name(&mut self, uuid: Uuid, name: &str) {
+        let stats = self.player(uuid);
+
+        if stats.name != name {
+            name.clone_into(&mut stats.name);
+        }
+    }
+
+    /// Counts a tick `uuid` was alive in the battle.
+    pub fn alive(&mut self, uuid: Uuid) {
+        self.player(uuid).ticks_alive += 1;
+    }
+
+    /// Counts `amount` damage dealt by `attacker` to `victim`. Either is `None` if it is not a
+    /// player.
+    pub fn damage(&mut self, attacker: Option<Uuid>, victim: Option<Uuid>, amount: f32) {
+        if let Some(attacker) = attacker {
+            self.player(attacker).damage_dealt += amount;
+        }
+
+        if let Some(victim) = victim {
+            self.player(victim).damage_taken += amount;
+        }
+    }
+
+    /// Counts the death of `victim` and, if a player killed them, the kill of `killer`.
+    pub fn kill(&mut self, victim: Uuid, killer: Option<Uuid>) {
+        let stats = self.player(victim);
+        stats.deaths += 1;
+        stats.streak = 0;
+
+        let Some(killer) = killer.filter(|&killer| killer != victim) else {
+            return;
+        };
+
+        let stats = self.player(killer);
+        stats.kills += 1;
+        stats.streak += 1;
+        stats.longest_streak = stats.longest_streak.max(stats.streak);
+    }
+
+    /// Forgets all statistics for a new round.
+    pub fn reset(&mut self) {
+        self.players.clear();
+    }
+
+    /// All statistics from the best to the worst player. Players are ranked by kills, then by
+    /// deaths and then by damage dealt.
+    pub fn leaderboard(&self) -> Vec<PlayerStats> {
+        let mut players: Vec<_> = self.players.values().cloned().collect();
+
+        players.sort_by(|a, b| {
+            b.kills
+                .cmp(&a.kills)
+                .then(a.deaths.cmp(&b.deaths))
+                .then(b.damage_dealt.total_cmp(&a.damage_dealt))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        players
+    }
+}
+
+/// The results of a round as written to disk.
+#[derive(Serialize, Debug)]
+pub struct RoundResults {
+    /// The number of the round, counting from zero since the server started.
+    pub round: usize,
+    /// The seconds since the Unix epoch when the round ended.
+    pub time: u64,
+    /// The statistics from the best to the worst player.
+    pub players: Vec<PlayerStats>,
+}
+
+impl RoundResults {
+    /// The results as CSV with a header and one line per player.
+    pub fn csv(&self) -> String {
+        let mut csv =
+            "rank,uuid,name,kills,deaths,damage_dealt,damage_taken,ticks_alive,longest_streak\n"
+                .to_owned();
+
+        for (rank, stats) in self.players.iter().enumerate() {
+            // names cannot contain commas or quotes, but they are escaped in case that changes
+            let name = stats.name.replace('"', "\"\"");
+
+            let _ = writeln!(
+                csv,
+                "{},{},\"{name}\",{},{},{:.1},{:.1},{},{}",
+                rank + 1,
+                stats.uuid,
+                stats.kills,
+                stats.deaths,
+                stats.damage_dealt,
+                stats.damage_taken,
+                stats.ticks_alive,
+                stats.longest_streak,
+            );
+        }
+
+        csv
+    }
+
+    /// Writes the results to `round-<time>-<round>.csv` and `.json` in `dir` and returns the path
+    /// without extension.
+    pub fn export(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+        let path = dir.join(format!("round-{}-{}", self.time, self.round));
+
+        let json = serde_json::to_string_pretty(self)?;
+
+        fs::write(path.with_extension("csv"), self.csv()).context("failed to write the CSV")?;
+        fs::write(path.with_extension("json"), json).context("failed to write the JSON")?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{net::offline_uuid, singleton::scoreboard::PlayerScore};
+
+    const ALICE: Uuid = Uuid::from_u128(1);
+    const BOB: Uuid = Uuid::from_u128(2);
+
+    #[test]
+    fn kills_count_streaks() {
+        let mut stats = MatchStats::default();
+        stats.name(ALICE, "alice");
+        stats.name(BOB, "bob");
+
+        stats.damage(Some(ALICE), Some(BOB), 20.0);
+        stats.kill(BOB, Some(ALICE));
+        stats.kill(BOB, Some(ALICE));
+        stats.kill(ALICE, Some(BOB));
+        stats.kill(BOB, Some(ALICE));
+
+        // dying to the border or yourself is not a kill
+        stats.kill(BOB, None);
+        stats.kill(BOB, Some(BOB));
+
+        let alice = stats.get(ALICE).unwrap();
+        assert_eq!((alice.kills, alice.deaths, alice.longest_streak), (3, 1, 2));
+        assert!((alice.damage_dealt - 20.0).abs() < f32::EPSILON);
+
+        let bob = stats.get(BOB).unwrap();
+        assert_eq!((bob.kills, bob.deaths, bob.longest_streak), (1, 5, 1));
+        assert!((bob.damage_taken - 20.0).abs() < f32::EPSILON);
+
+        let names: Vec<_> = stats
+            .leaderboard()
+            .into_iter()
+            .map(|stats| stats.name)
+            .collect();
+        assert_eq!(names, ["alice", "bob"]);
+    }
+
+    #[test]
+    fn players_keep_their_statistics_when_they_reconnect() {
+        let mut stats = MatchStats::default();
+
+        let alice = offline_uuid("alice").unwrap();
+        stats.name(alice, "alice");
+        stats.kill(alice, None);
+
+        // alice disconnects and logs back in with the same name
+        let rejoined = offline_uuid("alice").unwrap();
+        assert_eq!(rejoined, alice);
+        assert_ne!(offline_uuid("bob").unwrap(), alice);
+
+        stats.name(rejoined, "alice");
+        stats.kill(rejoined, None);
+
+        let leaderboard = stats.leaderboard();
+        assert_eq!(leaderboard.len(), 1);
+        assert_eq!(
+            PlayerScore::from(&leaderboard[0]),
+            PlayerScore {
+                kills: 0,
+                deaths: 2
+            }
+        );
+    }
+
+    #[test]
+    fn results_are_exported_as_csv() {
+        let mut stats = MatchStats::default();
+        stats.name(ALICE, "alice");
+        stats.alive(ALICE);
+        stats.damage(None, Some(ALICE), 2.5);
+
+        let results = RoundResults {
+            round: 3,
+            time: 0,
+            players: stats.leaderboard(),
+        };
+
+        assert_eq!(
+            results.csv(),
+            "rank,uuid,name,kills,deaths,damage_dealt,damage_taken,ticks_alive,longest_streak\n\
+             1,00000000-0000-0000-0000-000000000001,\"alice\",0,0,0.0,2.5,1,0\n"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! The teams players fight in.
+//!
+//! Teams are defined in the [`crate::config::Config`]. Players join the team with the fewest
+//! players, spawn at its spawn points with its kit, see its color on name tags and cannot hurt
+//! their teammates unless friendly fire is enabled. Without teams, everyone fights everyone and
+//! name tags are hidden.
+use std::collections::BTreeSet;
+
+use anyhow::{bail, ensure};
+use evenio::component::Component;
+use serde::{Deserialize, Serialize};
+use valence_protocol::packets::play::team_s2c::TeamColor;
+
+use crate::{config::Config, singleton::phase::Phase};
+
+/// The name of the team of players without a team, which hides their name tags.
+pub const NO_TEAM: &str = "no_tag";
+
+/// A team as defined in the configuration.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TeamConfig {
+    /// The name of the team, which is also used in `team_kits` and `team_spawns`.
+    pub name: String,
+    /// The color of the name tags of the team, i.e., `red` or `dark_aqua`.
+    #[serde(default = "TeamConfig::default_color")]
+    pub color: String,
+    /// The text in front of the names of the team.
+    #[serde(default)]
+    pub prefix: String,
+}
+
+impl TeamConfig {
+    fn default_color() -> String {
+        "white".to_owned()
+    }
+}
+
+/// A team which was validated.
+#[derive(Debug)]
+pub struct TeamInfo {
+    /// See [`TeamConfig::name`].
+    pub name: String,
+    /// See [`TeamConfig::color`].
+    pub color: TeamColor,
+    /// See [`TeamConfig::prefix`].
+    pub prefix: String,
+}
+
+/// The team of a player as an index into [`Teams`], `None` if there are no teams.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Team(pub Option<usize>);
+
+/// See [`crate::singleton::teams`].
+#[derive(Component, Debug)]
+pub struct Teams {
+    /// All teams.
+    teams: Vec<TeamInfo>,
+    /// Whether players can hurt their teammates.
+    friendly_fire: bool,
+}
+
+impl Teams {
+    /// Validates the teams of the given configuration.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let mut names = BTreeSet::new();
+
+        let teams = config
+            .teams
+            .iter()
+            .map(|team| {
+                ensure!(names.insert(&team.name), "team {} exists twice", team.name);
+                ensure!(
+                    team.name != NO_TEAM,
+                    "the team name {NO_TEAM} is reserved for players without a team"
+                );
+
+                Ok(TeamInfo {
+                    name: team.name.clone(),
+                    color: parse_color(&team.color)?,
+                    prefix: team.prefix.clone(),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let referenced = config.team_kits.keys().chain(config.team_spawns.keys());
+
+        for team in referenced {
+            ensure!(names.contains(team), "team {team} does not exist");
+        }
+
+        Ok(Self {
+            teams,
+            friendly_fire: config.friendly_fire,
+        })
+    }
+
+    /// All teams.
+    pub fn iter(&self) -> impl Iterator<Item = &TeamInfo> {
+        self.teams.iter()
+    }
+
+    /// Whether players can hurt their teammates.
+    pub const fn friendly_fire(&self) -> bool {
+        self.friendly_fire
+    }
+
+    /// The team info of a player, if they are in a team.
+    pub fn get(&self, team: Team) -> Option<&TeamInfo> {
+        self.teams.get(team.0?)
+    }
+
+    /// The name of the team of a player, if they are in a team.
+    pub fn name(&self, team: Team) -> Option<&str> {
+        self.get(team).map(|info| info.name.as_str())
+    }
+
+    /// The name the client knows the team of a player by.
+    pub fn packet_name(&self, team: Team) -> &str {
+        self.name(team).unwrap_or(NO_TEAM)
+    }
+
+    /// The team with the fewest `members`, which a joining player is put in to balance the teams.
+    pub fn balance<'a>(&self, members: impl IntoIterator<Item = &'a Team>) -> Team {
+        let mut counts = vec![0_usize; self.teams.len()];
+
+        for idx in members.into_iter().filter_map(|team| team.0) {
+            if let Some(count) = counts.get_mut(idx) {
+                *count += 1;
+            }
+        }
+
+        let smallest = counts
+            .iter()
+            .enumerate()
+            .min_by_key(|(idx, count)| (**count, *idx))
+            .map(|(idx, _)| idx);
+
+        Team(smallest)
+    }
+
+    /// Whether a player of the `attacker` team can hurt an entity of the `target` team. Entities
+    /// without a team can always be hurt.
+    pub fn can_hurt(&self, attacker: Team, target: Option<Team>) -> bool {
+        let allies = attacker.0.is_some() && target.is_some_and(|target| target == attacker);
+        self.friendly_fire || !allies
+    }
+
+    /// Whether damage from an entity of the `attacker` team hurts an entity of the `target` team
+    /// in `phase`, see `system::damage_entity`. Only players have a team, so only damage between
+    /// players is stopped: outside the battle ([`Phase::pvp`]) and between teammates unless
+    /// friendly fire is enabled.
+    pub fn can_damage(&self, phase: Phase, attacker: Option<Team>, target: Option<Team>) -> bool {
+        let Some(attacker) = attacker else {
+            return true;
+        };
+
+        (phase.pvp() || target.is_none()) && self.can_hurt(attacker, target)
+    }
+}
+
+/// Parses the name of a chat color like vanilla uses it, i.e., `dark_aqua`.
+fn parse_color(name: &str) -> anyhow::Result<TeamColor> {
+    let color = match name {
+        "black" => TeamColor::Black,
+        "dark_blue" => TeamColor::DarkBlue,
+        "dark_green" => TeamColor::DarkGreen,
+        "dark_aqua" => TeamColor::DarkCyan,
+        "dark_red" => TeamColor::DarkRed,
+        "dark_purple" => TeamColor::Purple,
+        "gold" => TeamColor::Gold,
+        "gray" => TeamColor::Gray,
+        "dark_gray" => TeamColor::DarkGray,
+        "blue" => TeamColor::Blue,
+        "green" => TeamColor::BrightGreen,
+        "aqua" => TeamColor::Cyan,
+        "red" => TeamColor::Red,
+        "light_purple" => TeamColor::Pink,
+        "yellow" => TeamColor::Yellow,
+        "white" => TeamColor::White,
+        _ => bail!("unknown team color {name}"),
+    };
+
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(name: &str, color: &str) -> TeamConfig {
+        TeamConfig {
+            name: name.to_owned(),
+            color: color.to_owned(),
+            prefix: String::new(),
+        }
+    }
+
+    fn teams(friendly_fire: bool) -> Teams {
+        let config = Config {
+            teams: vec![team("red", "red"), team("blue", "blue")],
+            friendly_fire,
+            ..Config::default()
+        };
+
+        Teams::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn players_join_the_smallest_team() {
+        let teams = teams(false);
+
+        assert_eq!(teams.balance([]), Team(Some(0)));
+        assert_eq!(teams.balance(&[Team(Some(0))]), Team(Some(1)));
+        assert_eq!(
+            teams.balance(&[Team(Some(1)), Team(Some(0)), Team(Some(1))]),
+            Team(Some(0))
+        );
+
+        let no_teams = Teams::from_config(&Config::default()).unwrap();
+        assert_eq!(no_teams.balance(&[Team(None)]), Team(None));
+        assert_eq!(no_teams.packet_name(Team(None)), NO_TEAM);
+    }
+
+    #[test]
+    fn teammates_cannot_hurt_each_other() {
+        let teams = teams(false);
+        let red = Team(Some(0));
+        let blue = Team(Some(1));
+
+        assert!(!teams.can_hurt(red, Some(red)));
+        assert!(teams.can_hurt(red, Some(blue)));
+        assert!(teams.can_hurt(red, None));
+        assert!(teams.can_hurt(Team(None), Some(Team(None))));
+
+        assert!(self::teams(true).can_hurt(red, Some(red)));
+    }
+
+    #[test]
+    fn players_only_hurt_each_other_in_the_battle() {
+        let teams = teams(false);
+        let red = Some(Team(Some(0)));
+        let blue = Some(Team(Some(1)));
+
+        for phase in Phase::ALL {
+            assert_eq!(teams.can_damage(phase, red, blue), phase == Phase::Battle);
+
+            // mobs have no team and fight in every phase
+            assert!(teams.can_damage(phase, red, None));
+            assert!(teams.can_damage(phase, None, blue));
+        }
+    }
+
+    #[test]
+    fn invalid_teams_are_rejected() {
+        let config = Config {
+            teams: vec![team("red", "crimson")],
+            ..Config::default()
+        };
+        assert!(Teams::from_config(&config).is_err());
+
+        let config = Config {
+            teams: vec![team("red", "red"), team("red", "blue")],
+            ..Config::default()
+        };
+        assert!(Teams::from_config(&config).is_err());
+
+        let config = Config {
+            teams: vec![team("red", "red")],
+            team_spawns: [("blue".to_owned(), vec![[0.0, 64.0, 0.0]])].into(),
+            ..Config::default()
+        };
+        assert!(Teams::from_config(&config).is_err());
+    }
+}
@@ -2,9 +2,17 @@
 
 #![allow(clippy::missing_docs_in_private_items, reason = "self-explanatory")]
 
+mod audit_kill;
+mod change_phase;
+mod damage_entity;
+mod death_message;
+mod debug_command;
+mod eat;
 mod egress;
 mod entity_detect_collisions;
 mod entity_move_logic;
+mod entity_pathfinding;
+mod entity_physics;
 mod entity_position;
 mod generate_egress_packets;
 mod ingress;
@@ -12,21 +20,45 @@ mod init_entity;
 mod init_player;
 mod keep_alive;
 mod kill_all;
+mod kit_command;
+mod mob_death;
+mod phase_command;
 mod pkt_attack;
 mod pkt_hand_swing;
 mod player_detect_mob_hits;
 mod player_join_world;
 mod player_kick;
+mod player_leave;
+mod projectile_physics;
 mod rebuild_player_location;
 mod reset_bounding_boxes;
+mod round_results;
+mod score_kill;
+mod send_announcements;
+mod shoot_projectile;
 mod stats_message;
+mod status_effects;
+mod sync_boss_bars;
+mod sync_equipment;
 mod sync_players;
+mod sync_scoreboard;
+mod update_border;
 mod update_health;
+mod update_phase;
+mod update_stats;
 mod update_time;
 
+pub use audit_kill::audit_kill;
+pub use change_phase::change_phase;
+pub use damage_entity::damage_entity;
+pub use death_message::death_message;
+pub use debug_command::debug_command;
+pub use eat::eat;
 pub use egress::egress;
 pub use entity_detect_collisions::entity_detect_collisions;
 pub use entity_move_logic::entity_move_logic;
+pub use entity_pathfinding::entity_pathfinding;
+pub use entity_physics::entity_physics;
 pub use entity_position::sync_entity_position;
 pub use generate_egress_packets::generate_egress_packets;
 pub use ingress::{ingress, IngressSender};
@@ -34,16 +66,32 @@ pub use init_entity::init_entity;
 pub use init_player::init_player;
 pub use keep_alive::keep_alive;
 pub use kill_all::kill_all;
+pub use kit_command::kit_command;
+pub use mob_death::mob_death;
+pub use phase_command::phase_command;
 pub use pkt_attack::pkt_attack;
 pub use pkt_hand_swing::pkt_hand_swing;
 pub use player_detect_mob_hits::player_detect_mob_hits;
 pub use player_join_world::player_join_world;
 pub use player_kick::player_kick;
+pub use player_leave::player_leave;
+pub use projectile_physics::projectile_physics;
 pub use rebuild_player_location::rebuild_player_location;
 pub use reset_bounding_boxes::reset_bounding_boxes;
+pub use round_results::round_results;
+pub use score_kill::score_kill;
+pub use send_announcements::send_announcements;
+pub use shoot_projectile::shoot_projectile;
 pub use stats_message::stats_message;
+pub use status_effects::status_effects;
+pub use sync_boss_bars::sync_boss_bars;
+pub use sync_equipment::sync_equipment;
 pub use sync_players::sync_players;
+pub use sync_scoreboard::sync_scoreboard;
+pub use update_border::update_border;
 pub use update_health::update_health;
+pub use update_phase::update_phase;
+pub use update_stats::update_stats;
 pub use update_time::update_time;
 
 use crate::events::{AttackEntity, InitEntity, KickPlayer, KillAllEntities, SwingArm};
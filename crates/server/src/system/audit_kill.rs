@@ -0,0 +1,39 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    audit::{Actor, Record},
+    components::{mob_kind::MobKind, FullEntityPose, Uuid},
+    events::PlayerKilled,
+    global::Global,
+    singleton::audit_log::AuditLog,
+};
+
+/// Records the death of a player in the audit log, see [`crate::audit`].
+#[instrument(skip_all)]
+pub fn audit_kill(
+    r: Receiver<PlayerKilled>,
+    global: Single<&Global>,
+    victims: Fetcher<(&Uuid, &FullEntityPose)>,
+    actors: Fetcher<(Option<&Uuid>, Option<&MobKind>)>,
+    audit: Single<&AuditLog>,
+) {
+    let event = r.event;
+
+    let Ok((victim, pose)) = victims.get(event.victim) else {
+        return;
+    };
+
+    let killer = event
+        .killer
+        .and_then(|killer| actors.get(killer).ok())
+        .and_then(|(uuid, kind)| Actor::new(uuid.map(|uuid| uuid.0), kind.copied()));
+
+    audit.record(Record::kill(
+        global.tick,
+        killer,
+        victim.0,
+        event.cause,
+        pose.position.to_array(),
+    ));
+}
@@ -0,0 +1,168 @@
+use std::cmp::Reverse;
+
+use evenio::prelude::*;
+use tracing::{info, instrument};
+use valence_protocol::text::{Color, IntoText, Text};
+
+use super::update_health::{respawn, UpdateHealthQuery};
+use crate::{
+    audit::Record,
+    components::InGameName,
+    events::PhaseChanged,
+    global::Global,
+    singleton::{
+        announcements::{Announcements, Audience, Priority, Title},
+        audit_log::AuditLog,
+        blocks::Blocks,
+        border::WorldBorder,
+        broadcast::BroadcastBuf,
+        kits::Kits,
+        phase::Phase,
+        registries::Registries,
+        scoreboard::{team_line, PlayerScore, Scoreboard},
+        spawns::Spawns,
+        teams::Teams,
+    },
+};
+
+/// Reacts to a new phase, see [`crate::singleton::phase`]. Players are moved to their spawns when
+/// the battle starts and when they return to the lobby, the border shrinks during the battle and the
+/// winners are announced when the battle ends.
+#[instrument(skip_all)]
+pub fn change_phase(
+    r: Receiver<PhaseChanged>,
+    global: Single<&Global>,
+    mut kits: Single<&mut Kits>,
+    spawns: Single<&Spawns>,
+    blocks: Single<&Blocks>,
+    teams: Single<&Teams>,
+    registries: Single<&Registries>,
+    mut scoreboard: Single<&mut Scoreboard>,
+    mut border: Single<&mut WorldBorder>,
+    mut players: Fetcher<UpdateHealthQuery>,
+    mut scores: Fetcher<(&InGameName, &mut PlayerScore)>,
+    mut announcements: Single<&mut Announcements>,
+    mut broadcast: Single<&mut BroadcastBuf>,
+    audit: Single<&AuditLog>,
+) {
+    let PhaseChanged { from, to } = *r.event;
+
+    info!("switching from the {} to the {}", from.name(), to.name());
+
+    audit.record(Record::Phase {
+        tick: global.tick,
+        from,
+        to,
+    });
+
+    let mut broadcast = broadcast.get_round_robin();
+
+    let mut announce = |title: Text, message: &str| {
+        let title = Title::new(title).priority(Priority::Important);
+        announcements.title(Audience::Everyone, title);
+        announcements.chat(Audience::Everyone, message.to_owned());
+    };
+
+    match to {
+        Phase::Lobby => {
+            border.reset(global.tick);
+
+            if from == Phase::Countdown {
+                announce(
+                    "Waiting".into_text().color(Color::GRAY),
+                    "Not enough players, the countdown stopped",
+                );
+            } else {
+                if from == Phase::End {
+                    kits.round += 1;
+                }
+
+                announce(
+                    "Lobby".into_text().color(Color::GRAY),
+                    "The next round starts once enough players joined",
+                );
+            }
+        }
+        Phase::Countdown => {
+            announce(
+                "Get ready".into_text().color(Color::GOLD),
+                "The battle is about to start",
+            );
+        }
+        Phase::Battle => {
+            // every round starts from zero
+            scores
+                .iter_mut()
+                .for_each(|(_, score)| *score = PlayerScore::default());
+
+            for team in teams.iter() {
+                scoreboard.sidebar.set(&team_line(&team.name), 0);
+            }
+
+            border.start(global.tick);
+
+            announce("Fight!".into_text().color(Color::RED), "The battle started");
+        }
+        Phase::End => {
+            let winners = winners(&teams, &scoreboard, &scores);
+            announce("Battle over".into_text().color(Color::GOLD), &winners);
+        }
+    }
+
+    if to == Phase::Lobby {
+        let (center, size) = border.update_packets(global.tick);
+        broadcast.append_packet(&center).unwrap();
+        broadcast.append_packet(&size).unwrap();
+    }
+
+    // players fight at their battle spawns and wait at the lobby spawn
+    let moved = to == Phase::Battle || (to == Phase::Lobby && from != Phase::Countdown);
+
+    if moved {
+        for mut query in &mut players {
+            respawn(
+                &mut query,
+                &global,
+                &kits,
+                &spawns,
+                &blocks,
+                &teams,
+                &registries,
+                to,
+            );
+        }
+    }
+}
+
+/// The announcement of the team with the highest score or, without teams, the player with the
+/// most kills.
+fn winners(
+    teams: &Teams,
+    scoreboard: &Scoreboard,
+    scores: &Fetcher<(&InGameName, &mut PlayerScore)>,
+) -> String {
+    let mut ranking: Vec<(&str, i32)> = if teams.iter().next().is_some() {
+        teams
+            .iter()
+            .map(|team| {
+                let score = scoreboard.sidebar.get(&team_line(&team.name));
+                (team.name.as_str(), score)
+            })
+            .collect()
+    } else {
+        scores
+            .iter()
+            .map(|(name, score)| (&***name, score.kills))
+            .collect()
+    };
+
+    ranking.sort_by_key(|&(_, kills)| Reverse(kills));
+
+    match ranking.as_slice() {
+        [] => "Nobody fought".to_owned(),
+        [(_, first), (_, second), ..] if first == second => {
+            format!("It is a draw with {first} kills")
+        }
+        [(winner, kills), ..] => format!("{winner} wins with {kills} kills"),
+    }
+}
@@ -0,0 +1,238 @@
+use evenio::prelude::*;
+use glam::Vec2;
+use tracing::instrument;
+use valence_protocol::{packets::play, VarInt};
+
+use crate::{
+    audit::{Actor, Record},
+    combat::{self, Defense, BASE_KNOCKBACK},
+    components::{
+        damage::{Attacker, DamageTracker},
+        inventory::Inventory,
+        mob_kind::MobKind,
+        status_effects::{EffectKind, StatusEffects},
+        Dying, EntityPhysics, EntityReaction, FullEntityPose, Hunger, Hurt, ImmuneStatus, OnGround,
+        Uuid, Vitals,
+    },
+    config,
+    events::{DamageEntity, PlayerKilled},
+    food::DAMAGE_EXHAUSTION,
+    global::Global,
+    net::LocalEncoder,
+    singleton::{
+        audit_log::AuditLog,
+        broadcast::BroadcastBuf,
+        phase::PhaseController,
+        registries::Registries,
+        stats::MatchStats,
+        teams::{Team, Teams},
+    },
+};
+
+/// The number of ticks the death animation of a mob plays before it is despawned.
+const DEATH_ANIMATION_TICKS: i64 = 20;
+
+/// The entity status which plays the death animation.
+const DEATH_STATUS: u8 = 3;
+
+/// The entity animation which shows critical hit particles.
+const CRITICAL_EFFECT: u8 = 4;
+
+/// The target of an attack. This is either a player or a mob which is still alive.
+#[derive(Query)]
+pub struct DamageQuery<'a> {
+    id: EntityId,
+    pose: &'a FullEntityPose,
+    on_ground: &'a OnGround,
+    reaction: &'a mut EntityReaction,
+    physics: Option<&'a mut EntityPhysics>,
+    encoder: Option<&'a mut LocalEncoder>,
+    immunity: &'a mut ImmuneStatus,
+    vitals: &'a mut Vitals,
+    inventory: Option<&'a Inventory>,
+    effects: Option<&'a StatusEffects>,
+    hunger: Option<&'a mut Hunger>,
+    kind: Option<&'a MobKind>,
+    uuid: Option<&'a Uuid>,
+    tracker: Option<&'a mut DamageTracker>,
+    team: Option<&'a Team>,
+    _alive: Not<&'static Dying>,
+}
+
+#[instrument(skip_all, level = "trace")]
+pub fn damage_entity(
+    global: Single<&Global>,
+    registries: Single<&Registries>,
+    r: Receiver<DamageEntity, DamageQuery>,
+    actors: Fetcher<(Option<&Uuid>, Option<&MobKind>, Option<&Team>)>,
+    teams: Single<&Teams>,
+    phase: Single<&PhaseController>,
+    mut broadcast: Single<&mut BroadcastBuf>,
+    audit: Single<&AuditLog>,
+    mut stats: Single<&mut MatchStats>,
+    mut s: Sender<(Insert<Dying>, PlayerKilled)>,
+) {
+    let DamageQuery {
+        id: entity_id,
+        pose,
+        on_ground,
+        reaction,
+        physics,
+        encoder,
+        immunity,
+        vitals,
+        inventory,
+        effects,
+        hunger,
+        kind,
+        uuid,
+        tracker,
+        team,
+        _alive,
+    } = r.query;
+
+    let event = r.event;
+
+    // only players have a team, and every source of damage checks it here so that players only
+    // hurt each other in the battle and never hurt their teammates
+    let attacker_team = event
+        .from
+        .filter(|&from| from != entity_id)
+        .and_then(|from| actors.get(from).ok())
+        .and_then(|(.., team)| team.copied());
+
+    if !teams.can_damage(phase.phase(), attacker_team, team.copied()) {
+        return;
+    }
+
+    let resistance = effects.map_or(0, |effects| effects.level(EffectKind::Resistance));
+    let defense = inventory
+        .map_or(Defense::NONE, Inventory::defense)
+        .with_resistance(resistance);
+    let hit_points = vitals.hit_points();
+    let hurt = vitals.hurt(&global, event.damage, &defense, immunity);
+
+    if hurt == Hurt::Immune {
+        return;
+    }
+
+    let died = matches!(vitals, Vitals::Dead { .. });
+
+    let attacker = event
+        .from
+        .and_then(|from| actors.get(from).ok())
+        .and_then(|(uuid, kind, _)| Actor::new(uuid.map(|uuid| uuid.0), kind.copied()));
+    let victim = Actor::new(uuid.map(|uuid| uuid.0), kind.copied());
+
+    stats.damage(
+        attacker.as_ref().and_then(Actor::player),
+        victim.as_ref().and_then(Actor::player),
+        hit_points - vitals.hit_points(),
+    );
+
+    if let Some(victim) = victim {
+        audit.record(Record::Hit {
+            tick: global.tick,
+            attacker,
+            victim,
+            damage: event.damage,
+            attacker_pos: event.from_pos.to_array(),
+            victim_pos: pose.position.to_array(),
+        });
+    }
+
+    let attacker = event.from.map(|id| Attacker {
+        id,
+        weapon: event.weapon,
+        tick: global.tick,
+    });
+
+    // only players remember who hurt them
+    if let Some(killed) = tracker
+        .and_then(|tracker| tracker.hurt(entity_id, vitals, event.cause, attacker, global.tick))
+    {
+        s.send(killed);
+    }
+
+    if let Some(hunger) = hunger.filter(|_| config::CONFIG.combat.rules().hunger) {
+        hunger.exhaust(DAMAGE_EXHAUSTION);
+    }
+
+    let entity_id_varint = VarInt(entity_id.index().0 as i32);
+
+    if event.critical {
+        broadcast
+            .get_round_robin()
+            .append_packet(&play::EntityAnimationS2c {
+                entity_id: entity_id_varint,
+                animation: CRITICAL_EFFECT,
+            })
+            .unwrap();
+    }
+
+    // mobs keep their knockback as momentum, players are sent it as a velocity update
+    let velocity = match physics {
+        Some(physics) => &mut physics.velocity,
+        None => &mut reaction.velocity,
+    };
+
+    if hurt == Hurt::Full {
+        let source_type_id = registries
+            .damage_type_id(event.cause.damage_type())
+            .unwrap_or_default();
+
+        // the ids of the source are offset by one so that zero means none
+        let source_id = VarInt(event.from.map_or(0, |from| from.index().0 as i32 + 1));
+
+        let mut damage_broadcast = play::EntityDamageS2c {
+            entity_id: entity_id_varint,
+            source_type_id: VarInt(source_type_id),
+            source_cause_id: source_id,
+            source_direct_id: source_id,
+            source_pos: None,
+        };
+
+        broadcast
+            .get_round_robin()
+            .append_packet(&damage_broadcast)
+            .unwrap();
+
+        // local is id 0
+        if let Some(encoder) = encoder {
+            damage_broadcast.entity_id = VarInt(0);
+            encoder.append(&damage_broadcast, &global).unwrap();
+        }
+
+        let this = pose.position;
+        let other = event.from_pos;
+
+        // todo: implement like vanilla when the attacker is exactly above the target
+        let away = Vec2::new(this.x - other.x, this.z - other.z);
+        if away.x.abs() >= 0.01 || away.y.abs() >= 0.01 {
+            combat::knockback(velocity, BASE_KNOCKBACK, away, on_ground.0);
+        }
+    }
+
+    combat::knockback(
+        velocity,
+        event.knockback.length(),
+        event.knockback,
+        on_ground.0,
+    );
+
+    if died && kind.is_some() {
+        let status = play::EntityStatusS2c {
+            entity_id: entity_id_varint.0,
+            entity_status: DEATH_STATUS,
+        };
+
+        broadcast.get_round_robin().append_packet(&status).unwrap();
+
+        s.insert(
+            entity_id,
+            Dying {
+                despawn_tick: global.tick + DEATH_ANIMATION_TICKS,
+            },
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::{
+    packets::play,
+    text::{Color, IntoText, Text},
+    VarInt,
+};
+
+use crate::{
+    components::{
+        damage::{self, weapon_name},
+        mob_kind::MobKind,
+        InGameName,
+    },
+    events::PlayerKilled,
+    global::Global,
+    net::LocalEncoder,
+    singleton::announcements::{Announcements, Audience, Priority},
+};
+
+/// Announces the death of a player in the chat and the kill feed and shows the victim the death
+/// screen, see [`crate::components::damage`].
+#[instrument(skip_all)]
+pub fn death_message(
+    r: Receiver<PlayerKilled>,
+    global: Single<&Global>,
+    names: Fetcher<(Option<&InGameName>, Option<&MobKind>)>,
+    mut encoders: Fetcher<&mut LocalEncoder>,
+    mut announcements: Single<&mut Announcements>,
+) {
+    let event = r.event;
+
+    let name = |id: EntityId| match names.get(id) {
+        Ok((Some(name), _)) => Some(name.to_string().into_text()),
+        Ok((None, Some(kind))) => {
+            let key = format!("entity.minecraft.{}", kind.name());
+            Some(Text::translate(key, []))
+        }
+        _ => None,
+    };
+
+    let Some(victim) = name(event.victim) else {
+        return;
+    };
+
+    let killer = event
+        .killer
+        .filter(|&killer| killer != event.victim)
+        .and_then(name);
+
+    let message = damage::death_message(victim.clone(), event.cause, killer.clone(), event.weapon);
+
+    if let Ok(encoder) = encoders.get_mut(event.victim) {
+        // local is id 0
+        let pkt = play::DeathMessageS2c {
+            player_id: VarInt(0),
+            message: message.clone().into(),
+        };
+
+        encoder.append(&pkt, &global).unwrap();
+    }
+
+    announcements.chat(Audience::Everyone, message);
+
+    let feed = match killer {
+        Some(killer) => {
+            // i.e., `Alex [Iron Sword] Steve`
+            let weapon = event.weapon.map_or_else(
+                || " killed ".into_text(),
+                |weapon| " ".into_text() + weapon_name(weapon).color(Color::GRAY) + " ",
+            );
+
+            killer.color(Color::RED) + weapon + victim
+        }
+        None => victim + " died".into_text().color(Color::GRAY),
+    };
+
+    announcements.action_bar(Audience::Everyone, feed, Priority::Info);
+}
@@ -0,0 +1,31 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    events::DebugCommand,
+    singleton::announcements::{Announcements, Audience, Screen},
+};
+
+/// Handles `/debug`, which shows or hides the ms per tick in the action bar.
+#[instrument(skip_all)]
+pub fn debug_command(
+    r: Receiver<DebugCommand>,
+    mut screens: Fetcher<&mut Screen>,
+    mut announcements: Single<&mut Announcements>,
+) {
+    let from = r.event.from;
+
+    let Ok(screen) = screens.get_mut(from) else {
+        return;
+    };
+
+    screen.debug_overlay = !screen.debug_overlay;
+
+    let feedback = if screen.debug_overlay {
+        "Showing the debug overlay"
+    } else {
+        "Hiding the debug overlay"
+    };
+
+    announcements.chat(Audience::Player(from), feedback);
+}
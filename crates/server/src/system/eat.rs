@@ -0,0 +1,155 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::{
+    ident,
+    packets::play,
+    sound::{SoundCategory, SoundId},
+    ItemStack, RawBytes, VarInt,
+};
+
+use crate::{
+    components::{
+        inventory::{Inventory, OFFHAND},
+        status_effects::{StatusEffect, StatusEffects},
+        Eating, FullEntityPose, Hunger, Vitals,
+    },
+    events::Gametick,
+    food::Food,
+    global::Global,
+    metadata::Metadata,
+    net::LocalEncoder,
+    singleton::broadcast::BroadcastBuf,
+};
+
+/// The metadata index of the hand states of a living entity.
+const HAND_STATES_INDEX: u8 = 8;
+
+/// The hand state flag of an entity which is using an item.
+const HAND_ACTIVE: u8 = 0x01;
+
+/// The hand state flag of an entity which is using the item in their offhand.
+const OFFHAND_ACTIVE: u8 = 0x02;
+
+/// The entity status which tells a player they finished using an item.
+const USE_ITEM_FINISHED: u8 = 9;
+
+#[derive(Query)]
+pub struct EatQuery<'a> {
+    id: EntityId,
+    pose: &'a FullEntityPose,
+    eating: &'a mut Eating,
+    inventory: &'a mut Inventory,
+    vitals: &'a Vitals,
+    hunger: &'a mut Hunger,
+    effects: &'a mut StatusEffects,
+    encoder: &'a mut LocalEncoder,
+}
+
+/// Finishes eating for players who ate long enough, see [`crate::food`].
+#[instrument(skip_all, level = "trace")]
+pub fn eat(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut players: Fetcher<EatQuery>,
+    broadcast: Single<&mut BroadcastBuf>,
+) {
+    let tick = global.tick;
+    let mut broadcast = broadcast.0.get_round_robin();
+
+    for mut query in &mut players {
+        let entity_id = VarInt(query.id.index().0 as i32);
+
+        if let Some(meal) = query.eating.meal {
+            let still_holding = query.inventory.get(meal.slot).item == meal.item;
+
+            if matches!(query.vitals, Vitals::Dead { .. }) || !still_holding {
+                query.eating.meal = None;
+            } else if tick >= meal.finish_tick {
+                query.eating.meal = None;
+
+                // the food was checked when eating started
+                let food = Food::of(meal.item).unwrap();
+                finish(&food, meal.slot, &mut query, tick);
+
+                query
+                    .encoder
+                    .append(&query.inventory.slot_packet(meal.slot), &global)
+                    .unwrap();
+
+                // local is id 0
+                query
+                    .encoder
+                    .append(
+                        &play::EntityStatusS2c {
+                            entity_id: 0,
+                            entity_status: USE_ITEM_FINISHED,
+                        },
+                        &global,
+                    )
+                    .unwrap();
+
+                for sound in [ident!("entity.generic.eat"), ident!("entity.player.burp")] {
+                    let pkt = play::PlaySoundS2c {
+                        id: SoundId::Direct {
+                            id: sound.into(),
+                            range: None,
+                        },
+                        category: SoundCategory::Player,
+                        position: (query.pose.position * 8.0).as_ivec3(),
+                        volume: 1.0,
+                        pitch: 1.0,
+                        seed: 0,
+                    };
+
+                    broadcast.append_packet(&pkt).unwrap();
+                }
+            }
+        }
+
+        // show other players whether the player is eating
+        let eating = query.eating.meal.is_some();
+
+        if eating == query.eating.shown {
+            continue;
+        }
+
+        query.eating.shown = eating;
+
+        let hand_states = match query.eating.meal {
+            Some(meal) if meal.slot == OFFHAND => HAND_ACTIVE | OFFHAND_ACTIVE,
+            Some(_) => HAND_ACTIVE,
+            None => 0,
+        };
+
+        let metadata = Metadata::default()
+            .byte(HAND_STATES_INDEX, hand_states)
+            .finish();
+
+        let pkt = play::EntityTrackerUpdateS2c {
+            entity_id,
+            tracked_values: RawBytes(&metadata),
+        };
+
+        broadcast.append_packet(&pkt).unwrap();
+    }
+}
+
+/// Consumes the item and applies the food.
+fn finish(food: &Food, slot: usize, query: &mut EatQuery, tick: i64) {
+    let mut stack = query.inventory.get(slot).clone();
+    stack.count -= 1;
+
+    if stack.count <= 0 {
+        stack = ItemStack::EMPTY;
+    }
+
+    query.inventory.set(slot, stack);
+    query.hunger.eat(food);
+
+    for effect in food.effects {
+        query.effects.add(
+            effect.kind,
+            StatusEffect::new(effect.amplifier, tick, effect.duration),
+        );
+    }
+}
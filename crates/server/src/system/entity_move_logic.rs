@@ -1,77 +1,191 @@
+use bvh::aabb::Aabb;
 use evenio::{
     entity::EntityId,
-    event::Receiver,
+    event::{Receiver, Sender},
     fetch::{Fetcher, Single},
-    query::{Query, With},
+    query::{Not, Query, With},
+};
+use rand::Rng;
+use tracing::instrument;
+use valence_protocol::{
+    math::{Vec2, Vec3},
+    ItemKind,
 };
-use tracing::{info, instrument};
-use valence_protocol::math::{Vec2, Vec3};
 
 use crate::{
-    components::{EntityReaction, FullEntityPose, MinecraftEntity, RunningSpeed},
-    events::Gametick,
-    singleton::player_aabb_lookup::PlayerBoundingBoxes,
+    components::{
+        mob_kind::{
+            Behavior, MobKind, RANGED_ARROW_SPEED, RANGED_ATTACK_DISTANCE, RANGED_COOLDOWN,
+            RANGED_DISTANCE,
+        },
+        projectile::{self, ProjectileKind},
+        status_effects::StatusEffects,
+        Dying, EntityPhysics, EntityReaction, FullEntityPose, MinecraftEntity, PathFollower,
+        RangedAttack, RunningSpeed, Target, Wander,
+    },
+    events::{Gametick, ShootProjectile},
+    global::Global,
 };
 
 #[derive(Query, Debug)]
 pub struct EntityQuery<'a> {
+    id: EntityId,
+    kind: &'a MobKind,
     running_speed: Option<&'a RunningSpeed>,
+    effects: Option<&'a StatusEffects>,
     reaction: &'a mut EntityReaction,
+    physics: &'a mut EntityPhysics,
+    follower: Option<&'a mut PathFollower>,
+    target: Option<&'a Target>,
+    wander: Option<&'a mut Wander>,
+    ranged: Option<&'a mut RangedAttack>,
     pose: &'a mut FullEntityPose,
     _entity: With<&'static MinecraftEntity>,
+    _alive: Not<&'static Dying>,
 }
 
 #[instrument(skip_all, level = "trace")]
 pub fn entity_move_logic(
     _: Receiver<Gametick>,
     mut entities: Fetcher<EntityQuery>,
-    lookup: Single<&PlayerBoundingBoxes>,
+    global: Single<&Global>,
+    mut s: Sender<ShootProjectile>,
 ) {
+    let tick = global.tick;
+
     entities.iter_mut().for_each(|query| {
         let EntityQuery {
+            id,
+            kind,
             running_speed,
+            effects,
             pose,
             reaction,
+            physics,
+            follower,
+            target,
+            wander,
+            ranged,
             ..
         } = query;
 
         let current = pose.position;
-
-        let Some(target) = lookup.closest_to(current) else {
-            return;
+        let speed = running_speed.copied().unwrap_or_default().0
+            * effects.map_or(1.0, StatusEffects::speed_multiplier);
+
+        // the horizontal direction to look in and whether to walk in that direction
+        let heading = match kind.behavior() {
+            Behavior::Passive => wander.map(|wander| (wander_direction(wander, tick), true)),
+            behavior => target.and_then(|target| target.0).map(|target| {
+                let dif_mid = target.aabb.mid() - current;
+                let to_target = Vec2::new(dif_mid.x, dif_mid.z);
+
+                if behavior == Behavior::Ranged {
+                    let distance = dif_mid.length();
+
+                    if let Some(ranged) = ranged.filter(|ranged| {
+                        distance < RANGED_ATTACK_DISTANCE && tick >= ranged.next_shot
+                    }) {
+                        ranged.next_shot = tick + RANGED_COOLDOWN;
+                        s.send(shoot_at(id, *kind, current, target.aabb));
+                    }
+
+                    if distance < RANGED_DISTANCE {
+                        return (to_target, false);
+                    }
+                }
+
+                let direction = follower
+                    .and_then(|follower| next_waypoint(follower, current))
+                    .map_or(to_target, |waypoint| {
+                        Vec2::new(waypoint.x - current.x, waypoint.z - current.z)
+                    });
+
+                (direction, true)
+            }),
         };
 
-        let dif_mid = target.aabb.mid() - current;
-        // let dif_height = target.aabb.min.y - current.y;
+        let mut walk = Vec3::ZERO;
+
+        if let Some((dif2d, walking)) = heading {
+            if dif2d.length_squared() >= 0.01 {
+                let yaw = dif2d.y.atan2(dif2d.x).to_degrees();
+
+                // subtract 90 degrees
+                pose.yaw = yaw - 90.0;
+                pose.pitch = 0.0;
 
-        let dif2d = Vec2::new(dif_mid.x, dif_mid.z);
+                if walking {
+                    let dif2d = dif2d.normalize() * speed;
+                    walk = Vec3::new(dif2d.x, 0.0, dif2d.y);
+                }
+            }
+        }
 
-        let yaw = dif2d.y.atan2(dif2d.x).to_degrees();
+        physics.walk = walk + reaction.velocity;
 
-        // subtract 90 degrees
-        let yaw = yaw - 90.0;
+        reaction.velocity = Vec3::ZERO;
+    });
+}
 
-        // let pitch = -dif_height.atan2(dif2d.length()).to_degrees();
-        let pitch = 0.0;
+/// The arrow a ranged mob standing at `feet` shoots at a target with the bounding box `target`.
+fn shoot_at(id: EntityId, kind: MobKind, feet: Vec3, target: Aabb) -> ShootProjectile {
+    let (_, height) = kind.size();
+
+    // like vanilla, arrows start just below the eyes at 85% of the height and are aimed at a third
+    // of the height of the target
+    let position = feet + Vec3::new(0.0, height.mul_add(0.85, -0.1), 0.0);
+    let mid = target.mid();
+    let aim = Vec3::new(
+        mid.x,
+        target.lens().y.mul_add(1.0 / 3.0, target.min.y),
+        mid.z,
+    );
+
+    ShootProjectile {
+        owner: id,
+        kind: ProjectileKind::Arrow,
+        position,
+        velocity: projectile::aim(position, aim, RANGED_ARROW_SPEED),
+        weapon: Some(ItemKind::Bow),
+        critical: false,
+    }
+}
 
-        if dif2d.length_squared() < 0.01 {
-            // info!("Moving entity {:?} by {:?}", id, reaction.velocity);
-            pose.move_by(reaction.velocity);
+/// The chance per tick that a wandering mob starts or stops walking.
+const WANDER_CHANCE: f64 = 1.0 / 60.0;
+
+/// The horizontal direction a passive mob walks in, picking a new one from time to time.
+fn wander_direction(wander: &mut Wander, tick: i64) -> Vec2 {
+    if tick >= wander.until || rand::random::<f64>() < WANDER_CHANCE {
+        let walking = rand::random::<bool>();
+
+        wander.direction = if walking {
+            let angle = rand::random::<f32>() * std::f32::consts::TAU;
+            Vec3::new(angle.cos(), 0.0, angle.sin())
         } else {
-            // normalize
-            let dif2d = dif2d.normalize();
+            Vec3::ZERO
+        };
 
-            let speed = running_speed.copied().unwrap_or_default();
-            let dif2d = dif2d * speed.0;
+        wander.until = tick + rand::thread_rng().gen_range(40..120);
+    }
 
-            let vec = Vec3::new(dif2d.x, 0.0, dif2d.y) + reaction.velocity;
+    Vec2::new(wander.direction.x, wander.direction.z)
+}
 
-            pose.move_by(vec);
-        }
+/// The horizontal distance at which a waypoint counts as reached.
+const WAYPOINT_REACHED: f32 = 0.35;
 
-        pose.pitch = pitch;
-        pose.yaw = yaw;
+/// The center of the next waypoint to walk to, skipping waypoints which were already reached.
+fn next_waypoint(follower: &mut PathFollower, current: Vec3) -> Option<Vec3> {
+    loop {
+        let waypoint = follower.next_waypoint()?.as_vec3() + Vec3::new(0.5, 0.0, 0.5);
+        let dif = Vec2::new(waypoint.x - current.x, waypoint.z - current.z);
 
-        reaction.velocity = Vec3::ZERO;
-    });
+        if dif.length() > WAYPOINT_REACHED {
+            return Some(waypoint);
+        }
+
+        follower.waypoint += 1;
+    }
 }
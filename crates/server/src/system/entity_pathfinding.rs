@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use evenio::{
+    event::Receiver,
+    fetch::{Fetcher, Single},
+    query::{Not, Query, With},
+};
+use glam::{IVec3, Vec3};
+use tracing::instrument;
+
+use crate::{
+    components::{Dying, FullEntityPose, MinecraftEntity, PathFollower, Target},
+    config,
+    events::Gametick,
+    global::Global,
+    pathfinding::{find_path, Outcome},
+    singleton::{
+        blocks::Blocks,
+        path_cache::{Path, PathCache, PATH_TTL},
+        player_aabb_lookup::PlayerBoundingBoxes,
+    },
+};
+
+/// How far (in blocks) the target may move away from the goal of a path before a new path is
+/// needed.
+const GOAL_DRIFT: i32 = 2;
+
+/// The number of ticks to wait before searching again after a search failed.
+const RETRY_DELAY: i64 = 20;
+
+#[derive(Query, Debug)]
+pub struct EntityQuery<'a> {
+    pose: &'a FullEntityPose,
+    follower: &'a mut PathFollower,
+    target: &'a mut Target,
+    _entity: With<&'static MinecraftEntity>,
+    _alive: Not<&'static Dying>,
+}
+
+/// Picks the closest player as the [`Target`] of every hostile entity and finds paths to it for
+/// entities which need a new one.
+///
+/// All searches share a per-tick node budget. Entities which do not get to search this tick, or
+/// whose search the budget cut short, keep following their old path (or walk straight towards
+/// their target) and try again next tick. Only searches which fail on their own wait
+/// [`RETRY_DELAY`] ticks.
+#[instrument(skip_all, level = "trace")]
+pub fn entity_pathfinding(
+    _: Receiver<Gametick>,
+    mut entities: Fetcher<EntityQuery>,
+    lookup: Single<&PlayerBoundingBoxes>,
+    blocks: Single<&Blocks>,
+    global: Single<&Global>,
+    mut cache: Single<&mut PathCache>,
+) {
+    let tick = global.tick;
+    let budget = config::CONFIG.pathfinding_budget;
+    // a single search can never expand more nodes than the whole budget
+    let max_nodes = config::CONFIG.pathfinding_max_nodes.min(budget);
+
+    cache.start_tick(tick, budget);
+
+    for query in entities.iter_mut() {
+        let EntityQuery {
+            pose,
+            follower,
+            target,
+            ..
+        } = query;
+
+        target.0 = lookup.closest_to(pose.position).copied();
+
+        let Some(target) = target.0 else {
+            follower.path = None;
+            continue;
+        };
+
+        let mid = target.aabb.mid();
+        let goal = Vec3::new(mid.x, target.aabb.min.y, mid.z)
+            .floor()
+            .as_ivec3();
+
+        if !needs_path(follower, goal, tick) {
+            continue;
+        }
+
+        let start = pose.position.floor().as_ivec3();
+
+        if let Some((path, waypoint)) = cache.find(goal, start) {
+            follower.path = Some(path);
+            follower.waypoint = waypoint;
+            continue;
+        }
+
+        if cache.budget == 0 {
+            continue;
+        }
+
+        let limit = cache.budget.min(max_nodes);
+        let search = find_path(start, goal, limit, |pos| blocks.is_solid(pos));
+
+        cache.budget = cache.budget.saturating_sub(search.expanded);
+
+        match search.outcome {
+            Outcome::Found(waypoints) => {
+                let path = Arc::new(Path {
+                    goal,
+                    waypoints,
+                    created: tick,
+                });
+
+                if !path.waypoints.is_empty() {
+                    cache.insert(path.clone());
+                }
+
+                follower.path = Some(path);
+                follower.waypoint = 0;
+            }
+            // only the shared budget ran out, so search again next tick without a penalty
+            Outcome::Exhausted if limit < max_nodes => {}
+            Outcome::Unreachable | Outcome::Exhausted => {
+                follower.path = None;
+                follower.next_search = tick + RETRY_DELAY;
+            }
+        }
+    }
+}
+
+fn needs_path(follower: &PathFollower, goal: IVec3, tick: i64) -> bool {
+    if tick < follower.next_search {
+        return false;
+    }
+
+    let Some(path) = &follower.path else {
+        return true;
+    };
+
+    follower.waypoint >= path.waypoints.len()
+        || tick - path.created >= PATH_TTL
+        || (path.goal - goal).abs().max_element() > GOAL_DRIFT
+}
@@ -0,0 +1,85 @@
+use evenio::{
+    event::Receiver,
+    fetch::{Fetcher, Single},
+    query::{Query, With},
+};
+use rayon::prelude::*;
+use tracing::instrument;
+use valence_protocol::math::Vec3;
+
+use crate::{
+    components::{EntityPhysics, FullEntityPose, MinecraftEntity, OnGround},
+    events::Gametick,
+    physics::{self, AIR_DRAG, GRAVITY, GROUND_DRAG, STEP_HEIGHT, VERTICAL_DRAG},
+    singleton::blocks::Blocks,
+};
+
+#[derive(Query, Debug)]
+pub struct EntityQuery<'a> {
+    pose: &'a mut FullEntityPose,
+    physics: &'a mut EntityPhysics,
+    on_ground: &'a mut OnGround,
+    _entity: With<&'static MinecraftEntity>,
+}
+
+/// Moves entities by their velocity and walking movement, colliding with blocks and applying
+/// gravity.
+#[instrument(skip_all, level = "trace")]
+pub fn entity_physics(
+    _: Receiver<Gametick>,
+    mut entities: Fetcher<EntityQuery>,
+    blocks: Single<&Blocks>,
+) {
+    let blocks = &*blocks;
+
+    entities
+        .par_iter_mut()
+        .for_each_init(Vec::new, |obstacles, query| {
+            let EntityQuery {
+                pose,
+                physics,
+                on_ground,
+                ..
+            } = query;
+
+            let movement = physics.velocity + physics.walk;
+
+            // Include everything the entity could touch while stepping up.
+            let region = physics::swept(pose.bounding, movement).expand(STEP_HEIGHT);
+
+            obstacles.clear();
+            blocks.collisions(region, |aabb| obstacles.push(aabb));
+
+            let result =
+                physics::move_aabb(pose.bounding, movement, STEP_HEIGHT, on_ground.0, obstacles);
+
+            pose.move_by(result.offset);
+            on_ground.0 = result.on_ground;
+
+            let velocity = &mut physics.velocity;
+
+            if result.collided_x {
+                velocity.x = 0.0;
+            }
+
+            if result.collided_y {
+                velocity.y = 0.0;
+            }
+
+            if result.collided_z {
+                velocity.z = 0.0;
+            }
+
+            let drag = if result.on_ground {
+                GROUND_DRAG
+            } else {
+                AIR_DRAG
+            };
+
+            velocity.x *= drag;
+            velocity.z *= drag;
+            velocity.y = (velocity.y - GRAVITY) * VERTICAL_DRAG;
+
+            physics.walk = Vec3::ZERO;
+        });
+}
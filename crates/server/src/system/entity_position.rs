@@ -4,7 +4,7 @@ use tracing::instrument;
 use valence_protocol::{packets::play, ByteAngle, VarInt};
 
 use crate::{
-    components::{FullEntityPose, Uuid},
+    components::{FullEntityPose, OnGround, Uuid},
     events::Gametick,
     singleton::broadcast::{BroadcastBuf, PacketMetadata, PacketNecessity},
 };
@@ -15,6 +15,7 @@ pub(crate) struct EntityQuery<'a> {
     uuid: &'a Uuid,
 
     pose: &'a mut FullEntityPose,
+    on_ground: Option<&'a OnGround>,
     last_pose: &'a mut PositionSyncMetadata,
 }
 
@@ -37,9 +38,12 @@ pub fn sync_entity_position(
             uuid,
 
             pose,
+            on_ground,
             last_pose: sync_meta,
         } = query;
 
+        let on_ground = on_ground.copied().unwrap_or_default().0;
+
         let pos = pose.position;
         let pitch = ByteAngle::from_degrees(pose.pitch);
         let yaw = ByteAngle::from_degrees(pose.yaw);
@@ -110,7 +114,7 @@ pub fn sync_entity_position(
             exclude_player: Some(uuid.0),
         };
 
-        movement.write_packets(id, &broadcast, metadata);
+        movement.write_packets(id, on_ground, &broadcast, metadata);
 
         if let EntityMovement::Teleport { .. } = movement {
             sync_meta.rounding_error = Vec3::ZERO;
@@ -143,14 +147,19 @@ pub enum EntityMovement {
 }
 
 impl EntityMovement {
-    fn write_packets(&self, id: EntityId, broadcast: &BroadcastBuf, metadata: PacketMetadata) {
+    fn write_packets(
+        &self,
+        id: EntityId,
+        on_ground: bool,
+        broadcast: &BroadcastBuf,
+        metadata: PacketMetadata,
+    ) {
         #[expect(
             clippy::cast_possible_wrap,
             reason = "wrapping is okay in this scenario"
         )]
         let entity_id = VarInt(id.index().0 as i32);
 
-        // TODO: calculate on_ground
         // TODO: remove unwrap
         match *self {
             Self::PositionAndRotation { delta, pitch, yaw } => {
@@ -159,7 +168,7 @@ impl EntityMovement {
                     delta,
                     pitch,
                     yaw,
-                    on_ground: false,
+                    on_ground,
                 };
 
                 let look = play::EntitySetHeadYawS2c {
@@ -174,7 +183,7 @@ impl EntityMovement {
                 let pos = play::MoveRelativeS2c {
                     entity_id,
                     delta,
-                    on_ground: false,
+                    on_ground,
                 };
 
                 broadcast.append(&pos, metadata).unwrap();
@@ -184,7 +193,7 @@ impl EntityMovement {
                     entity_id,
                     pitch,
                     yaw,
-                    on_ground: false,
+                    on_ground,
                 };
 
                 let look = play::EntitySetHeadYawS2c {
@@ -201,7 +210,7 @@ impl EntityMovement {
                     position: pos.as_dvec3(),
                     yaw,
                     pitch,
-                    on_ground: false,
+                    on_ground,
                 };
 
                 let look = play::EntitySetHeadYawS2c {
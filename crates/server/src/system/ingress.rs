@@ -7,7 +7,6 @@ use evenio::{
 };
 use serde_json::json;
 use tracing::{info, instrument, trace, warn};
-use uuid::Uuid;
 use valence_protocol::{
     decode::PacketFrame,
     packets,
@@ -24,11 +23,16 @@ use crate::{
 mod player_packet_buffer;
 
 use crate::{
-    components::{FullEntityPose, LoginState},
+    components::{
+        inventory::Inventory, CombatState, Eating, FullEntityPose, Hunger, LoginState, OnGround,
+        Teleport,
+    },
     events::{
-        AttackEntity, Gametick, InitEntity, KickPlayer, KillAllEntities, PlayerInit, SwingArm,
+        AttackEntity, DebugCommand, Gametick, InitEntity, KickPlayer, KillAllEntities, KitCommand,
+        PhaseCommand, PlayerInit, ShootProjectile, SwingArm,
     },
-    net::{Fd, LocalEncoder, MINECRAFT_VERSION, PROTOCOL_VERSION},
+    net::{self, Fd, LocalEncoder, MINECRAFT_VERSION, PROTOCOL_VERSION},
+    packets::PacketSwitchQuery,
     singleton::{buffer_allocator::BufferAllocator, player_id_lookup::PlayerIdLookup},
     system::ingress::player_packet_buffer::DecodeBuffer,
 };
@@ -48,6 +52,10 @@ pub type IngressSender<'a> = Sender<
         KillAllEntities,
         SwingArm,
         AttackEntity,
+        KitCommand,
+        PhaseCommand,
+        DebugCommand,
+        ShootProjectile,
     ),
 >;
 
@@ -66,6 +74,12 @@ pub fn ingress(
         &mut LocalEncoder,
         &Fd,
         Option<&mut FullEntityPose>,
+        Option<&mut OnGround>,
+        Option<&mut CombatState>,
+        Option<&mut Inventory>,
+        Option<&mut Eating>,
+        Option<&mut Hunger>,
+        Option<&mut Teleport>,
     )>,
     mut sender: IngressSender,
 ) {
@@ -104,8 +118,19 @@ pub fn ingress(
         ServerEvent::RecvData { fd, data } => {
             trace!("got data: {data:?}");
             let id = *fd_lookup.get(&fd).expect("player with fd not found");
-            let (login_state, decoder, encoder, _, mut pose) =
-                players.get_mut(id).expect("player with fd not found");
+            let (
+                login_state,
+                decoder,
+                encoder,
+                _,
+                mut pose,
+                mut on_ground,
+                mut combat,
+                mut inventory,
+                mut eating,
+                mut hunger,
+                mut teleport,
+            ) = players.get_mut(id).expect("player with fd not found");
 
             decoder.queue_slice(data);
 
@@ -137,8 +162,37 @@ pub fn ingress(
                     }
                     LoginState::TransitioningPlay | LoginState::Play => {
                         *login_state = LoginState::Play;
-                        if let Some(pose) = &mut pose {
-                            crate::packets::switch(frame, &global, &mut sender, pose).unwrap();
+                        if let (
+                            Some(pose),
+                            Some(on_ground),
+                            Some(combat),
+                            Some(inventory),
+                            Some(eating),
+                            Some(hunger),
+                            Some(teleport),
+                        ) = (
+                            &mut pose,
+                            &mut on_ground,
+                            &mut combat,
+                            &mut inventory,
+                            &mut eating,
+                            &mut hunger,
+                            &mut teleport,
+                        ) {
+                            let query = PacketSwitchQuery {
+                                id,
+                                pose,
+                                on_ground,
+                                combat,
+                                inventory,
+                                eating,
+                                hunger,
+                                teleport,
+                                encoder: &mut *encoder,
+                            };
+
+                            crate::packets::switch(frame, &global, &mut sender, &id_lookup, query)
+                                .unwrap();
                         }
                     }
                 }
@@ -184,7 +238,7 @@ fn process_login(
     info!("received LoginHello for {username}");
 
     let username = username.0;
-    let uuid = Uuid::new_v4();
+    let uuid = net::offline_uuid(&username)?;
 
     let pkt = LoginCompressionS2c {
         threshold: VarInt(global.shared.compression_level.0),
@@ -4,17 +4,21 @@ use evenio::{
     fetch::Fetcher,
     prelude::Single,
 };
-use generator::EntityType;
 use rand_distr::{Distribution, LogNormal};
 use tracing::{info, instrument};
 use valence_protocol::{ByteAngle, VarInt, Velocity};
 
 use crate::{
-    components::{EntityReaction, FullEntityPose, MinecraftEntity, RunningSpeed, Uuid},
+    components::{
+        mob_kind::{Behavior, MobKind},
+        status_effects::StatusEffects,
+        EntityPhysics, EntityReaction, FullEntityPose, ImmuneStatus, MinecraftEntity, OnGround,
+        PathFollower, RangedAttack, RunningSpeed, Target, Uuid, Vitals, Wander,
+    },
     events::InitEntity,
     global::Global,
     net::LocalEncoder,
-    singleton::broadcast::BroadcastBuf,
+    singleton::{broadcast::BroadcastBuf, player_id_lookup::PlayerIdLookup},
     system::entity_position::PositionSyncMetadata,
 };
 
@@ -22,16 +26,17 @@ pub fn spawn_packet(
     id: EntityId,
     uuid: Uuid,
     pose: &FullEntityPose,
+    kind: MobKind,
 ) -> valence_protocol::packets::play::EntitySpawnS2c {
     #[expect(clippy::cast_possible_wrap, reason = "wrapping is ok in this case")]
     let entity_id = VarInt(id.index().0 as i32);
 
-    info!("spawn packet for zombie with id {entity_id:?} pose {pose:?}");
+    info!("spawn packet for {kind:?} with id {entity_id:?} pose {pose:?}");
 
     valence_protocol::packets::play::EntitySpawnS2c {
         entity_id,
         object_uuid: *uuid,
-        kind: VarInt(EntityType::Zombie as i32),
+        kind: VarInt(kind.entity_type() as i32),
         position: pose.position.as_dvec3(),
         pitch: ByteAngle::from_degrees(pose.pitch),
         yaw: ByteAngle::from_degrees(pose.yaw),
@@ -51,12 +56,23 @@ pub fn init_entity(
         Insert<FullEntityPose>,
         Insert<PositionSyncMetadata>,
         Insert<MinecraftEntity>,
+        Insert<MobKind>,
+        Insert<Vitals>,
+        Insert<ImmuneStatus>,
+        Insert<Wander>,
         Insert<Uuid>,
         Insert<RunningSpeed>,
         Insert<EntityReaction>,
+        Insert<EntityPhysics>,
+        Insert<OnGround>,
+        Insert<PathFollower>,
+        Insert<Target>,
+        Insert<RangedAttack>,
+        Insert<StatusEffects>,
         Spawn,
     )>,
     mut broadcast: Single<&mut BroadcastBuf>,
+    mut id_lookup: Single<&mut PlayerIdLookup>,
 ) {
     let event = r.event;
 
@@ -64,16 +80,41 @@ pub fn init_entity(
 
     let uuid = Uuid::from(uuid::Uuid::new_v4());
 
+    id_lookup.inner.insert(id.index().0 as i32, id);
+
     s.insert(id, MinecraftEntity);
+    s.insert(id, event.kind);
+    s.insert(
+        id,
+        Vitals::Alive {
+            health: event.kind.max_health(),
+            absorption: 0.0,
+        },
+    );
+    s.insert(id, ImmuneStatus::default());
     s.insert(id, event.pose);
     s.insert(id, uuid);
     s.insert(id, EntityReaction::default());
-    s.insert(id, generate_running_speed());
+    s.insert(id, EntityPhysics::default());
+    s.insert(id, OnGround::default());
+    s.insert(id, generate_running_speed(event.kind));
     s.insert(id, PositionSyncMetadata::default());
+    s.insert(id, StatusEffects::default());
+
+    if event.kind.behavior() == Behavior::Passive {
+        s.insert(id, Wander::default());
+    } else {
+        s.insert(id, PathFollower::default());
+        s.insert(id, Target::default());
+    }
+
+    if event.kind.behavior() == Behavior::Ranged {
+        s.insert(id, RangedAttack::default());
+    }
 
     let pose = event.pose;
 
-    let pkt = spawn_packet(id, uuid, &pose);
+    let pkt = spawn_packet(id, uuid, &pose, event.kind);
 
     // for encoder in encoders {
     //     encoder.append(&pkt, &global).unwrap();
@@ -82,12 +123,12 @@ pub fn init_entity(
     broadcast.get_round_robin().append_packet(&pkt).unwrap();
 }
 
-fn generate_running_speed() -> RunningSpeed {
+fn generate_running_speed(kind: MobKind) -> RunningSpeed {
     // Parameters for the Log-Normal distribution
     let mean = 0.10; // Mean of the underlying Normal distribution
     let std_dev = 0.20; // Standard deviation of the underlying Normal distribution
     let log_normal = LogNormal::new(mean, std_dev).unwrap();
 
-    let speed = log_normal.sample(&mut rand::thread_rng()) * 0.1;
+    let speed = log_normal.sample(&mut rand::thread_rng()) * kind.speed();
     RunningSpeed(speed)
 }
@@ -3,10 +3,24 @@ use tracing::{info, instrument};
 
 use crate::{
     components::{
-        AiTargetable, EntityReaction, FullEntityPose, ImmuneStatus, InGameName, KeepAlive, Player,
-        Uuid, Vitals,
+        damage::DamageTracker, inventory::Inventory, status_effects::StatusEffects, AiTargetable,
+        CombatState, Eating, EntityReaction, FullEntityPose, Hunger, ImmuneStatus, InGameName,
+        KeepAlive, OnGround, Player, Teleport, Uuid, Vitals,
+    },
+    config,
+    events::{KickPlayer, PlayerInit, PlayerJoinWorld},
+    global::Global,
+    singleton::{
+        announcements::Screen,
+        blocks::Blocks,
+        kits::{GivenKit, Kits},
+        phase::PhaseController,
+        player_uuid_lookup::PlayerUuidLookup,
+        scoreboard::PlayerScore,
+        spawns::Spawns,
+        stats::MatchStats,
+        teams::{Team, Teams},
     },
-    events::{PlayerInit, PlayerJoinWorld},
     system::entity_position::PositionSyncMetadata,
     tracker::Prev,
 };
@@ -14,6 +28,15 @@ use crate::{
 #[instrument(skip_all)]
 pub fn init_player(
     r: ReceiverMut<PlayerInit>,
+    global: Single<&Global>,
+    kits: Single<&Kits>,
+    spawns: Single<&Spawns>,
+    blocks: Single<&Blocks>,
+    teams: Single<&Teams>,
+    phase: Single<&PhaseController>,
+    stats: Single<&MatchStats>,
+    members: Fetcher<&Team>,
+    mut uuid_lookup: Single<&mut PlayerUuidLookup>,
     mut s: Sender<(
         Insert<FullEntityPose>,
         Insert<PositionSyncMetadata>,
@@ -26,7 +49,22 @@ pub fn init_player(
         Insert<KeepAlive>,
         Insert<AiTargetable>,
         Insert<InGameName>,
+        Insert<OnGround>,
+        Insert<CombatState>,
+        Insert<Inventory>,
+        Insert<GivenKit>,
+        Insert<Hunger>,
+        Insert<Prev<Hunger>>,
+        Insert<Eating>,
+        Insert<StatusEffects>,
+        Insert<Teleport>,
+        Insert<Team>,
+        Insert<PlayerScore>,
+        Insert<Prev<PlayerScore>>,
+        Insert<Screen>,
+        Insert<DamageTracker>,
         PlayerJoinWorld,
+        KickPlayer,
     )>,
 ) {
     // take ownership
@@ -39,25 +77,70 @@ pub fn init_player(
         entity,
         username: name,
         uuid,
-        pose,
+        mut pose,
     } = event;
 
     info!("PlayerInit: {name}");
 
+    // the UUID is derived from the name, so a second session would share the statistics and the
+    // lookup entry of the first one
+    if !uuid_lookup.try_insert(uuid, entity) {
+        s.send(KickPlayer {
+            target: entity,
+            reason: format!("{name} is already online"),
+        });
+        return;
+    }
+
+    let team = teams.balance(members.iter());
+    let team_name = teams.name(team);
+
+    let spawn = spawns.spawn_for(team_name, phase.phase(), &blocks, &mut rand::thread_rng());
+    pose.move_to(spawn);
+
+    let mut immunity = ImmuneStatus::default();
+    immunity.protect(global.tick, config::CONFIG.spawn_protection);
+
     s.insert(entity, pose);
     s.insert(entity, Player);
     s.insert(entity, AiTargetable);
     s.insert(entity, InGameName::from(name));
-    s.insert(entity, ImmuneStatus::default());
+    s.insert(entity, immunity);
     s.insert(entity, Uuid::from(uuid));
     s.insert(entity, PositionSyncMetadata::default());
     s.insert(entity, KeepAlive::default());
 
+    let given = GivenKit::default();
+    let mut inventory = Inventory::default();
+    let mut effects = StatusEffects::default();
+
+    if let Some(kit) = kits.kit_for(&given, team_name) {
+        kit.apply(&mut inventory, &mut effects, global.tick);
+    }
+
     s.insert(entity, Prev::from(Vitals::ALIVE));
     s.insert(entity, Vitals::ALIVE);
 
-    s.insert(entity, FullEntityPose::player());
     s.insert(entity, EntityReaction::default());
+    s.insert(entity, OnGround::default());
+    s.insert(entity, CombatState::default());
+    s.insert(entity, inventory);
+    s.insert(entity, given);
+    s.insert(entity, Hunger::default());
+    s.insert(entity, Prev::from(Hunger::default()));
+    s.insert(entity, Eating::default());
+    s.insert(entity, effects);
+    s.insert(entity, Teleport::default());
+    s.insert(entity, team);
+    // players who reconnect during a round keep their score
+    let score = stats
+        .get(uuid)
+        .map_or_else(PlayerScore::default, PlayerScore::from);
+
+    s.insert(entity, score);
+    s.insert(entity, Prev::from(score));
+    s.insert(entity, Screen::default());
+    s.insert(entity, DamageTracker::default());
 
     s.send(PlayerJoinWorld { target: entity });
 }
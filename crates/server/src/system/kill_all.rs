@@ -5,7 +5,7 @@ use valence_protocol::VarInt;
 use crate::{
     components::{MinecraftEntity, Player},
     events::KillAllEntities,
-    singleton::broadcast::BroadcastBuf,
+    singleton::{broadcast::BroadcastBuf, player_id_lookup::PlayerIdLookup},
 };
 
 #[instrument(skip_all)]
@@ -13,6 +13,7 @@ pub fn kill_all(
     _r: ReceiverMut<KillAllEntities>,
     entities: Fetcher<(EntityId, &MinecraftEntity, Not<&Player>)>,
     broadcast: Single<&mut BroadcastBuf>,
+    mut id_lookup: Single<&mut PlayerIdLookup>,
     mut s: Sender<Despawn>,
 ) {
     let ids = entities.iter().map(|(id, ..)| id).collect::<Vec<_>>();
@@ -29,6 +30,7 @@ pub fn kill_all(
         .unwrap();
 
     for id in ids {
+        id_lookup.inner.remove(&(id.index().0 as i32));
         s.send(Despawn(id));
     }
 }
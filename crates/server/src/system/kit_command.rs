@@ -0,0 +1,106 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::{packets::play, text::IntoText};
+
+use crate::{
+    components::{inventory::Inventory, status_effects::StatusEffects, InGameName},
+    config,
+    events::KitCommand,
+    global::Global,
+    net::LocalEncoder,
+    singleton::{
+        kits::{GivenKit, Kits},
+        teams::{Team, Teams},
+    },
+};
+
+#[derive(Query)]
+pub struct KitQuery<'a> {
+    id: EntityId,
+    name: &'a InGameName,
+    inventory: &'a mut Inventory,
+    effects: &'a mut StatusEffects,
+    given: &'a mut GivenKit,
+    team: &'a Team,
+    encoder: &'a mut LocalEncoder,
+}
+
+/// Handles `/kit give <kit> [player]` and `/kit reset [player]`.
+#[instrument(skip_all)]
+pub fn kit_command(
+    r: Receiver<KitCommand>,
+    global: Single<&Global>,
+    kits: Single<&Kits>,
+    teams: Single<&Teams>,
+    mut players: Fetcher<KitQuery>,
+) {
+    let event = r.event;
+
+    let Ok(sender) = players.get(event.from) else {
+        return;
+    };
+
+    let feedback = if config::CONFIG.is_admin(sender.name) {
+        change_kit(event, &global, &kits, &teams, &mut players)
+    } else {
+        "You are not allowed to change kits".to_owned()
+    };
+
+    let Ok(sender) = players.get_mut(event.from) else {
+        return;
+    };
+
+    let pkt = play::GameMessageS2c {
+        chat: feedback.into_cow_text(),
+        overlay: false,
+    };
+
+    sender.encoder.append(&pkt, &global).unwrap();
+}
+
+/// Changes the kit of the player and returns the feedback for the sender.
+fn change_kit(
+    event: &KitCommand,
+    global: &Global,
+    kits: &Kits,
+    teams: &Teams,
+    players: &mut Fetcher<KitQuery>,
+) -> String {
+    let target = match &event.player {
+        Some(name) => players
+            .iter()
+            .find(|player| ***player.name == **name)
+            .map(|player| player.id),
+        None => Some(event.from),
+    };
+
+    let Some(player) = target.and_then(|id| players.get_mut(id).ok()) else {
+        return "There is no such player".to_owned();
+    };
+
+    let given = match event.kit.as_deref() {
+        Some(name) => match kits.find(name) {
+            Some(idx) => Some(idx),
+            None => {
+                let names: Vec<_> = kits.names().collect();
+                return format!("There is no kit {name}, try one of {}", names.join(", "));
+            }
+        },
+        None => None,
+    };
+
+    *player.given = GivenKit(given);
+
+    let Some(kit) = kits.kit_for(player.given, teams.name(*player.team)) else {
+        return format!("{} has no kit", player.name);
+    };
+
+    kit.apply(player.inventory, player.effects, global.tick);
+
+    player
+        .encoder
+        .append(&player.inventory.sync_packet(), global)
+        .unwrap();
+
+    format!("Gave {} the kit {}", player.name, kit.name)
+}
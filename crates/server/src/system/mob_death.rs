@@ -0,0 +1,127 @@
+use evenio::prelude::*;
+use generator::EntityType;
+use tracing::instrument;
+use valence_protocol::{packets::play, ByteAngle, ItemStack, RawBytes, VarInt, Velocity};
+
+use crate::{
+    components::{mob_kind::MobKind, DroppedItem, Dying, FullEntityPose},
+    config,
+    events::Gametick,
+    global::Global,
+    metadata::Metadata,
+    singleton::{broadcast::BroadcastBuf, player_id_lookup::PlayerIdLookup},
+};
+
+/// The number of ticks dropped items stay on the ground (5 minutes like in vanilla).
+const ITEM_LIFETIME: i64 = 6000;
+
+/// The metadata index of the item stack of an item entity.
+const ITEM_STACK_INDEX: u8 = 8;
+
+#[derive(Query)]
+pub struct DyingQuery<'a> {
+    id: EntityId,
+    dying: &'a Dying,
+    pose: &'a FullEntityPose,
+    kind: &'a MobKind,
+}
+
+/// Despawns mobs once their death animation finished, drops their items and despawns items which
+/// were lying on the ground for too long.
+#[instrument(skip_all, level = "trace")]
+pub fn mob_death(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    dying: Fetcher<DyingQuery>,
+    items: Fetcher<(EntityId, &DroppedItem)>,
+    mut id_lookup: Single<&mut PlayerIdLookup>,
+    broadcast: Single<&mut BroadcastBuf>,
+    mut s: Sender<(Spawn, Insert<DroppedItem>, Despawn)>,
+) {
+    let tick = global.tick;
+    let mut broadcast = broadcast.0.get_round_robin();
+
+    let mut despawned = Vec::new();
+
+    for query in dying {
+        if tick < query.dying.despawn_tick {
+            continue;
+        }
+
+        despawned.push(query.id);
+        id_lookup.inner.remove(&(query.id.index().0 as i32));
+
+        if !config::CONFIG.mob_drops {
+            continue;
+        }
+
+        let item = DroppedItem {
+            uuid: uuid::Uuid::new_v4(),
+            stack: ItemStack::new(query.kind.drop(), 1, None),
+            position: query.pose.position,
+            despawn_tick: tick + ITEM_LIFETIME,
+        };
+
+        let id = s.spawn();
+
+        let metadata = item_metadata(&item);
+
+        broadcast
+            .append_packet(&item_spawn_packet(id, &item))
+            .unwrap();
+        broadcast
+            .append_packet(&play::EntityTrackerUpdateS2c {
+                entity_id: VarInt(id.index().0 as i32),
+                tracked_values: RawBytes(&metadata),
+            })
+            .unwrap();
+
+        s.insert(id, item);
+    }
+
+    for (id, item) in items {
+        if tick >= item.despawn_tick {
+            despawned.push(id);
+        }
+    }
+
+    if despawned.is_empty() {
+        return;
+    }
+
+    let entity_ids = despawned
+        .iter()
+        .map(|id| VarInt(id.index().0 as i32))
+        .collect();
+
+    broadcast
+        .append_packet(&play::EntitiesDestroyS2c { entity_ids })
+        .unwrap();
+
+    for id in despawned {
+        s.despawn(id);
+    }
+}
+
+/// The packet which spawns a dropped item for clients. It has to be followed by the metadata from
+/// [`item_metadata`].
+pub fn item_spawn_packet(id: EntityId, item: &DroppedItem) -> play::EntitySpawnS2c {
+    play::EntitySpawnS2c {
+        entity_id: VarInt(id.index().0 as i32),
+        object_uuid: item.uuid,
+        kind: VarInt(EntityType::Item as i32),
+        position: item.position.as_dvec3(),
+        pitch: ByteAngle(0),
+        yaw: ByteAngle(0),
+        head_yaw: ByteAngle(0),
+        data: VarInt::default(),
+        velocity: Velocity([0; 3]),
+    }
+}
+
+/// The encoded metadata of a dropped item which tells clients which item it is.
+pub fn item_metadata(item: &DroppedItem) -> Vec<u8> {
+    Metadata::default()
+        .item_stack(ITEM_STACK_INDEX, &item.stack)
+        .finish()
+}
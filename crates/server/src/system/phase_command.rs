@@ -0,0 +1,49 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::{packets::play, text::IntoText};
+
+use crate::{
+    components::InGameName,
+    config,
+    events::{PhaseChanged, PhaseCommand},
+    global::Global,
+    net::LocalEncoder,
+    singleton::phase::PhaseController,
+};
+
+/// Handles `/phase <phase>`.
+#[instrument(skip_all)]
+pub fn phase_command(
+    r: Receiver<PhaseCommand>,
+    global: Single<&Global>,
+    mut controller: Single<&mut PhaseController>,
+    mut players: Fetcher<(&InGameName, &mut LocalEncoder)>,
+    mut s: Sender<PhaseChanged>,
+) {
+    let event = r.event;
+
+    let Ok((name, encoder)) = players.get_mut(event.from) else {
+        return;
+    };
+
+    let feedback = if !config::CONFIG.is_admin(name) {
+        "You are not allowed to change the phase".to_owned()
+    } else if controller.phase() == event.phase {
+        format!("The match is already in the {}", event.phase.name())
+    } else {
+        let from = controller.switch(event.phase, global.tick);
+        s.send(PhaseChanged {
+            from,
+            to: event.phase,
+        });
+
+        format!("Switched to the {}", event.phase.name())
+    };
+
+    let pkt = play::GameMessageS2c {
+        chat: feedback.into_cow_text(),
+        overlay: false,
+    };
+
+    encoder.append(&pkt, &global).unwrap();
+}
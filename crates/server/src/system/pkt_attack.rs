@@ -1,89 +1,132 @@
+use bvh::aabb::Aabb;
 use evenio::prelude::*;
+use glam::Vec3;
 use tracing::instrument;
-use valence_protocol::{packets::play, VarInt};
+use valence_protocol::ItemKind;
 
 use crate::{
-    components::{EntityReaction, FullEntityPose, ImmuneStatus, Player, Vitals},
-    events::AttackEntity,
-    net::LocalEncoder,
-    singleton::broadcast::BroadcastBuf,
+    combat::{self, SWEEP_DAMAGE, SWEEP_HORIZONTAL, SWEEP_KNOCKBACK, SWEEP_REACH, SWEEP_VERTICAL},
+    components::{
+        damage::DamageCause, inventory::Inventory, status_effects::StatusEffects, CombatState,
+        Dying, FullEntityPose, Hunger, OnGround, Vitals,
+    },
+    config,
+    events::{AttackEntity, DamageEntity},
+    food::ATTACK_EXHAUSTION,
+    global::Global,
+    singleton::{bounding_box::EntityBoundingBoxes, player_aabb_lookup::PlayerBoundingBoxes},
 };
 
+/// The player who attacks.
 #[derive(Query)]
-pub struct AttackQuery<'a> {
-    id: EntityId,
+pub struct AttackerQuery<'a> {
     pose: &'a FullEntityPose,
-    reaction: &'a mut EntityReaction,
-    encoder: &'a mut LocalEncoder,
-    immunity: &'a mut ImmuneStatus,
-    vitals: &'a mut Vitals,
-    _player: With<&'static Player>,
+    on_ground: &'a OnGround,
+    combat: &'a mut CombatState,
+    inventory: &'a Inventory,
+    effects: &'a StatusEffects,
+    vitals: &'a Vitals,
+    hunger: &'a mut Hunger,
 }
 
+/// Turns an attack of a player into damage for the target and, if the attack sweeps, the entities
+/// next to it. Whether players and teammates are hurt is up to `system::damage_entity`.
 #[instrument(skip_all, level = "trace")]
 pub fn pkt_attack(
-    global: Single<&crate::global::Global>,
-    attack: Receiver<AttackEntity, AttackQuery>,
-    mut broadcast: Single<&mut BroadcastBuf>,
+    global: Single<&Global>,
+    attack: Receiver<AttackEntity, (EntityId, &FullEntityPose, Not<&Dying>)>,
+    mut attackers: Fetcher<AttackerQuery>,
+    entity_bounding_boxes: Single<&EntityBoundingBoxes>,
+    player_bounding_boxes: Single<&PlayerBoundingBoxes>,
+    mut s: Sender<DamageEntity>,
 ) {
-    let AttackQuery {
-        id: entity_id,
-        pose,
-        reaction,
-        encoder,
-        immunity,
-        vitals,
-        _player,
-    } = attack.query;
-
-    if immunity.is_invincible(&global) {
+    let (target, target_pose, _) = attack.query;
+    let event = attack.event;
+
+    let Ok(attacker) = attackers.get_mut(event.from) else {
+        return;
+    };
+
+    if matches!(attacker.vitals, Vitals::Dead { .. }) {
         return;
     }
 
-    let event = attack.event;
+    let tick = global.tick;
+    let rules = config::CONFIG.combat.rules();
 
-    // todo
-    let mut damage_broadcast = play::EntityDamageS2c {
-        entity_id: VarInt(entity_id.index().0 as i32),
-        source_type_id: VarInt::default(),
-        source_cause_id: VarInt::default(),
-        source_direct_id: VarInt::default(),
-        source_pos: None,
-    };
+    let hit = rules.hit(&attacker.combat.attack(
+        tick,
+        attacker.on_ground.0,
+        attacker.inventory.main_hand(),
+        attacker.effects,
+    ));
+
+    attacker.combat.last_reset = tick;
 
-    broadcast
-        .get_round_robin()
-        .append_packet(&damage_broadcast)
-        .unwrap();
+    if rules.hunger {
+        attacker.hunger.exhaust(ATTACK_EXHAUSTION);
+    }
 
-    // local is id 0
-    damage_broadcast.entity_id = VarInt(0);
-    encoder.append(&damage_broadcast, &global).unwrap();
+    let facing = combat::facing(attacker.pose.yaw);
+    let weapon = Some(attacker.inventory.main_hand().item).filter(|&item| item != ItemKind::Air);
 
-    let this = pose.position;
-    let other = event.from_pos;
+    if hit.knockback > 0.0 {
+        // like vanilla, knocking back a target stops sprinting
+        attacker.combat.sprinting = false;
+    }
 
-    let delta_x = other.x - this.x;
-    let delta_z = other.z - this.z;
+    s.send(DamageEntity {
+        target,
+        from: Some(event.from),
+        cause: DamageCause::PlayerAttack,
+        weapon,
+        from_pos: event.from_pos,
+        damage: hit.damage,
+        knockback: facing * hit.knockback,
+        critical: hit.critical,
+    });
 
-    if delta_x.abs() < 0.01 && delta_z.abs() < 0.01 {
-        // todo: implement like vanilla
+    if !hit.sweep {
         return;
     }
 
-    let dist_xz = delta_x.hypot(delta_z);
-    let multiplier = 0.4;
+    let grow = Vec3::new(SWEEP_HORIZONTAL, SWEEP_VERTICAL, SWEEP_HORIZONTAL);
+    let area = Aabb::new(
+        target_pose.bounding.min - grow,
+        target_pose.bounding.max + grow,
+    );
 
-    reaction.velocity.x /= 2.0;
-    reaction.velocity.y /= 2.0;
-    reaction.velocity.z /= 2.0;
-    reaction.velocity.x -= delta_x / dist_xz * multiplier;
-    reaction.velocity.y += multiplier;
-    reaction.velocity.z -= delta_z / dist_xz * multiplier;
+    let mut swept = Vec::new();
 
-    if reaction.velocity.y > 0.4 {
-        reaction.velocity.y = 0.4;
-    }
+    let mut sweep = |id: EntityId, aabb: Aabb| {
+        if id != target
+            && id != event.from
+            && aabb.dist2(event.from_pos) < SWEEP_REACH * SWEEP_REACH
+        {
+            swept.push(id);
+        }
+
+        true
+    };
+
+    entity_bounding_boxes
+        .query
+        .get_collisions(area, |stored| sweep(stored.id, stored.aabb));
 
-    vitals.hurt(&global, 1.0, immunity);
+    player_bounding_boxes
+        .query
+        .get_collisions(area, |player| sweep(player.id, player.aabb));
+
+    for id in swept {
+        s.send(DamageEntity {
+            target: id,
+            from: Some(event.from),
+            cause: DamageCause::PlayerAttack,
+            weapon,
+            from_pos: event.from_pos,
+            damage: SWEEP_DAMAGE,
+            knockback: facing * SWEEP_KNOCKBACK,
+            critical: false,
+        });
+    }
 }
@@ -1,26 +1,36 @@
 use evenio::{
     entity::EntityId,
-    event::Receiver,
+    event::{Receiver, Sender},
     fetch::{Fetcher, Single},
-    prelude::With,
-    query::Query,
+    query::{Not, Query, With},
 };
 use tracing::instrument;
 
 use crate::{
-    components::{EntityReaction, FullEntityPose, ImmuneStatus, Player, Vitals},
-    events::Gametick,
+    audit::{Actor, Record},
+    components::{
+        damage::{Attacker, DamageCause, DamageTracker},
+        inventory::Inventory,
+        mob_kind::{Behavior, MobKind},
+        status_effects::{EffectKind, StatusEffects},
+        Dying, EntityReaction, FullEntityPose, Hurt, ImmuneStatus, Player, Uuid, Vitals,
+    },
+    events::{Gametick, PlayerKilled},
     global::Global,
-    singleton::bounding_box::EntityBoundingBoxes,
+    singleton::{audit_log::AuditLog, bounding_box::EntityBoundingBoxes, stats::MatchStats},
 };
 
 #[derive(Query)]
 pub struct PlayerDetectMobHitsQuery<'a> {
     id: EntityId,
+    uuid: &'a Uuid,
     pose: &'a FullEntityPose,
     reaction: &'a mut EntityReaction,
     vitals: &'a mut Vitals,
     immunity: &'a mut ImmuneStatus,
+    inventory: &'a Inventory,
+    effects: &'a StatusEffects,
+    tracker: &'a mut DamageTracker,
     _player: With<&'static Player>,
 }
 
@@ -30,17 +40,29 @@ pub fn player_detect_mob_hits(
     entity_bounding_boxes: Single<&EntityBoundingBoxes>,
     global: Single<&Global>,
     mut poses_fetcher: Fetcher<PlayerDetectMobHitsQuery>,
+    kinds: Fetcher<(&MobKind, Not<&Dying>)>,
+    audit: Single<&AuditLog>,
+    mut stats: Single<&mut MatchStats>,
+    mut s: Sender<PlayerKilled>,
 ) {
     poses_fetcher.iter_mut().for_each(|query| {
         let PlayerDetectMobHitsQuery {
             id,
+            uuid,
             pose,
             reaction,
             vitals,
             immunity,
+            inventory,
+            effects,
+            tracker,
             _player,
         } = query;
 
+        let defense = inventory
+            .defense()
+            .with_resistance(effects.level(EffectKind::Resistance));
+
         // todo: remove mid just use loc directly
         let this = pose.bounding.mid();
 
@@ -52,6 +74,15 @@ pub fn player_detect_mob_hits(
                     return true;
                 }
 
+                // only living melee mobs hurt players on contact
+                let Ok((&kind, _)) = kinds.get(collision.id) else {
+                    return true;
+                };
+
+                if kind.behavior() != Behavior::Melee {
+                    return true;
+                }
+
                 let other = collision.aabb.mid();
 
                 let delta_x = other.x - this.x;
@@ -76,7 +107,35 @@ pub fn player_detect_mob_hits(
                     reaction.velocity.y = 0.4;
                 }
 
-                vitals.hurt(&global, 1.0, immunity);
+                let hit_points = vitals.hit_points();
+                let hurt = vitals.hurt(&global, kind.attack_damage(), &defense, immunity);
+
+                if hurt == Hurt::Immune {
+                    return true;
+                }
+
+                stats.damage(None, Some(uuid.0), hit_points - vitals.hit_points());
+
+                audit.record(Record::Hit {
+                    tick: global.tick,
+                    attacker: Actor::new(None, Some(kind)),
+                    victim: Actor::Player(uuid.0),
+                    damage: kind.attack_damage(),
+                    attacker_pos: other.to_array(),
+                    victim_pos: pose.position.to_array(),
+                });
+
+                let attacker = Attacker {
+                    id: collision.id,
+                    weapon: None,
+                    tick: global.tick,
+                };
+
+                let cause = DamageCause::MobAttack;
+
+                if let Some(killed) = tracker.hurt(id, vitals, cause, Some(attacker), global.tick) {
+                    s.send(killed);
+                }
 
                 true
             });
@@ -1,21 +1,23 @@
-use std::{borrow::Cow, collections::BTreeSet, io::Write};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
 
-use anyhow::Context;
 use chunk::{
     bit_width,
     chunk::{BiomeContainer, BlockStateContainer, SECTION_BLOCK_COUNT},
 };
 use evenio::prelude::*;
 use itertools::Itertools;
-use tracing::{debug, info, instrument};
+use tracing::{info, instrument};
 use valence_protocol::{
     game_mode::OptGameMode,
-    ident,
-    nbt::{compound, Compound, List},
+    nbt::{compound, List},
     packets::{
         play,
         play::{
-            entity_equipment_update_s2c::EquipmentEntry,
+            command_tree_s2c::{CommandTreeS2c, Node, NodeData, Parser, StringArg},
             player_list_s2c::PlayerListActions,
             player_position_look_s2c::PlayerPositionLookFlags,
             team_s2c::{CollisionRule, Mode, NameTagVisibility, TeamColor, TeamFlags},
@@ -23,24 +25,39 @@ use valence_protocol::{
         },
     },
     text::IntoText,
-    BlockPos, BlockState, ByteAngle, ChunkPos, Encode, FixedArray, GameMode, Ident, ItemKind,
-    ItemStack, PacketEncoder, VarInt,
+    BlockPos, BlockState, ByteAngle, ChunkPos, Encode, FixedArray, GameMode, Ident, PacketEncoder,
+    RawBytes, VarInt,
 };
-use valence_registry::{biome::BiomeId, BiomeRegistry, RegistryCodec, RegistryIdx};
+use valence_registry::RegistryIdx;
 
 use crate::{
+    audit::Record,
     bits::BitStorage,
     chunk::heightmap,
-    components::{FullEntityPose, InGameName, MinecraftEntity, Player, Uuid},
+    components::{
+        inventory::Inventory, mob_kind::MobKind, projectile::Projectile, DroppedItem,
+        FullEntityPose, InGameName, MinecraftEntity, Player, Teleport, Uuid,
+    },
     config,
     events::PlayerJoinWorld,
     global::Global,
     net::LocalEncoder,
     singleton::{
-        broadcast::BroadcastBuf, player_id_lookup::PlayerIdLookup,
-        player_uuid_lookup::PlayerUuidLookup,
+        audit_log::AuditLog,
+        blocks::Blocks,
+        border::WorldBorder,
+        broadcast::BroadcastBuf,
+        phase::Phase,
+        player_id_lookup::PlayerIdLookup,
+        registries::Registries,
+        scoreboard::{self, PlayerScore, Scoreboard, DEATHS_LINE, KILLS_LINE, SIDEBAR},
+        teams::{Team, Teams, NO_TEAM},
+    },
+    system::{
+        init_entity::spawn_packet,
+        mob_death::{item_metadata, item_spawn_packet},
+        shoot_projectile::projectile_spawn_packet,
     },
-    system::init_entity::spawn_packet,
 };
 
 #[derive(Query, Debug)]
@@ -48,6 +65,7 @@ pub(crate) struct EntityQuery<'a> {
     id: EntityId,
     uuid: &'a Uuid,
     pose: &'a FullEntityPose,
+    kind: &'a MobKind,
     _player: With<&'static MinecraftEntity>,
 }
 
@@ -58,6 +76,10 @@ pub(crate) struct PlayerJoinWorldQuery<'a> {
     pose: &'a FullEntityPose,
     encoder: &'a mut LocalEncoder,
     name: &'a InGameName,
+    inventory: &'a Inventory,
+    teleport: &'a mut Teleport,
+    team: &'a Team,
+    score: &'a PlayerScore,
     _player: With<&'static Player>,
 }
 
@@ -67,6 +89,8 @@ pub(crate) struct PlayerQuery<'a> {
     uuid: &'a Uuid,
     pose: &'a FullEntityPose,
     name: &'a InGameName,
+    inventory: &'a Inventory,
+    team: &'a Team,
     _player: With<&'static Player>,
 }
 
@@ -76,10 +100,17 @@ pub fn player_join_world(
     r: Receiver<PlayerJoinWorld, PlayerJoinWorldQuery>,
     entities: Fetcher<EntityQuery>,
     global: Single<&Global>,
+    registries: Single<&Registries>,
+    blocks: Single<&Blocks>,
+    teams: Single<&Teams>,
+    scoreboard: Single<&Scoreboard>,
+    border: Single<&WorldBorder>,
+    items: Fetcher<(EntityId, &DroppedItem)>,
+    projectiles: Fetcher<(EntityId, &Projectile)>,
     players: Fetcher<PlayerQuery>,
-    mut uuid_lookup: Single<&mut PlayerUuidLookup>,
     mut id_lookup: Single<&mut PlayerIdLookup>,
     mut broadcast: Single<&mut BroadcastBuf>,
+    audit: Single<&AuditLog>,
 ) {
     static CACHED_DATA: once_cell::sync::OnceCell<bytes::Bytes> = once_cell::sync::OnceCell::new();
 
@@ -90,7 +121,7 @@ pub fn player_join_world(
         encoder.set_compression(compression_level);
 
         info!("Caching world data for new players");
-        inner(&mut encoder).unwrap();
+        inner(&mut encoder, &registries, &blocks, &teams).unwrap();
 
         let bytes = encoder.take();
         bytes.freeze()
@@ -102,42 +133,15 @@ pub fn player_join_world(
 
     let query = r.query;
 
-    uuid_lookup.insert(query.uuid.0, query.id);
     id_lookup.inner.insert(query.id.index().0 as i32, query.id);
 
-    let boots = ItemStack::new(ItemKind::NetheriteBoots, 1, None);
-    let leggings = ItemStack::new(ItemKind::NetheriteLeggings, 1, None);
-    let chestplate = ItemStack::new(ItemKind::NetheriteChestplate, 1, None);
-    let helmet = ItemStack::new(ItemKind::NetheriteHelmet, 1, None);
-    let sword = ItemStack::new(ItemKind::NetheriteSword, 1, None);
-
-    // 0: Mainhand
-    // 2: Boots
-    // 3: Leggings
-    // 4: Chestplate
-    // 5: Helmet
-    let mainhand = EquipmentEntry {
-        slot: 0,
-        item: sword,
-    };
-    let boots = EquipmentEntry {
-        slot: 2,
-        item: boots,
-    };
-    let leggings = EquipmentEntry {
-        slot: 3,
-        item: leggings,
-    };
-    let chestplate = EquipmentEntry {
-        slot: 4,
-        item: chestplate,
-    };
-    let helmet = EquipmentEntry {
-        slot: 5,
-        item: helmet,
-    };
+    audit.record(Record::Join {
+        tick: global.tick,
+        player: query.uuid.0,
+        name: query.name.to_string(),
+    });
 
-    let equipment = vec![mainhand, boots, leggings, chestplate, helmet];
+    let equipment = query.inventory.equipment();
 
     let entries = &[play::player_list_s2c::PlayerListEntry {
         player_uuid: query.uuid.0,
@@ -165,14 +169,11 @@ pub fn player_join_world(
 
     info!("appending cached data");
 
+    encoder.append(&query.inventory.packet(), &global).unwrap();
+
+    // the border changes during the battle, so it is not cached
     encoder
-        .append(
-            &crate::packets::def::EntityEquipmentUpdateS2c {
-                entity_id: VarInt(0),
-                equipment: Cow::Borrowed(&equipment),
-            },
-            &global,
-        )
+        .append(&border.initialize_packet(global.tick), &global)
         .unwrap();
 
     let actions = PlayerListActions::default()
@@ -188,10 +189,33 @@ pub fn player_join_world(
     broadcast.append_packet(&info).unwrap();
 
     for entity in entities {
-        let pkt = spawn_packet(entity.id, *entity.uuid, entity.pose);
+        let pkt = spawn_packet(entity.id, *entity.uuid, entity.pose, *entity.kind);
         encoder.append(&pkt, &global).unwrap();
     }
 
+    for (id, item) in items {
+        let metadata = item_metadata(item);
+
+        encoder
+            .append(&item_spawn_packet(id, item), &global)
+            .unwrap();
+        encoder
+            .append(
+                &play::EntityTrackerUpdateS2c {
+                    entity_id: VarInt(id.index().0 as i32),
+                    tracked_values: RawBytes(&metadata),
+                },
+                &global,
+            )
+            .unwrap();
+    }
+
+    for (id, projectile) in projectiles {
+        encoder
+            .append(&projectile_spawn_packet(id, projectile), &global)
+            .unwrap();
+    }
+
     // todo: cache
     let entries = players
         .iter()
@@ -207,28 +231,51 @@ pub fn player_join_world(
         })
         .collect::<Vec<_>>();
 
-    let player_names: Vec<_> = players
-        .iter()
-        .map(|query| &***query.name) // todo: lol
-        .collect();
+    let mut members: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
 
-    encoder
-        .append(
-            &play::TeamS2c {
-                team_name: "no_tag",
-                mode: Mode::AddEntities {
-                    entities: player_names,
+    for player in &players {
+        members
+            .entry(teams.packet_name(*player.team))
+            .or_default()
+            .push(&***player.name); // todo: lol
+    }
+
+    for (team_name, entities) in members {
+        encoder
+            .append(
+                &play::TeamS2c {
+                    team_name,
+                    mode: Mode::AddEntities { entities },
                 },
-            },
-            &global,
-        )
-        .unwrap();
+                &global,
+            )
+            .unwrap();
+    }
+
+    let objectives = [&scoreboard.sidebar, &scoreboard.health];
+
+    for objective in objectives {
+        for (holder, score) in objective.iter() {
+            let pkt = scoreboard::score_packet(objective.name(), holder, Some(score));
+            encoder.append(&pkt, &global).unwrap();
+        }
+    }
+
+    let lines = [
+        (KILLS_LINE, query.score.kills),
+        (DEATHS_LINE, query.score.deaths),
+    ];
+
+    for (line, score) in lines {
+        let pkt = scoreboard::score_packet(SIDEBAR, line, Some(score));
+        encoder.append(&pkt, &global).unwrap();
+    }
 
     let current_name = query.name;
 
     broadcast
         .append_packet(&play::TeamS2c {
-            team_name: "no_tag",
+            team_name: teams.packet_name(*query.team),
             mode: Mode::AddEntities {
                 entities: vec![current_name],
             },
@@ -278,7 +325,7 @@ pub fn player_join_world(
 
         let pkt = crate::packets::def::EntityEquipmentUpdateS2c {
             entity_id,
-            equipment: Cow::Borrowed(&equipment),
+            equipment: Cow::Owned(current_query.inventory.equipment()),
         };
         encoder.append(&pkt, &global).unwrap();
     }
@@ -304,7 +351,7 @@ pub fn player_join_world(
                 yaw: query.pose.yaw,
                 pitch: query.pose.pitch,
                 flags: PlayerPositionLookFlags::default(),
-                teleport_id: 1.into(),
+                teleport_id: query.teleport.start().into(),
             },
             &global,
         )
@@ -312,6 +359,13 @@ pub fn player_join_world(
 
     broadcast.append_packet(&spawn_player).unwrap();
 
+    broadcast
+        .append_packet(&crate::packets::def::EntityEquipmentUpdateS2c {
+            entity_id: current_entity_id,
+            equipment: Cow::Borrowed(&equipment),
+        })
+        .unwrap();
+
     info!("Player {} joined the world", query.name);
 }
 
@@ -326,40 +380,21 @@ fn write_block_states(states: &BlockStateContainer, writer: &mut impl Write) ->
     Ok(())
 }
 
-fn write_biomes(biomes: &BiomeContainer, writer: &mut impl Write) -> anyhow::Result<()> {
+fn write_biomes(
+    biomes: &BiomeContainer,
+    registries: &Registries,
+    writer: &mut impl Write,
+) -> anyhow::Result<()> {
     biomes.encode_mc_format(
         writer,
         |b| b.to_index() as u64,
         0,
         3,
-        6, // bit_width(info.biome_registry_len - 1),
+        registries.biome_bits(),
     )?;
     Ok(())
 }
 
-trait Array3d {
-    type Item;
-    #[expect(dead_code, reason = "unused")]
-    fn get3(&self, x: usize, y: usize, z: usize) -> &Self::Item;
-    fn get3_mut(&mut self, x: usize, y: usize, z: usize) -> &mut Self::Item;
-}
-
-#[expect(
-    clippy::indexing_slicing,
-    reason = "the signature of the trait allows for panics"
-)]
-impl<T, const N: usize> Array3d for [T; N] {
-    type Item = T;
-
-    fn get3(&self, x: usize, y: usize, z: usize) -> &Self::Item {
-        &self[x + z * 16 + y * 16 * 16]
-    }
-
-    fn get3_mut(&mut self, x: usize, y: usize, z: usize) -> &mut Self::Item {
-        &mut self[x + z * 16 + y * 16 * 16]
-    }
-}
-
 pub fn send_keep_alive(encoder: &mut LocalEncoder, global: &Global) -> anyhow::Result<()> {
     let pkt = play::KeepAliveS2c {
         // The ID can be set to zero because it doesn't matter
@@ -371,68 +406,34 @@ pub fn send_keep_alive(encoder: &mut LocalEncoder, global: &Global) -> anyhow::R
     Ok(())
 }
 
-fn registry_codec_raw(codec: &RegistryCodec) -> anyhow::Result<Compound> {
-    // codec.cached_codec.clear();
-
-    let mut compound = Compound::default();
-
-    for (reg_name, reg) in &codec.registries {
-        let mut value = vec![];
-
-        for (id, v) in reg.iter().enumerate() {
-            let id = i32::try_from(id).context("id too large")?;
-            value.push(compound! {
-                "id" => id,
-                "name" => v.name.as_str(),
-                "element" => v.element.clone(),
-            });
-        }
-
-        let registry = compound! {
-            "type" => reg_name.as_str(),
-            "value" => List::Compound(value),
-        };
-
-        compound.insert(reg_name.as_str(), registry);
-    }
-
-    Ok(compound)
-}
-
-pub fn send_game_join_packet(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
+pub fn send_game_join_packet(
+    encoder: &mut PacketEncoder,
+    registries: &Registries,
+) -> anyhow::Result<()> {
     // recv ack
 
-    let codec = RegistryCodec::default();
-
-    let registry_codec = registry_codec_raw(&codec)?;
-
-    let dimension_names: BTreeSet<Ident<Cow<str>>> = codec
-        .registry(BiomeRegistry::KEY)
-        .iter()
-        .map(|value| value.name.as_str_ident().into())
-        .collect();
-
-    let dimension_name = ident!("overworld");
-    // let dimension_name: Ident<Cow<str>> = chunk_layer.dimension_type_name().into();
+    let dimension_name: Ident<Cow<str>> = registries.dimension.name.as_str().try_into()?;
+    let dimension_names = BTreeSet::from([dimension_name.clone()]);
 
     let pkt = GameJoinS2c {
         entity_id: 0,
         is_hardcore: false,
         dimension_names: Cow::Owned(dimension_names),
-        registry_codec: Cow::Borrowed(&registry_codec),
+        registry_codec: Cow::Borrowed(registries.codec()),
         max_players: config::CONFIG.max_players.into(),
         view_distance: config::CONFIG.view_distance.into(), // max view distance
         simulation_distance: config::CONFIG.simulation_distance.into(),
         reduced_debug_info: false,
-        enable_respawn_screen: false,
-        dimension_name: dimension_name.into(),
+        // the death screen shows the death message until the player respawns
+        enable_respawn_screen: true,
+        dimension_name: dimension_name.clone(),
         hashed_seed: 0,
         game_mode: GameMode::Survival,
         is_flat: false,
         last_death_location: None,
         portal_cooldown: 60.into(),
         previous_game_mode: OptGameMode(Some(GameMode::Survival)),
-        dimension_type_name: "minecraft:overworld".try_into()?,
+        dimension_type_name: dimension_name,
         is_debug: false,
     };
 
@@ -441,163 +442,153 @@ pub fn send_game_join_packet(encoder: &mut PacketEncoder) -> anyhow::Result<()>
     Ok(())
 }
 
-fn send_commands(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
-    // https://wiki.vg/Command_Data
-    use valence_protocol::packets::play::command_tree_s2c::{
-        CommandTreeS2c, Node, NodeData, Parser,
-    };
+/// The packet which respawns a dead player in the same dimension. It closes the death screen.
+pub fn respawn_packet(registries: &Registries) -> anyhow::Result<play::PlayerRespawnS2c<'_>> {
+    let dimension_name: Ident<Cow<str>> = registries.dimension.name.as_str().try_into()?;
 
-    // id 0
-    let root = Node {
-        data: NodeData::Root,
-        executable: false,
-        children: vec![VarInt(1), VarInt(3)],
-        redirect_node: None,
-    };
+    Ok(play::PlayerRespawnS2c {
+        dimension_type_name: dimension_name.clone(),
+        dimension_name,
+        hashed_seed: 0,
+        game_mode: GameMode::Survival,
+        previous_game_mode: OptGameMode(Some(GameMode::Spectator)),
+        is_debug: false,
+        is_flat: false,
+        copy_metadata: false,
+        last_death_location: None,
+        portal_cooldown: 60.into(),
+    })
+}
 
-    // id 1
-    let spawn = Node {
-        data: NodeData::Literal {
-            name: "spawn".to_owned(),
-        },
-        executable: true,
-        children: vec![VarInt(2)],
-        redirect_node: None,
-    };
+/// The nodes of the command graph, see <https://wiki.vg/Command_Data>. Nodes refer to their
+/// children by index, so nodes are only added through [`CommandGraph::add`], which returns it.
+struct CommandGraph {
+    /// The nodes by their index. The root has the index 0.
+    nodes: Vec<Node>,
+}
 
-    // id 2
-    let spawn_arg = Node {
-        data: NodeData::Argument {
-            name: "position".to_owned(),
-            parser: Parser::BlockPos,
-            suggestion: None,
-        },
-        executable: true,
-        children: vec![],
-        redirect_node: None,
-    };
+impl CommandGraph {
+    /// The index of the root node.
+    const ROOT: usize = 0;
 
-    // id 3 = "killall"
-    let clear = Node {
-        data: NodeData::Literal {
-            name: "ka".to_owned(),
-        },
-        executable: true,
-        children: vec![],
-        redirect_node: None,
-    };
+    /// A graph with only the root node.
+    fn new() -> Self {
+        Self {
+            nodes: vec![Self::node(NodeData::Root, false)],
+        }
+    }
 
-    // id 4 = "ka" replace with "killall"
-    // let ka = Node {
-    //     data: NodeData::Literal {
-    //         name: "ka".to_owned(),
-    //     },
-    //     executable: false,
-    //     children: vec![],
-    //     redirect_node: Some(VarInt(3)),
-    // };
+    /// Adds a node as a child of `parent` and returns its index.
+    fn add(&mut self, parent: usize, node: Node) -> usize {
+        let idx = self.nodes.len();
+        self.nodes[parent].children.push(VarInt(idx as i32));
+        self.nodes.push(node);
+        idx
+    }
 
-    encoder.append_packet(&CommandTreeS2c {
-        commands: vec![root, spawn, spawn_arg, clear],
-        root_index: VarInt(0),
-    })?;
+    /// Adds a literal, i.e., the name of a command, as a child of `parent`.
+    fn literal(&mut self, parent: usize, name: &str, executable: bool) -> usize {
+        let data = NodeData::Literal {
+            name: name.to_owned(),
+        };
 
-    Ok(())
+        self.add(parent, Self::node(data, executable))
+    }
+
+    /// Adds an argument which completes the command as a child of `parent`.
+    fn argument(&mut self, parent: usize, name: &str, parser: Parser) -> usize {
+        let data = NodeData::Argument {
+            name: name.to_owned(),
+            parser,
+            suggestion: None,
+        };
+
+        self.add(parent, Self::node(data, true))
+    }
+
+    /// A node without children.
+    const fn node(data: NodeData, executable: bool) -> Node {
+        Node {
+            data,
+            executable,
+            children: Vec::new(),
+            redirect_node: None,
+        }
+    }
 }
 
-fn air_section() -> Vec<u8> {
-    let mut section_bytes = Vec::new();
-    0_u16.encode(&mut section_bytes).unwrap();
+fn send_commands(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
+    let mut graph = CommandGraph::new();
+    let root = CommandGraph::ROOT;
+
+    // "spawn [position]" and "spawn <type> [count]"
+    let spawn = graph.literal(root, "spawn", true);
+    graph.argument(spawn, "position", Parser::BlockPos);
+
+    for kind in MobKind::ALL {
+        let kind = graph.literal(spawn, kind.name(), true);
+        let count = Parser::Integer {
+            min: Some(1),
+            max: None,
+        };
+        graph.argument(kind, "count", count);
+    }
 
-    let block_states = BlockStateContainer::Single(BlockState::AIR);
-    write_block_states(&block_states, &mut section_bytes).unwrap();
+    // todo: replace "ka" with "killall"
+    graph.literal(root, "ka", true);
 
-    let biomes = BiomeContainer::Single(BiomeId::DEFAULT);
-    write_biomes(&biomes, &mut section_bytes).unwrap();
+    // "kit give <kit> [player]" and "kit reset [player]"
+    let kit = graph.literal(root, "kit", false);
+    let give = graph.literal(kit, "give", false);
+    let name = graph.argument(give, "kit", Parser::String(StringArg::SingleWord));
+    graph.argument(name, "player", Parser::String(StringArg::SingleWord));
+    let reset = graph.literal(kit, "reset", true);
+    graph.argument(reset, "player", Parser::String(StringArg::SingleWord));
 
-    section_bytes
-}
+    // "phase <phase>"
+    let phase = graph.literal(root, "phase", false);
 
-fn stone_section() -> Vec<u8> {
-    let mut section_bytes = Vec::new();
-    SECTION_BLOCK_COUNT.encode(&mut section_bytes).unwrap();
+    for name in Phase::ALL.map(Phase::name) {
+        graph.literal(phase, name, true);
+    }
 
-    let blocks = [BlockState::STONE; { SECTION_BLOCK_COUNT as usize }];
-    let block_states = BlockStateContainer::Direct(Box::new(blocks));
-    write_block_states(&block_states, &mut section_bytes).unwrap();
+    graph.literal(root, "debug", true);
 
-    let biomes = BiomeContainer::Single(BiomeId::DEFAULT);
-    write_biomes(&biomes, &mut section_bytes).unwrap();
+    encoder.append_packet(&CommandTreeS2c {
+        commands: graph.nodes,
+        root_index: VarInt(CommandGraph::ROOT as i32),
+    })?;
 
-    section_bytes
+    Ok(())
 }
 
-fn ground_section() -> Vec<u8> {
-    let mut section_bytes = Vec::new();
-
-    let number_blocks: u16 = 16 * 16;
-    number_blocks.encode(&mut section_bytes).unwrap();
-
-    let mut blocks = [BlockState::AIR; { SECTION_BLOCK_COUNT as usize }];
-
-    let surface_blocks = [
-        BlockState::END_STONE,
-        BlockState::SAND,
-        BlockState::GRAVEL,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-        BlockState::END_STONE,
-    ];
-
-    let mut rnd = rand::thread_rng();
-
-    for x in 0..16 {
-        for z in 0..16 {
-            // let dist_from_center = (x as f64 - 8.0).hypot(z as f64 - 8.0);
-
-            // based on x and z
-            // should be highest at center of chunk
-            // let height = (16.0 - dist_from_center) * 0.5 + 3.0;
-            let height = 5;
-            let height = height.min(16);
-            for y in 0..height {
-                use rand::seq::SliceRandom;
-                let block = surface_blocks.choose(&mut rnd).unwrap();
-                *blocks.get3_mut(x, y, z) = *block;
-            }
-        }
-    }
+/// Encodes a chunk section in the format of the chunk data packet.
+fn section_bytes(
+    block_states: &BlockStateContainer,
+    registries: &Registries,
+    writer: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    let non_air = (0..usize::from(SECTION_BLOCK_COUNT))
+        .filter(|&idx| !block_states.get(idx).is_air())
+        .count();
 
-    let block_states = BlockStateContainer::Direct(Box::new(blocks));
+    u16::try_from(non_air)?.encode(&mut *writer)?;
 
-    write_block_states(&block_states, &mut section_bytes).unwrap();
+    write_block_states(block_states, writer)?;
 
-    let biomes = BiomeContainer::Single(BiomeId::DEFAULT);
-    write_biomes(&biomes, &mut section_bytes).unwrap();
+    let biomes = BiomeContainer::Single(registries.biome);
+    write_biomes(&biomes, registries, writer)?;
 
-    section_bytes
+    Ok(())
 }
 
-fn inner(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
-    send_game_join_packet(encoder)?;
+fn inner(
+    encoder: &mut PacketEncoder,
+    registries: &Registries,
+    blocks: &Blocks,
+    teams: &Teams,
+) -> anyhow::Result<()> {
+    send_game_join_packet(encoder, registries)?;
 
     // TODO: Do we need to send this else where?
     encoder.append_packet(&play::ChunkRenderDistanceCenterS2c {
@@ -605,28 +596,19 @@ fn inner(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
         chunk_z: 0.into(),
     })?;
 
-    let section_count = 384 / 16_usize;
-    let air_section = air_section();
-    let ground_section = ground_section();
-    let stone_section = stone_section();
+    let section_count = registries.dimension.section_count();
 
     let mut bytes = Vec::new();
 
-    bytes.extend_from_slice(&stone_section);
-    bytes.extend_from_slice(&stone_section);
-    bytes.extend_from_slice(&stone_section);
-    bytes.extend_from_slice(&stone_section);
-    bytes.extend_from_slice(&ground_section);
+    for section in blocks.sections() {
+        section_bytes(section, registries, &mut bytes)?;
+    }
 
     // 2048 bytes per section -> long count = 2048 / 8 = 256
     let sky_light_array = FixedArray([0xFF_u8; 2048]);
     let sky_light_arrays = vec![sky_light_array; section_count + 2];
 
-    for _ in (0..section_count).skip(5) {
-        bytes.extend_from_slice(&air_section);
-    }
-
-    let dimension_height = 384;
+    let dimension_height = registries.dimension.height;
 
     let map = heightmap(dimension_height, dimension_height - 3);
     let map: Vec<_> = map.into_iter().map(i64::try_from).try_collect()?;
@@ -654,8 +636,10 @@ fn inner(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
         block_light_arrays: Cow::Borrowed(&[]),
     };
 
-    for x in -16..=16 {
-        for z in -16..=16 {
+    let radius = blocks.radius();
+
+    for x in -radius..=radius {
+        for z in -radius..=radius {
             pkt.pos = ChunkPos::new(x, z);
             encoder.append_packet(&pkt)?;
         }
@@ -669,7 +653,7 @@ fn inner(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
     })?;
 
     encoder.append_packet(&play::TeamS2c {
-        team_name: "no_tag",
+        team_name: NO_TEAM,
         mode: Mode::CreateTeam {
             team_display_name: Cow::default(),
             friendly_flags: TeamFlags::default(),
@@ -682,26 +666,29 @@ fn inner(encoder: &mut PacketEncoder) -> anyhow::Result<()> {
         },
     })?;
 
-    if let Some(diameter) = config::CONFIG.border_diameter {
-        debug!("Setting world border to diameter {}", diameter);
-
-        encoder.append_packet(&play::WorldBorderInitializeS2c {
-            x: 0.0,
-            z: 0.0,
-            old_diameter: diameter,
-            new_diameter: diameter,
-            duration_millis: 1.into(),
-            portal_teleport_boundary: 29_999_984.into(),
-            warning_blocks: 50.into(),
-            warning_time: 200.into(),
+    let friendly_flags = TeamFlags::new()
+        .with_friendly_fire(teams.friendly_fire())
+        .with_see_invisible_teammates(true);
+
+    for team in teams.iter() {
+        encoder.append_packet(&play::TeamS2c {
+            team_name: &team.name,
+            mode: Mode::CreateTeam {
+                team_display_name: team.name.as_str().into_cow_text(),
+                friendly_flags,
+                name_tag_visibility: NameTagVisibility::Always,
+                collision_rule: CollisionRule::Always,
+                team_color: team.color,
+                team_prefix: team.prefix.as_str().into_cow_text(),
+                team_suffix: Cow::default(),
+                entities: vec![],
+            },
         })?;
+    }
 
-        encoder.append_packet(&play::WorldBorderSizeChangedS2c { diameter })?;
-
-        encoder.append_packet(&play::WorldBorderCenterChangedS2c {
-            x_pos: 0.0,
-            z_pos: 0.0,
-        })?;
+    for (objective, display) in scoreboard::objective_packets() {
+        encoder.append_packet(&objective)?;
+        encoder.append_packet(&display)?;
     }
 
     Ok(())
@@ -1,5 +1,3 @@
-use std::sync::atomic::Ordering;
-
 use evenio::prelude::*;
 use tracing::instrument;
 use valence_protocol::{
@@ -7,27 +5,17 @@ use valence_protocol::{
     text::{Color, IntoText},
 };
 
-use crate::{
-    components::Uuid,
-    events::KickPlayer,
-    global::Global,
-    net::LocalEncoder,
-    singleton::{player_id_lookup::PlayerIdLookup, player_uuid_lookup::PlayerUuidLookup},
-};
+use crate::{events::KickPlayer, global::Global, net::LocalEncoder};
 
+/// Disconnects a player with the reason of the kick. What is left of them is cleaned up by
+/// `system::player_leave` once they are despawned.
 #[instrument(skip_all)]
 pub fn player_kick(
-    r: Receiver<KickPlayer, (EntityId, &Uuid, &mut LocalEncoder)>,
+    r: Receiver<KickPlayer, (EntityId, &mut LocalEncoder)>,
     global: Single<&Global>,
-    mut uuid_lookup: Single<&mut PlayerUuidLookup>,
-    mut id_lookup: Single<&mut PlayerIdLookup>,
     mut s: Sender<Despawn>,
 ) {
-    let (id, uuid, encoder) = r.query;
-
-    uuid_lookup.remove(&uuid.0);
-    // todo: also remove on socket close
-    id_lookup.inner.remove(&(id.index().0 as i32));
+    let (id, encoder) = r.query;
 
     let reason = &r.event.reason;
 
@@ -42,9 +30,5 @@ pub fn player_kick(
         )
         .unwrap();
 
-    // todo: also handle disconnecting without kicking, io socket being closed, etc
-
-    global.0.shared.player_count.fetch_sub(1, Ordering::Relaxed);
-
     s.send(Despawn(id));
 }
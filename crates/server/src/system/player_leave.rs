@@ -0,0 +1,41 @@
+use std::sync::atomic::Ordering;
+
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    audit::Record,
+    components::{InGameName, Player, Uuid},
+    global::Global,
+    singleton::{
+        audit_log::AuditLog, player_id_lookup::PlayerIdLookup,
+        player_uuid_lookup::PlayerUuidLookup, scoreboard::Scoreboard,
+    },
+};
+
+/// Forgets a player who is despawned, either because they disconnected or because they were
+/// kicked.
+#[instrument(skip_all)]
+pub fn player_leave(
+    r: Receiver<Despawn, (EntityId, &InGameName, &Uuid, With<&Player>)>,
+    global: Single<&Global>,
+    mut uuid_lookup: Single<&mut PlayerUuidLookup>,
+    mut id_lookup: Single<&mut PlayerIdLookup>,
+    mut scoreboard: Single<&mut Scoreboard>,
+    audit: Single<&AuditLog>,
+) {
+    let (id, name, uuid, _) = r.query;
+
+    uuid_lookup.remove(&uuid.0);
+    id_lookup.inner.remove(&(id.index().0 as i32));
+
+    // players who left have no health
+    scoreboard.health.remove(name);
+
+    global.0.shared.player_count.fetch_sub(1, Ordering::Relaxed);
+
+    audit.record(Record::Leave {
+        tick: global.tick,
+        player: uuid.0,
+    });
+}
@@ -0,0 +1,330 @@
+use bvh::{aabb::Aabb, Bvh};
+use evenio::prelude::*;
+use fxhash::FxHashSet;
+use glam::{Vec2, Vec3};
+use rand::Rng;
+use rayon::prelude::*;
+use tracing::instrument;
+use valence_protocol::{
+    packets::{play, play::player_position_look_s2c::PlayerPositionLookFlags},
+    ByteAngle, VarInt, Velocity,
+};
+
+use crate::{
+    components::{
+        damage::DamageCause,
+        projectile::{Projectile, ProjectileKind, HIT_MARGIN, PEARL_DAMAGE},
+        Dying, FullEntityPose, Teleport, Vitals,
+    },
+    events::{DamageEntity, Gametick},
+    global::Global,
+    net::LocalEncoder,
+    physics,
+    singleton::{
+        blocks::Blocks,
+        bounding_box::{EntityBoundingBoxes, Stored},
+        broadcast::BroadcastBuf,
+    },
+};
+
+/// The entity status which shows the particles of a thrown item breaking.
+const BREAK_STATUS: u8 = 3;
+
+/// What a projectile ran into this tick.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Impact {
+    /// It hit an entity.
+    Entity(EntityId),
+    /// It hit a block.
+    Block,
+    /// It was around for too long.
+    Expired,
+}
+
+/// A projectile which ran into something, to be applied after all projectiles moved.
+#[derive(Copy, Clone, Debug)]
+struct Hit {
+    /// The projectile entity.
+    id: EntityId,
+    /// The projectile as it was before the impact.
+    projectile: Projectile,
+    /// What it ran into.
+    impact: Impact,
+    /// Where it is after the impact.
+    position: Vec3,
+}
+
+/// Moves projectiles and applies their hits, see [`crate::components::projectile`].
+///
+/// Projectiles are moved and checked for hits in parallel. Each one checks the segment it moves
+/// along this tick against the blocks and the bounding boxes of entities, so fast arrows cannot
+/// pass through anything. Hits are applied afterwards one by one.
+#[instrument(skip_all, level = "trace")]
+pub fn projectile_physics(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    blocks: Single<&Blocks>,
+    entity_bounding_boxes: Single<&EntityBoundingBoxes>,
+    mut projectiles: Fetcher<(EntityId, &mut Projectile)>,
+    targets: Fetcher<(EntityId, &Vitals, Option<&Dying>)>,
+    mut owners: Fetcher<(
+        &mut FullEntityPose,
+        &mut Teleport,
+        &mut LocalEncoder,
+        &Vitals,
+    )>,
+    broadcast: Single<&mut BroadcastBuf>,
+    mut s: Sender<(DamageEntity, Despawn)>,
+) {
+    let tick = global.tick;
+    let blocks = &*blocks;
+    let entities = &entity_bounding_boxes.query;
+
+    // most ticks nothing is flying
+    if projectiles.iter_mut().next().is_none() {
+        return;
+    }
+
+    // the bounding boxes still contain dead players and dying mobs, which projectiles fly through
+    let fallen: FxHashSet<EntityId> = targets
+        .iter()
+        .filter(|(_, vitals, dying)| matches!(vitals, Vitals::Dead { .. }) || dying.is_some())
+        .map(|(id, ..)| id)
+        .collect();
+    let fallen = &fallen;
+
+    let hits: Vec<Hit> = projectiles
+        .par_iter_mut()
+        .map_init(Vec::new, |obstacles, (id, projectile)| {
+            step(id, projectile, tick, blocks, entities, fallen, obstacles)
+        })
+        .flatten()
+        .collect();
+
+    if hits.is_empty() {
+        return;
+    }
+
+    let mut broadcast = broadcast.0.get_round_robin();
+    let mut despawned = Vec::new();
+
+    for hit in hits {
+        let Hit {
+            id,
+            projectile,
+            impact,
+            position,
+        } = hit;
+
+        let entity_id = VarInt(id.index().0 as i32);
+
+        match impact {
+            Impact::Expired => {
+                despawned.push(id);
+                continue;
+            }
+            Impact::Block if projectile.kind == ProjectileKind::Arrow => {
+                // stuck arrows stay until they expire
+                let (yaw, pitch) = rotation(projectile.velocity);
+
+                broadcast
+                    .append_packet(&play::EntityPositionS2c {
+                        entity_id,
+                        position: position.as_dvec3(),
+                        yaw,
+                        pitch,
+                        on_ground: false,
+                    })
+                    .unwrap();
+                broadcast
+                    .append_packet(&play::EntityVelocityUpdateS2c {
+                        entity_id,
+                        velocity: Velocity([0; 3]),
+                    })
+                    .unwrap();
+
+                continue;
+            }
+            Impact::Block => {}
+            Impact::Entity(target) => {
+                let mut damage = projectile.damage();
+
+                // like vanilla, fully drawn bows add up to half the damage plus one
+                if projectile.critical {
+                    let bonus = rand::thread_rng().gen_range(0..damage as i32 / 2 + 2);
+                    damage += bonus as f32;
+                }
+
+                s.send(DamageEntity {
+                    target,
+                    from: Some(projectile.owner),
+                    cause: projectile.kind.cause(),
+                    weapon: projectile.weapon,
+                    // knock the target back in the direction the projectile flew
+                    from_pos: position - projectile.velocity,
+                    damage,
+                    knockback: Vec2::ZERO,
+                    critical: projectile.critical,
+                });
+            }
+        }
+
+        if projectile.kind != ProjectileKind::Arrow {
+            broadcast
+                .append_packet(&play::EntityStatusS2c {
+                    entity_id: entity_id.0,
+                    entity_status: BREAK_STATUS,
+                })
+                .unwrap();
+        }
+
+        if projectile.kind == ProjectileKind::EnderPearl {
+            teleport(&mut owners, &projectile, position, &global, &mut s);
+        }
+
+        despawned.push(id);
+    }
+
+    if despawned.is_empty() {
+        return;
+    }
+
+    let entity_ids = despawned
+        .iter()
+        .map(|id| VarInt(id.index().0 as i32))
+        .collect();
+
+    broadcast
+        .append_packet(&play::EntitiesDestroyS2c { entity_ids })
+        .unwrap();
+
+    for id in despawned {
+        s.despawn(id);
+    }
+}
+
+/// Moves a projectile for one tick and returns what it ran into, if anything. Entities in `fallen`
+/// are not hit.
+fn step(
+    id: EntityId,
+    projectile: &mut Projectile,
+    tick: i64,
+    blocks: &Blocks,
+    entities: &Bvh<Stored>,
+    fallen: &FxHashSet<EntityId>,
+    obstacles: &mut Vec<Aabb>,
+) -> Option<Hit> {
+    if tick >= projectile.despawn_tick {
+        return Some(Hit {
+            id,
+            projectile: *projectile,
+            impact: Impact::Expired,
+            position: projectile.position,
+        });
+    }
+
+    if projectile.stuck {
+        return None;
+    }
+
+    let start = projectile.position;
+    let movement = projectile.velocity;
+    let path = physics::swept(Aabb::new(start, start), movement);
+
+    obstacles.clear();
+    blocks.collisions(path, |aabb| obstacles.push(aabb));
+
+    let mut nearest = obstacles
+        .iter()
+        .filter_map(|aabb| aabb.ray_intersection(start, movement, 1.0))
+        .min_by(f32::total_cmp)
+        .map(|fraction| (fraction, Impact::Block));
+
+    // sweeping a box around the projectile is the same as growing the boxes of entities
+    let hitbox = Aabb::new(start, start).expand(HIT_MARGIN);
+
+    entities.sweep_all(hitbox, movement, |stored, fraction| {
+        if projectile.can_hit(stored.id, tick)
+            && !fallen.contains(&stored.id)
+            && !nearest.is_some_and(|(nearest, _)| nearest <= fraction)
+        {
+            nearest = Some((fraction, Impact::Entity(stored.id)));
+        }
+
+        true
+    });
+
+    let Some((fraction, impact)) = nearest else {
+        projectile.fly();
+        return None;
+    };
+
+    let mut hit = Hit {
+        id,
+        projectile: *projectile,
+        impact,
+        position: start + movement * fraction,
+    };
+
+    if impact == Impact::Block && projectile.kind == ProjectileKind::Arrow {
+        projectile.stick(hit.position, tick);
+        hit.position = projectile.position;
+    }
+
+    Some(hit)
+}
+
+/// Teleports the thrower of an ender pearl to where it landed and hurts them like vanilla.
+fn teleport(
+    owners: &mut Fetcher<(
+        &mut FullEntityPose,
+        &mut Teleport,
+        &mut LocalEncoder,
+        &Vitals,
+    )>,
+    pearl: &Projectile,
+    position: Vec3,
+    global: &Global,
+    s: &mut Sender<(DamageEntity, Despawn)>,
+) {
+    let Ok((pose, teleport, encoder, vitals)) = owners.get_mut(pearl.owner) else {
+        return;
+    };
+
+    if matches!(vitals, Vitals::Dead { .. }) {
+        return;
+    }
+
+    pose.move_to(position);
+
+    let pkt = play::PlayerPositionLookS2c {
+        position: position.as_dvec3(),
+        yaw: pose.yaw,
+        pitch: pose.pitch,
+        flags: PlayerPositionLookFlags::default(),
+        teleport_id: teleport.start().into(),
+    };
+
+    encoder.append(&pkt, global).unwrap();
+
+    s.send(DamageEntity {
+        target: pearl.owner,
+        from: None,
+        cause: DamageCause::Fall,
+        weapon: None,
+        from_pos: position,
+        damage: PEARL_DAMAGE,
+        knockback: Vec2::ZERO,
+        critical: false,
+    });
+}
+
+/// The rotation of an arrow flying with `velocity`, which points its tip forward.
+fn rotation(velocity: Vec3) -> (ByteAngle, ByteAngle) {
+    let horizontal = velocity.x.hypot(velocity.z);
+
+    let yaw = velocity.x.atan2(velocity.z).to_degrees();
+    let pitch = velocity.y.atan2(horizontal).to_degrees();
+
+    (ByteAngle::from_degrees(yaw), ByteAngle::from_degrees(pitch))
+}
@@ -0,0 +1,113 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use evenio::prelude::*;
+use tracing::{info, instrument, warn};
+use valence_protocol::text::{Color, IntoText, Text};
+
+use crate::{
+    components::{Player, Uuid},
+    config,
+    events::PhaseChanged,
+    global::TICKS_PER_SECOND,
+    singleton::{
+        announcements::{Announcements, Audience},
+        kits::Kits,
+        phase::Phase,
+        stats::{MatchStats, PlayerStats, RoundResults},
+    },
+};
+
+/// The number of players shown on the leaderboard in the chat.
+const LEADERBOARD_SIZE: usize = 5;
+
+/// Starts the statistics from zero when the battle starts and, when it ends, shows the leaderboard
+/// and every player their own statistics and exports them, see [`crate::singleton::stats`].
+#[instrument(skip_all)]
+pub fn round_results(
+    r: Receiver<PhaseChanged>,
+    kits: Single<&Kits>,
+    players: Fetcher<(EntityId, &Uuid, With<&Player>)>,
+    mut stats: Single<&mut MatchStats>,
+    mut announcements: Single<&mut Announcements>,
+) {
+    match r.event.to {
+        Phase::Battle => {
+            stats.reset();
+            return;
+        }
+        Phase::End => {}
+        Phase::Lobby | Phase::Countdown => return,
+    }
+
+    let leaderboard = stats.leaderboard();
+
+    announcements.chat(
+        Audience::Everyone,
+        "Leaderboard".into_text().color(Color::GOLD).bold(),
+    );
+
+    for (rank, player) in leaderboard.iter().take(LEADERBOARD_SIZE).enumerate() {
+        let line = format!("{}. ", rank + 1).into_text().color(Color::GRAY)
+            + player.name.clone().into_text().color(Color::YELLOW)
+            + format!(
+                " {} kills, {} deaths, {:.0} damage",
+                player.kills, player.deaths, player.damage_dealt
+            );
+
+        announcements.chat(Audience::Everyone, line);
+    }
+
+    for (id, uuid, _) in &players {
+        if let Some(player) = stats.get(uuid.0) {
+            announcements.chat(Audience::Player(id), summary(player));
+        }
+    }
+
+    let Some(dir) = &config::CONFIG.results_dir else {
+        return;
+    };
+
+    let results = RoundResults {
+        round: kits.round,
+        time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs()),
+        players: leaderboard,
+    };
+
+    // writing files takes too long for a tick
+    let dir = PathBuf::from(dir);
+    std::thread::spawn(move || match results.export(&dir) {
+        Ok(path) => info!(
+            "exported the results of round {} to {}",
+            results.round,
+            path.display()
+        ),
+        Err(error) => warn!(
+            "failed to export the results of round {}: {error:?}",
+            results.round
+        ),
+    });
+}
+
+/// The statistics of a player as they are shown to them.
+fn summary(player: &PlayerStats) -> Text {
+    let seconds = player.ticks_alive / TICKS_PER_SECOND;
+
+    "Your round: ".into_text().color(Color::GOLD)
+        + format!(
+            "{} kills, {} deaths ({:.2} K/D), {:.0} damage dealt, {:.0} taken, alive for {}:{:02}, \
+             best streak {}",
+            player.kills,
+            player.deaths,
+            player.ratio(),
+            player.damage_dealt,
+            player.damage_taken,
+            seconds / 60,
+            seconds % 60,
+            player.longest_streak,
+        )
+}
@@ -0,0 +1,51 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    components::Uuid,
+    events::PlayerKilled,
+    singleton::{
+        scoreboard::{team_line, PlayerScore, Scoreboard},
+        stats::MatchStats,
+        teams::{Team, Teams},
+    },
+};
+
+/// Counts the death of the victim and the kill of the killer and their team, see
+/// [`crate::singleton::scoreboard`] and [`crate::singleton::stats`].
+#[instrument(skip_all)]
+pub fn score_kill(
+    r: Receiver<PlayerKilled>,
+    teams: Single<&Teams>,
+    mut scoreboard: Single<&mut Scoreboard>,
+    mut stats: Single<&mut MatchStats>,
+    mut players: Fetcher<(&mut PlayerScore, &Team, &Uuid)>,
+) {
+    let event = r.event;
+
+    let Ok((score, _, victim)) = players.get_mut(event.victim) else {
+        return;
+    };
+
+    score.deaths += 1;
+    let victim = victim.0;
+
+    // mobs do not score
+    let killer = event
+        .killer
+        .filter(|&killer| killer != event.victim)
+        .and_then(|killer| players.get_mut(killer).ok());
+
+    let Some((score, team, killer)) = killer else {
+        stats.kill(victim, None);
+        return;
+    };
+
+    stats.kill(victim, Some(killer.0));
+
+    score.kills += 1;
+
+    if let Some(team) = teams.name(*team) {
+        scoreboard.sidebar.add(&team_line(team), 1);
+    }
+}
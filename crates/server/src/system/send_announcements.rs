@@ -0,0 +1,73 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    components::FullEntityPose,
+    events::Gametick,
+    global::Global,
+    net::LocalEncoder,
+    singleton::{
+        announcements::{Announcements, Audience, Screen},
+        teams::Team,
+    },
+};
+
+#[derive(Query)]
+pub struct SendAnnouncementsQuery<'a> {
+    id: EntityId,
+    team: &'a Team,
+    pose: &'a FullEntityPose,
+    screen: &'a mut Screen,
+    encoder: &'a mut LocalEncoder,
+}
+
+/// Sends the queued announcements to the players in their audience, see
+/// [`crate::singleton::announcements`].
+#[instrument(skip_all, level = "trace")]
+pub fn send_announcements(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut announcements: Single<&mut Announcements>,
+    mut players: Fetcher<SendAnnouncementsQuery>,
+) {
+    let queue = announcements.take();
+
+    if queue.is_empty() {
+        return;
+    }
+
+    // announcements for a single player go straight to them, the others are checked against every
+    // player
+    let mut shared = Vec::new();
+
+    for (audience, announcement) in &queue {
+        match *audience {
+            Audience::Player(id) => {
+                if let Ok(query) = players.get_mut(id) {
+                    query
+                        .screen
+                        .show(announcement, query.encoder, &global)
+                        .unwrap();
+                }
+            }
+            audience => shared.push((audience, announcement)),
+        }
+    }
+
+    if shared.is_empty() {
+        return;
+    }
+
+    for query in &mut players {
+        let position = query.pose.position;
+
+        for &(audience, announcement) in &shared {
+            if audience.includes(query.id, *query.team, position) {
+                query
+                    .screen
+                    .show(announcement, query.encoder, &global)
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::{
+    ident,
+    packets::play,
+    sound::{SoundCategory, SoundId},
+    ByteAngle, VarInt, Velocity,
+};
+
+use crate::{
+    components::projectile::{Projectile, ProjectileKind},
+    events::ShootProjectile,
+    global::Global,
+    singleton::broadcast::BroadcastBuf,
+};
+
+/// Spawns projectiles which players shot or threw. They are moved by
+/// [`crate::system::projectile_physics`].
+#[instrument(skip_all, level = "trace")]
+pub fn shoot_projectile(
+    r: Receiver<ShootProjectile>,
+    global: Single<&Global>,
+    mut broadcast: Single<&mut BroadcastBuf>,
+    mut s: Sender<(Spawn, Insert<Projectile>)>,
+) {
+    let event = r.event;
+
+    let projectile = Projectile {
+        weapon: event.weapon,
+        critical: event.critical,
+        ..Projectile::new(
+            event.kind,
+            event.owner,
+            event.position,
+            event.velocity,
+            global.tick,
+        )
+    };
+
+    let id = s.spawn();
+
+    let mut broadcast = broadcast.get_round_robin();
+
+    broadcast
+        .append_packet(&projectile_spawn_packet(id, &projectile))
+        .unwrap();
+
+    let sound = match event.kind {
+        ProjectileKind::Arrow => ident!("entity.arrow.shoot"),
+        ProjectileKind::Snowball => ident!("entity.snowball.throw"),
+        ProjectileKind::EnderPearl => ident!("entity.ender_pearl.throw"),
+    };
+
+    broadcast
+        .append_packet(&play::PlaySoundS2c {
+            id: SoundId::Direct {
+                id: sound.into(),
+                range: None,
+            },
+            category: SoundCategory::Player,
+            position: (event.position * 8.0).as_ivec3(),
+            volume: 0.5,
+            pitch: 0.4,
+            seed: 0,
+        })
+        .unwrap();
+
+    s.insert(id, projectile);
+}
+
+/// The packet which spawns a projectile for clients. They simulate its flight on their own until
+/// it hits something.
+pub fn projectile_spawn_packet(id: EntityId, projectile: &Projectile) -> play::EntitySpawnS2c {
+    let velocity = (projectile.velocity * 8000.0).as_i16vec3();
+
+    play::EntitySpawnS2c {
+        entity_id: VarInt(id.index().0 as i32),
+        object_uuid: projectile.uuid,
+        kind: VarInt(projectile.kind.entity_type() as i32),
+        position: projectile.position.as_dvec3(),
+        pitch: ByteAngle(0),
+        yaw: ByteAngle(0),
+        head_yaw: ByteAngle(0),
+        // clients look up the shooter by this id
+        data: VarInt(projectile.owner.index().0 as i32),
+        velocity: Velocity(velocity.to_array()),
+    }
+}
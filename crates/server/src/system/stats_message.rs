@@ -1,11 +1,18 @@
 use evenio::prelude::*;
 use tracing::instrument;
-use valence_protocol::text::IntoText;
 
-use crate::{events::StatsEvent, singleton::broadcast::BroadcastBuf};
+use crate::{
+    events::StatsEvent,
+    singleton::announcements::{Announcements, Audience, Priority, Screen},
+};
 
+/// Shows the ms per tick to the players who enabled the debug overlay with `/debug`.
 #[instrument(skip_all, level = "trace")]
-pub fn stats_message(r: Receiver<StatsEvent>, mut broadcast: Single<&mut BroadcastBuf>) {
+pub fn stats_message(
+    r: Receiver<StatsEvent>,
+    players: Fetcher<(EntityId, &Screen)>,
+    mut announcements: Single<&mut Announcements>,
+) {
     let StatsEvent {
         ms_per_tick_mean_1s,
         ms_per_tick_mean_5s,
@@ -13,9 +20,10 @@ pub fn stats_message(r: Receiver<StatsEvent>, mut broadcast: Single<&mut Broadca
     } = r.event;
 
     let message = format!("ms {ms_per_tick_mean_1s:05.2} {ms_per_tick_mean_5s:05.2}");
-    let packet = valence_protocol::packets::play::OverlayMessageS2c {
-        action_bar_text: message.into_cow_text(),
-    };
 
-    broadcast.get_round_robin().append_packet(&packet).unwrap();
+    for (id, screen) in &players {
+        if screen.debug_overlay {
+            announcements.action_bar(Audience::Player(id), message.clone(), Priority::Debug);
+        }
+    }
 }
@@ -0,0 +1,229 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use uuid::Uuid;
+use valence_protocol::{
+    ident,
+    packets::{
+        play,
+        play::entity_attributes_s2c::{AttributeModifier, AttributeProperty},
+    },
+    RawBytes, VarInt,
+};
+
+use crate::{
+    components::{
+        status_effects::{EffectKind, StatusEffects, SPEED_PER_LEVEL},
+        CombatState, Vitals,
+    },
+    events::Gametick,
+    global::Global,
+    metadata::Metadata,
+    net::LocalEncoder,
+    singleton::broadcast::BroadcastBuf,
+};
+
+/// The metadata index of the particle color of a living entity.
+const EFFECT_COLOR_INDEX: u8 = 10;
+
+/// The metadata index of whether the particles of a living entity are ambient.
+const EFFECT_AMBIENT_INDEX: u8 = 11;
+
+/// The health absorption gives per level, measured in half hearts.
+const ABSORPTION_PER_LEVEL: f32 = 4.0;
+
+/// The health instant health heals at level I. Every level doubles it.
+const INSTANT_HEALTH: f32 = 4.0;
+
+/// The base movement speed of players in blocks per tick.
+const PLAYER_SPEED: f64 = 0.1;
+
+/// The UUID of the movement speed modifier of speed in vanilla.
+const SPEED_MODIFIER: Uuid = Uuid::from_u128(0x91AE_AA56_376B_4498_935B_2F7F_6807_0635);
+
+/// The UUID of the movement speed modifier of sprinting in vanilla.
+const SPRINT_MODIFIER: Uuid = Uuid::from_u128(0x662A_6B8D_DA3E_4C1C_8813_96EA_6097_278D);
+
+/// The speed sprinting adds to the movement speed, relative to it.
+const SPRINT_BONUS: f64 = 0.3;
+
+/// The attribute modifier operation which multiplies the total value by one plus the amount.
+const MULTIPLY_TOTAL: u8 = 2;
+
+#[derive(Query)]
+pub struct StatusEffectsQuery<'a> {
+    id: EntityId,
+    effects: &'a mut StatusEffects,
+    vitals: &'a mut Vitals,
+    encoder: Option<&'a mut LocalEncoder>,
+    combat: Option<&'a CombatState>,
+}
+
+/// Applies status effects, removes them once they run out and syncs them to clients, see
+/// [`crate::components::status_effects`].
+#[instrument(skip_all, level = "trace")]
+pub fn status_effects(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut fetcher: Fetcher<StatusEffectsQuery>,
+    broadcast: Single<&mut BroadcastBuf>,
+) {
+    let tick = global.tick;
+    let mut broadcast = broadcast.0.get_round_robin();
+
+    for query in &mut fetcher {
+        let StatusEffectsQuery {
+            id,
+            effects,
+            vitals,
+            encoder,
+            combat,
+        } = query;
+
+        if matches!(vitals, Vitals::Dead { .. }) {
+            effects.clear();
+        }
+
+        for kind in effects.expire(tick) {
+            if kind == EffectKind::Absorption {
+                if let Vitals::Alive { absorption, .. } = vitals {
+                    *absorption = 0.0;
+                }
+            }
+        }
+
+        for (kind, effect) in effects.start() {
+            match kind {
+                EffectKind::InstantHealth => {
+                    let doubled = i32::from(effect.amplifier.min(16));
+                    vitals.heal(INSTANT_HEALTH * 2.0_f32.powi(doubled));
+                }
+                EffectKind::Absorption => {
+                    if let Vitals::Alive { absorption, .. } = vitals {
+                        let bonus = ABSORPTION_PER_LEVEL * effect.level() as f32;
+                        *absorption = absorption.max(bonus);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (kind, effect) in effects.iter() {
+            let Some(interval) = kind.interval(effect.amplifier) else {
+                continue;
+            };
+
+            if (effect.end_tick - tick) % interval != 0 {
+                continue;
+            }
+
+            match kind {
+                EffectKind::Regeneration => vitals.heal(1.0),
+                // poison never kills
+                EffectKind::Poison => {
+                    if let Vitals::Alive { health, .. } = vitals {
+                        *health = (*health - 1.0).max(health.min(1.0));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let changed = effects.take_changed();
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        // the entity sees its own effects as icons
+        if let Some(encoder) = encoder {
+            // clients only move faster if they are sent the speed as an attribute modifier
+            if changed.contains(&EffectKind::Speed) {
+                let sprinting = combat.is_some_and(|combat| combat.sprinting);
+                let pkt = movement_speed(effects, sprinting);
+                encoder.append(&pkt, &global).unwrap();
+            }
+
+            for kind in changed.into_iter().filter(|kind| !kind.is_instant()) {
+                // local is id 0
+                let entity_id = VarInt(0);
+                let effect_id = VarInt(kind.id());
+
+                match effects.get(kind) {
+                    Some(effect) => {
+                        let flags = play::entity_status_effect_s2c::Flags::new()
+                            .with_is_ambient(effect.ambient)
+                            .with_show_particles(effect.particles)
+                            .with_show_icon(effect.icon);
+
+                        let pkt = play::EntityStatusEffectS2c {
+                            entity_id,
+                            effect_id,
+                            amplifier: effect.amplifier,
+                            duration: VarInt(
+                                i32::try_from(effect.end_tick - tick).unwrap_or(i32::MAX),
+                            ),
+                            flags,
+                            factor_codec: None,
+                        };
+
+                        encoder.append(&pkt, &global).unwrap();
+                    }
+                    None => {
+                        let pkt = play::RemoveEntityStatusEffectS2c {
+                            entity_id,
+                            effect_id,
+                        };
+
+                        encoder.append(&pkt, &global).unwrap();
+                    }
+                }
+            }
+        }
+
+        // everyone sees the particles of the effects
+        let metadata = Metadata::default()
+            .var_int(EFFECT_COLOR_INDEX, effects.particle_color().unwrap_or(0))
+            .boolean(EFFECT_AMBIENT_INDEX, effects.is_ambient())
+            .finish();
+
+        let pkt = play::EntityTrackerUpdateS2c {
+            entity_id: VarInt(id.index().0 as i32),
+            tracked_values: RawBytes(&metadata),
+        };
+
+        broadcast.append_packet(&pkt).unwrap();
+    }
+}
+
+/// The movement speed attribute of a player with `effects`. Clients replace all modifiers of the
+/// attribute with the ones sent, so sprinting is sent as well.
+fn movement_speed(effects: &StatusEffects, sprinting: bool) -> play::EntityAttributesS2c<'static> {
+    let mut modifiers = Vec::new();
+
+    let level = effects.level(EffectKind::Speed);
+    if level > 0 {
+        modifiers.push(AttributeModifier {
+            uuid: SPEED_MODIFIER,
+            amount: f64::from(SPEED_PER_LEVEL) * f64::from(level),
+            operation: MULTIPLY_TOTAL,
+        });
+    }
+
+    if sprinting {
+        modifiers.push(AttributeModifier {
+            uuid: SPRINT_MODIFIER,
+            amount: SPRINT_BONUS,
+            operation: MULTIPLY_TOTAL,
+        });
+    }
+
+    play::EntityAttributesS2c {
+        // local is id 0
+        entity_id: VarInt(0),
+        properties: vec![AttributeProperty {
+            key: ident!("generic.movement_speed").into(),
+            value: PLAYER_SPEED,
+            modifiers,
+        }],
+    }
+}
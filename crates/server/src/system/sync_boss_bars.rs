@@ -0,0 +1,33 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    components::FullEntityPose,
+    events::Gametick,
+    global::Global,
+    net::LocalEncoder,
+    singleton::{boss_bars::BossBars, teams::Team},
+};
+
+/// Sends the boss bars which changed this tick to the players who see them, see
+/// [`crate::singleton::boss_bars`].
+#[instrument(skip_all, level = "trace")]
+pub fn sync_boss_bars(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut bars: Single<&mut BossBars>,
+    mut players: Fetcher<(EntityId, &Team, &FullEntityPose, &mut LocalEncoder)>,
+) {
+    let online: Vec<_> = players
+        .iter()
+        .map(|(id, team, pose, _)| (id, *team, pose.position))
+        .collect();
+
+    for (player, pkt) in bars.sync(&online, global.tick) {
+        let Ok((.., encoder)) = players.get_mut(player) else {
+            continue;
+        };
+
+        encoder.append(&pkt, &global).unwrap();
+    }
+}
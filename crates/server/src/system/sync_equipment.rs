@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+
+use evenio::prelude::*;
+use tracing::instrument;
+use valence_protocol::VarInt;
+
+use crate::{
+    components::inventory::Inventory, events::Gametick, packets::def::EntityEquipmentUpdateS2c,
+    singleton::broadcast::BroadcastBuf,
+};
+
+/// Shows other players the items a player holds and wears after they changed.
+#[instrument(skip_all, level = "trace")]
+pub fn sync_equipment(
+    _: Receiver<Gametick>,
+    mut inventories: Fetcher<(EntityId, &mut Inventory)>,
+    broadcast: Single<&mut BroadcastBuf>,
+) {
+    let mut broadcast = broadcast.0.get_round_robin();
+
+    for (id, inventory) in &mut inventories {
+        if !inventory.take_equipment_changed() {
+            continue;
+        }
+
+        let pkt = EntityEquipmentUpdateS2c {
+            entity_id: VarInt(id.index().0 as i32),
+            equipment: Cow::Owned(inventory.equipment()),
+        };
+
+        broadcast.append_packet(&pkt).unwrap();
+    }
+}
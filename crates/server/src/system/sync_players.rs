@@ -1,25 +1,35 @@
 use evenio::prelude::*;
 use tracing::instrument;
-use valence_protocol::{packets::play, VarInt};
+use valence_protocol::{packets::play, RawBytes, VarInt};
 use valence_text::{Color, IntoText};
 
 use crate::{
-    components::FullEntityPose, events::Gametick, global::Global, net::LocalEncoder, tracker::Prev,
+    components::{FullEntityPose, Hunger},
+    events::Gametick,
+    global::{Global, TICKS_PER_SECOND},
+    metadata::Metadata,
+    net::LocalEncoder,
+    singleton::announcements::{Announcements, Audience, Fade, Priority, Screen, Title},
+    tracker::Prev,
     Vitals,
 };
 
 const HURT_SOUND: VarInt = VarInt(1018); // represents 1019
-const REGENERATION: VarInt = VarInt(10);
-const ABSORPTION: VarInt = VarInt(22);
 const SURVIVAL: f32 = 0.0;
 const SPECTATOR: f32 = 3.0;
 
+/// The metadata index of the extra hearts of a player.
+const ADDITIONAL_HEARTS_INDEX: u8 = 15;
+
 #[derive(Query)]
 pub struct SyncPlayersQuery<'a> {
     id: EntityId,
     pose: &'a FullEntityPose,
     prev_vitals: &'a mut Prev<Vitals>,
     vitals: &'a mut Vitals,
+    prev_hunger: &'a mut Prev<Hunger>,
+    hunger: &'a Hunger,
+    screen: &'a mut Screen,
     encoder: &'a mut LocalEncoder,
 }
 
@@ -27,14 +37,17 @@ pub struct SyncPlayersQuery<'a> {
 pub fn sync_players(
     _r: Receiver<Gametick>,
     global: Single<&Global>,
+    mut announcements: Single<&mut Announcements>,
     mut fetcher: Fetcher<SyncPlayersQuery>,
 ) {
     let tick = global.tick;
 
     fetcher.iter_mut().for_each(|query| {
-        let entity_id = VarInt(query.id.index().0 as i32);
         let vitals = query.vitals;
         let encoder = query.encoder;
+        let hunger = *query.hunger;
+        let hunger_changed = hunger.differs(&**query.prev_hunger);
+        **query.prev_hunger = hunger;
 
         let mut previous = &mut **query.prev_vitals;
         let mut current = vitals;
@@ -43,57 +56,26 @@ pub fn sync_players(
             (
                 Vitals::Alive {
                     health: previous_health,
-                    regeneration: previous_regeneration,
-                    ..
+                    absorption: previous_absorption,
                 },
                 Vitals::Alive {
                     health: current_health,
-                    regeneration: current_regeneration,
-                    ..
+                    absorption: current_absorption,
                 },
             ) => {
-                if (*previous_health - *current_health).abs() > f32::EPSILON {
-                    // TODO: Sync absorption hearts
-
+                if (*previous_health - *current_health).abs() > f32::EPSILON || hunger_changed {
                     let _ = encoder.append(
                         &play::HealthUpdateS2c {
                             health: *current_health,
-                            food: VarInt(20),
-                            food_saturation: 5.0,
+                            food: VarInt(i32::from(hunger.food)),
+                            food_saturation: hunger.saturation,
                         },
                         &global,
                     );
                 }
 
-                // // TODO: Adding these effects don't work for some reason
-                // if previous_absorption.end_tick != current_absorption.end_tick {
-                //     let _ = encoder.append(
-                //         &play::EntityStatusEffectS2c {
-                //             entity_id,
-                //             effect_id: ABSORPTION,
-                //             amplifier: 0,
-                //             duration: dbg!(VarInt((current_absorption.end_tick - tick) as i32)),
-                //             flags: play::entity_status_effect_s2c::Flags::new()
-                //                 .with_show_icon(true),
-                //             factor_codec: None,
-                //         },
-                //         &global,
-                //     );
-                // }
-
-                if previous_regeneration.end_tick != current_regeneration.end_tick {
-                    let _ = encoder.append(
-                        &play::EntityStatusEffectS2c {
-                            entity_id,
-                            effect_id: REGENERATION,
-                            amplifier: 1,
-                            duration: dbg!(VarInt((current_regeneration.end_tick - tick) as i32)),
-                            flags: play::entity_status_effect_s2c::Flags::new()
-                                .with_show_icon(true),
-                            factor_codec: None,
-                        },
-                        &global,
-                    );
+                if (*previous_absorption - *current_absorption).abs() > f32::EPSILON {
+                    send_absorption(encoder, *current_absorption, &global);
                 }
             }
             (Vitals::Alive { .. }, Vitals::Dead { respawn_tick }) => {
@@ -104,24 +86,8 @@ pub fn sync_players(
                     },
                     &global,
                 );
-                // The title is repeatedly sent so it doesn't fade away after a few seconds
-                let _ = encoder.append(
-                    &play::TitleS2c {
-                        title_text: "YOU DIED!".into_text().color(Color::RED).into(),
-                    },
-                    &global,
-                );
 
-                let seconds_remaining = (*respawn_tick - tick) as f32 / 20.0;
-                let _ = encoder.append(
-                    &play::SubtitleS2c {
-                        subtitle_text: ("Respawning in ".into_text()
-                            + format!("{seconds_remaining:.2}").color(Color::RED)
-                            + " seconds")
-                            .into(),
-                    },
-                    &global,
-                );
+                announcements.title(Audience::Player(query.id), death_title(*respawn_tick, tick));
 
                 // encoder
                 //     .append(
@@ -137,8 +103,9 @@ pub fn sync_players(
                 //     )
                 //     .unwrap();
             }
-            (Vitals::Dead { .. }, Vitals::Alive { health, .. }) => {
+            (Vitals::Dead { .. }, Vitals::Alive { health, absorption }) => {
                 let _ = encoder.append(&play::ClearTitleS2c { reset: true }, &global);
+                query.screen.clear_title();
                 let _ = encoder.append(
                     &play::GameStateChangeS2c {
                         kind: play::game_state_change_s2c::GameEventKind::ChangeGameMode,
@@ -149,27 +116,60 @@ pub fn sync_players(
                 let _ = encoder.append(
                     &play::HealthUpdateS2c {
                         health: *health,
-                        food: VarInt(20),
-                        food_saturation: 5.0,
+                        food: VarInt(i32::from(hunger.food)),
+                        food_saturation: hunger.saturation,
                     },
                     &global,
                 );
-                // TODO: Update absorption and regeneration
+                send_absorption(encoder, *absorption, &global);
             }
             (Vitals::Dead { .. }, Vitals::Dead { respawn_tick }) => {
-                let seconds_remaining = (*respawn_tick - tick) as f32 / 20.0;
-                let _ = encoder.append(
-                    &play::SubtitleS2c {
-                        subtitle_text: ("Respawning in ".into_text()
-                            + format!("{seconds_remaining:.2}").color(Color::RED)
-                            + " seconds")
-                            .into(),
-                    },
-                    &global,
-                );
+                let remaining = *respawn_tick - tick;
+
+                // the countdown only changes once per second
+                if remaining > 0 && remaining % TICKS_PER_SECOND == 0 {
+                    announcements
+                        .title(Audience::Player(query.id), death_title(*respawn_tick, tick));
+                }
             }
         }
 
         *previous = *current;
     });
 }
+
+/// The title dead players see until they respawn at `respawn_tick`, counting down the seconds.
+fn death_title(respawn_tick: i64, tick: i64) -> Title {
+    let remaining = respawn_tick - tick;
+    let seconds = (remaining + TICKS_PER_SECOND - 1) / TICKS_PER_SECOND;
+    let subtitle = "Respawning in ".into_text()
+        + seconds.to_string().color(Color::RED)
+        + if seconds == 1 { " second" } else { " seconds" };
+
+    // the title stays until the player respawns
+    let fade = Fade {
+        fade_in: 0,
+        stay: i32::try_from(remaining).unwrap_or(i32::MAX),
+        fade_out: 0,
+    };
+
+    Title::new("YOU DIED!".into_text().color(Color::RED))
+        .subtitle(subtitle)
+        .fade(fade)
+        .priority(Priority::Critical)
+}
+
+/// Shows a player their extra hearts from absorption.
+fn send_absorption(encoder: &mut LocalEncoder, absorption: f32, global: &Global) {
+    let metadata = Metadata::default()
+        .float(ADDITIONAL_HEARTS_INDEX, absorption)
+        .finish();
+
+    // local is id 0
+    let pkt = play::EntityTrackerUpdateS2c {
+        entity_id: VarInt(0),
+        tracked_values: RawBytes(&metadata),
+    };
+
+    let _ = encoder.append(&pkt, global);
+}
@@ -0,0 +1,83 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    components::{InGameName, Vitals},
+    events::Gametick,
+    global::Global,
+    net::LocalEncoder,
+    singleton::{
+        broadcast::BroadcastBuf,
+        scoreboard::{
+            score_packet, PlayerScore, Scoreboard, DEATHS_LINE, KILLS_LINE, PLAYERS_LEFT_LINE,
+            SIDEBAR,
+        },
+    },
+    tracker::Prev,
+};
+
+#[derive(Query)]
+pub struct SyncScoreboardQuery<'a> {
+    name: &'a InGameName,
+    vitals: &'a Vitals,
+    score: &'a PlayerScore,
+    prev_score: &'a mut Prev<PlayerScore>,
+    encoder: &'a mut LocalEncoder,
+}
+
+/// Updates the scores shown to players and sends the scores which changed this tick, see
+/// [`crate::singleton::scoreboard`].
+#[instrument(skip_all, level = "trace")]
+pub fn sync_scoreboard(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut scoreboard: Single<&mut Scoreboard>,
+    mut players: Fetcher<SyncScoreboardQuery>,
+    broadcast: Single<&mut BroadcastBuf>,
+) {
+    let Scoreboard { sidebar, health } = &mut **scoreboard;
+
+    let mut alive = 0;
+
+    for query in &mut players {
+        let hearts = match query.vitals {
+            Vitals::Alive {
+                health: current, ..
+            } => {
+                alive += 1;
+                current.ceil() as i32
+            }
+            Vitals::Dead { .. } => 0,
+        };
+
+        health.set(query.name, hearts);
+
+        // the kills and deaths of a player are only sent to them
+        let score = *query.score;
+        let prev = **query.prev_score;
+        **query.prev_score = score;
+
+        let lines = [
+            (KILLS_LINE, score.kills, prev.kills),
+            (DEATHS_LINE, score.deaths, prev.deaths),
+        ];
+
+        for (line, score, prev) in lines {
+            if score != prev {
+                let pkt = score_packet(SIDEBAR, line, Some(score));
+                query.encoder.append(&pkt, &global).unwrap();
+            }
+        }
+    }
+
+    sidebar.set(PLAYERS_LEFT_LINE, alive);
+
+    let mut broadcast = broadcast.0.get_round_robin();
+
+    for objective in [sidebar, health] {
+        for (holder, score) in objective.take_changes() {
+            let pkt = score_packet(objective.name(), &holder, score);
+            broadcast.append_packet(&pkt).unwrap();
+        }
+    }
+}
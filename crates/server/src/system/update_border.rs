@@ -0,0 +1,161 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use uuid::Uuid;
+use valence_protocol::{
+    packets::play::{self, boss_bar_s2c::BossBarColor},
+    text::{Color, IntoText},
+    VarInt,
+};
+
+use crate::{
+    combat::Defense,
+    components::{
+        damage::{DamageCause, DamageTracker},
+        FullEntityPose, Hurt, ImmuneStatus, Player, Uuid, Vitals,
+    },
+    events::{Gametick, PlayerKilled},
+    global::{Global, TICKS_PER_SECOND},
+    net::LocalEncoder,
+    singleton::{
+        border::WorldBorder,
+        boss_bars::{BossBar, BossBars},
+        broadcast::BroadcastBuf,
+        phase::PhaseController,
+        registries::Registries,
+        stats::MatchStats,
+    },
+};
+
+/// The id of the boss bar which warns players of the shrinking border.
+pub const BORDER_BAR: Uuid = Uuid::from_u128(0x6879_7065_7269_6f6e_0000_0000_0000_0004);
+
+/// The number of ticks before the border shrinks at which players are warned.
+const WARNING_TICKS: i64 = 200;
+
+#[derive(Query)]
+pub struct UpdateBorderQuery<'a> {
+    id: EntityId,
+    uuid: &'a Uuid,
+    pose: &'a FullEntityPose,
+    vitals: &'a mut Vitals,
+    immunity: &'a mut ImmuneStatus,
+    tracker: &'a mut DamageTracker,
+    encoder: &'a mut LocalEncoder,
+    _player: With<&'static Player>,
+}
+
+/// Starts the stages of the border when they are due and, during the battle, damages players
+/// outside of it, see [`crate::singleton::border`].
+#[instrument(skip_all, level = "trace")]
+pub fn update_border(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    registries: Single<&Registries>,
+    mut border: Single<&mut WorldBorder>,
+    phase: Single<&PhaseController>,
+    mut players: Fetcher<UpdateBorderQuery>,
+    mut bars: Single<&mut BossBars>,
+    mut broadcast: Single<&mut BroadcastBuf>,
+    mut stats: Single<&mut MatchStats>,
+    mut s: Sender<PlayerKilled>,
+) {
+    let tick = global.tick;
+    let mut broadcast = broadcast.get_round_robin();
+
+    if border.update(tick) {
+        let (center, size) = border.update_packets(tick);
+        broadcast.append_packet(&center).unwrap();
+        broadcast.append_packet(&size).unwrap();
+    }
+
+    warn(&border, &mut bars, tick);
+
+    if !phase.phase().pvp() {
+        return;
+    }
+
+    let source_type_id = VarInt(
+        registries
+            .damage_type_id(DamageCause::OutsideBorder.damage_type())
+            .unwrap_or_default(),
+    );
+
+    for query in &mut players {
+        let Some(damage) = border.damage(query.pose.position.as_dvec3(), tick) else {
+            continue;
+        };
+
+        let hit_points = query.vitals.hit_points();
+
+        // like vanilla, the border ignores armor
+        let hurt = query
+            .vitals
+            .hurt(&global, damage, &Defense::NONE, query.immunity);
+
+        if hurt == Hurt::Immune {
+            continue;
+        }
+
+        stats.damage(
+            None,
+            Some(query.uuid.0),
+            hit_points - query.vitals.hit_points(),
+        );
+
+        // whoever hit the player last is credited
+        let cause = DamageCause::OutsideBorder;
+
+        if let Some(killed) = query
+            .tracker
+            .hurt(query.id, query.vitals, cause, None, tick)
+        {
+            s.send(killed);
+        }
+
+        if hurt != Hurt::Full {
+            continue;
+        }
+
+        let mut pkt = play::EntityDamageS2c {
+            entity_id: VarInt(query.id.index().0 as i32),
+            source_type_id,
+            source_cause_id: VarInt::default(),
+            source_direct_id: VarInt::default(),
+            source_pos: None,
+        };
+
+        broadcast.append_packet(&pkt).unwrap();
+
+        // local is id 0
+        pkt.entity_id = VarInt(0);
+        query.encoder.append(&pkt, &global).unwrap();
+    }
+}
+
+/// Shows how long until the border shrinks, or how far it shrank, in a boss bar.
+fn warn(border: &WorldBorder, bars: &mut BossBars, tick: i64) {
+    if let Some(progress) = border.shrink_progress(tick) {
+        let bar = BossBar::new("The border is shrinking".into_text().color(Color::RED))
+            .progress(1.0 - progress as f32)
+            .color(BossBarColor::Red);
+
+        bars.show(BORDER_BAR, bar);
+        return;
+    }
+
+    match border.next_stage_in(tick) {
+        Some(remaining) if remaining <= WARNING_TICKS => {
+            let seconds = (remaining + TICKS_PER_SECOND - 1) / TICKS_PER_SECOND;
+            let title = format!("The border shrinks in {seconds}s")
+                .into_text()
+                .color(Color::YELLOW);
+
+            let bar = BossBar::new(title)
+                .progress(remaining as f32 / WARNING_TICKS as f32)
+                .color(BossBarColor::Yellow);
+
+            bars.show(BORDER_BAR, bar);
+        }
+        _ => bars.hide(BORDER_BAR),
+    }
+}
@@ -1,61 +1,158 @@
 use evenio::prelude::*;
 use tracing::instrument;
+use valence_protocol::packets::{play, play::player_position_look_s2c::PlayerPositionLookFlags};
 
 use crate::{
-    components::vitals::{Absorption, Regeneration},
+    components::{
+        inventory::Inventory, status_effects::StatusEffects, CombatState, FullEntityPose, Hunger,
+        ImmuneStatus, Player, Teleport,
+    },
+    config,
     events::Gametick,
     global::Global,
+    net::LocalEncoder,
+    singleton::{
+        blocks::Blocks,
+        kits::{GivenKit, Kits},
+        phase::{Phase, PhaseController},
+        registries::Registries,
+        spawns::Spawns,
+        teams::{Team, Teams},
+    },
+    system::player_join_world::respawn_packet,
     tracker::Prev,
     Vitals,
 };
 
-/// Interval to regenerate half a heart from having a full hunger bar measured in ticks. All players
-/// are assumed to have a full hunger bar with no saturation.
-const HUNGER_INTERVAL: i64 = 80;
-
-/// Interval to regenerate half a heart from the regeneration potion effect measured in ticks. This
-/// assumes that all regeneration is at level 2, which is true since only golden apples are used.
-const REGENERATION_INTERVAL: i64 = 25;
+/// Interval to regenerate half a heart measured in ticks when hunger is not simulated.
+const REGENERATION_INTERVAL: i64 = 80;
 
 #[derive(Query)]
 pub struct UpdateHealthQuery<'a> {
     vitals: &'a mut Vitals,
+    inventory: &'a mut Inventory,
+    hunger: &'a mut Hunger,
+    effects: &'a mut StatusEffects,
+    given_kit: &'a GivenKit,
+    pose: &'a mut FullEntityPose,
+    combat: &'a mut CombatState,
+    immunity: &'a mut ImmuneStatus,
+    teleport: &'a mut Teleport,
+    team: &'a Team,
+    encoder: &'a mut LocalEncoder,
+    _player: With<&'static Player>,
 }
 
+/// Regenerates and starves players, see [`crate::food`], and respawns dead players once their
+/// respawn delay is over.
 #[instrument(skip_all)]
 pub fn update_health(
     _: Receiver<Gametick>,
     global: Single<&Global>,
+    kits: Single<&Kits>,
+    spawns: Single<&Spawns>,
+    blocks: Single<&Blocks>,
+    teams: Single<&Teams>,
+    registries: Single<&Registries>,
+    phase: Single<&PhaseController>,
     mut fetcher: Fetcher<UpdateHealthQuery>,
 ) {
     let tick = global.tick;
-    let hunger = tick % HUNGER_INTERVAL == 0;
-    // let regeneration = tick % REGENERATION_INTERVAL == 0;
+    let rules = config::CONFIG.combat.rules();
 
-    fetcher.iter_mut().for_each(|query| {
-        let vitals = query.vitals;
+    fetcher.iter_mut().for_each(|mut query| {
+        let vitals = &mut *query.vitals;
         match vitals {
-            &mut Vitals::Alive { regeneration, .. } => {
-                if hunger {
-                    vitals.heal(1.0);
+            Vitals::Alive { health, .. } => {
+                if !rules.hunger {
+                    if tick % REGENERATION_INTERVAL == 0 {
+                        vitals.heal(1.0);
+                    }
+                    return;
                 }
 
-                if tick < regeneration.end_tick {
-                    vitals.heal(1.0);
+                let change = query.hunger.update(rules, *health);
+
+                if change > 0.0 {
+                    vitals.heal(change);
+                } else {
+                    *health += change;
                 }
             }
             Vitals::Dead { respawn_tick } => {
-                if tick == *respawn_tick {
-                    // TODO: This code is really bad
-
-                    // TODO: Teleport player to spawn location after respawning
-                    *vitals = Vitals::Alive {
-                        health: 20.0,
-                        absorption: Absorption::default(),
-                        regeneration: Regeneration::default(),
-                    }
+                // deaths are applied after this system ran, so the respawn tick may have passed
+                if tick >= *respawn_tick {
+                    let phase = phase.phase();
+                    respawn(
+                        &mut query,
+                        &global,
+                        &kits,
+                        &spawns,
+                        &blocks,
+                        &teams,
+                        &registries,
+                        phase,
+                    );
                 }
             }
         }
     });
 }
+
+/// Revives a player at a spawn for `phase`, see [`crate::singleton::spawns`], and gives them their
+/// kit.
+pub fn respawn(
+    query: &mut UpdateHealthQuery,
+    global: &Global,
+    kits: &Kits,
+    spawns: &Spawns,
+    blocks: &Blocks,
+    teams: &Teams,
+    registries: &Registries,
+    phase: Phase,
+) {
+    let tick = global.tick;
+    let team = teams.name(*query.team);
+
+    if matches!(query.vitals, Vitals::Dead { .. }) {
+        // the client forgets its position and inventory when it respawns, so they are sent below
+        let pkt = respawn_packet(registries).unwrap();
+        query.encoder.append(&pkt, global).unwrap();
+    }
+
+    *query.vitals = Vitals::ALIVE;
+    *query.hunger = Hunger::default();
+    query.effects.clear();
+    query.combat.fall_distance = 0.0;
+    query
+        .immunity
+        .protect(tick, config::CONFIG.spawn_protection);
+
+    let position = spawns.spawn_for(team, phase, blocks, &mut rand::thread_rng());
+    query.pose.move_to(position);
+
+    let pkt = play::PlayerPositionLookS2c {
+        position: position.as_dvec3(),
+        yaw: query.pose.yaw,
+        pitch: query.pose.pitch,
+        flags: PlayerPositionLookFlags::default(),
+        teleport_id: query.teleport.start().into(),
+    };
+
+    query.encoder.append(&pkt, global).unwrap();
+
+    if let Some(kit) = kits.kit_for(query.given_kit, team) {
+        kit.apply(&mut *query.inventory, query.effects, tick);
+    }
+
+    let selected = play::UpdateSelectedSlotS2c {
+        slot: query.inventory.selected(),
+    };
+
+    query.encoder.append(&selected, global).unwrap();
+
+    query
+        .encoder
+        .append(&query.inventory.sync_packet(), global)
+        .unwrap();
+}
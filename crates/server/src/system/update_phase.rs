@@ -0,0 +1,135 @@
+use evenio::prelude::*;
+use tracing::instrument;
+use uuid::Uuid;
+use valence_protocol::{
+    packets::play::boss_bar_s2c::BossBarColor,
+    text::{Color, IntoText},
+};
+
+use crate::{
+    components::{Player, Vitals},
+    config,
+    events::{Gametick, PhaseChanged},
+    global::{Global, TICKS_PER_SECOND},
+    singleton::{
+        announcements::{Announcements, Audience, Fade, Priority, Title},
+        boss_bars::{BossBar, BossBars},
+        phase::{Phase, PhaseController},
+    },
+};
+
+/// The id of the boss bar shown during the countdown.
+pub const COUNTDOWN_BAR: Uuid = Uuid::from_u128(0x6879_7065_7269_6f6e_0000_0000_0000_0001);
+
+/// The id of the boss bar with the time left in the battle.
+pub const ROUND_TIMER_BAR: Uuid = Uuid::from_u128(0x6879_7065_7269_6f6e_0000_0000_0000_0002);
+
+/// The id of the boss bar with the number of players who are alive in the battle.
+pub const PLAYERS_LEFT_BAR: Uuid = Uuid::from_u128(0x6879_7065_7269_6f6e_0000_0000_0000_0003);
+
+/// Switches the phase once its trigger fires, see [`crate::singleton::phase`], announces the
+/// number of players in the lobby and the seconds left in the countdown, and shows the boss bars of
+/// the countdown and the battle.
+#[instrument(skip_all, level = "trace")]
+pub fn update_phase(
+    _: Receiver<Gametick>,
+    global: Single<&Global>,
+    mut controller: Single<&mut PhaseController>,
+    players: Fetcher<(&Vitals, With<&Player>)>,
+    mut bars: Single<&mut BossBars>,
+    mut announcements: Single<&mut Announcements>,
+    mut s: Sender<PhaseChanged>,
+) {
+    let tick = global.tick;
+    let online = players.iter().count();
+    let needed = config::CONFIG.lobby_players;
+
+    let joined_or_left = controller.count_players(online);
+
+    if let Some(next) = controller.next(tick, online, &config::CONFIG) {
+        let from = controller.switch(next, tick);
+        s.send(PhaseChanged { from, to: next });
+        return;
+    }
+
+    let phase = controller.phase();
+    let remaining = controller.remaining(tick, &config::CONFIG);
+
+    if phase != Phase::Countdown {
+        bars.hide(COUNTDOWN_BAR);
+    }
+
+    if phase != Phase::Battle {
+        bars.hide(ROUND_TIMER_BAR);
+        bars.hide(PLAYERS_LEFT_BAR);
+    }
+
+    match phase {
+        Phase::Lobby if joined_or_left => {
+            let message = format!("Waiting for players ({online}/{needed})");
+            announcements.chat(Audience::Everyone, message.into_text().color(Color::GRAY));
+        }
+        Phase::Countdown => {
+            let Some(remaining) = remaining else {
+                return;
+            };
+
+            let bar = BossBar::new("The battle starts soon")
+                .progress(fraction(remaining, config::CONFIG.countdown_ticks))
+                .color(BossBarColor::Yellow);
+            bars.show(COUNTDOWN_BAR, bar);
+
+            if remaining % TICKS_PER_SECOND != 0 {
+                return;
+            }
+
+            // each second replaces the last one without fading
+            let fade = Fade {
+                fade_in: 0,
+                stay: TICKS_PER_SECOND as i32,
+                fade_out: 5,
+            };
+
+            let seconds = remaining / TICKS_PER_SECOND;
+            let title = Title::new(seconds.to_string().into_text().color(Color::GOLD))
+                .fade(fade)
+                .priority(Priority::Important);
+
+            announcements.title(Audience::Everyone, title);
+        }
+        Phase::Battle => {
+            let alive = players
+                .iter()
+                .filter(|(vitals, _)| matches!(vitals, Vitals::Alive { .. }))
+                .count();
+
+            let bar = BossBar::new(format!("{alive} players left"))
+                .progress(fraction(alive as i64, online as i64))
+                .color(BossBarColor::Green);
+            bars.show(PLAYERS_LEFT_BAR, bar);
+
+            let (Some(remaining), Some(duration)) = (remaining, config::CONFIG.battle_ticks) else {
+                bars.hide(ROUND_TIMER_BAR);
+                return;
+            };
+
+            let seconds = (remaining + TICKS_PER_SECOND - 1) / TICKS_PER_SECOND;
+            let title = format!("Time left {}:{:02}", seconds / 60, seconds % 60);
+
+            let bar = BossBar::new(title)
+                .progress(fraction(remaining, duration))
+                .color(BossBarColor::Blue);
+            bars.show(ROUND_TIMER_BAR, bar);
+        }
+        _ => {}
+    }
+}
+
+/// The fraction `part / whole` for the progress of a boss bar, `0.0` if `whole` is not positive.
+fn fraction(part: i64, whole: i64) -> f32 {
+    if whole <= 0 {
+        return 0.0;
+    }
+
+    part as f32 / whole as f32
+}
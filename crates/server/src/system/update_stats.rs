@@ -0,0 +1,31 @@
+use evenio::prelude::*;
+use tracing::instrument;
+
+use crate::{
+    components::{InGameName, Player, Uuid, Vitals},
+    events::Gametick,
+    singleton::{
+        phase::{Phase, PhaseController},
+        stats::MatchStats,
+    },
+};
+
+/// Remembers the names of players and counts the ticks they are alive in the battle, see
+/// [`crate::singleton::stats`].
+#[instrument(skip_all, level = "trace")]
+pub fn update_stats(
+    _: Receiver<Gametick>,
+    phase: Single<&PhaseController>,
+    players: Fetcher<(&Uuid, &InGameName, &Vitals, With<&Player>)>,
+    mut stats: Single<&mut MatchStats>,
+) {
+    let battle = phase.phase() == Phase::Battle;
+
+    for (uuid, name, vitals, _) in &players {
+        stats.name(uuid.0, name);
+
+        if battle && matches!(vitals, Vitals::Alive { .. }) {
+            stats.alive(uuid.0);
+        }
+    }
+}